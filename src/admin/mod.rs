@@ -1,12 +1,21 @@
 use crate::*;
 use metriken::Value;
 use ratelimit::Ratelimiter;
+use std::collections::HashMap;
 use std::net::ToSocketAddrs;
 use std::sync::Arc;
 
+/// The ratelimiters for workload components which have their own ratelimit,
+/// by component name.
+pub type ComponentRatelimits = Arc<HashMap<String, Arc<Ratelimiter>>>;
+
 /// The HTTP admin server.
-pub async fn http(config: Config, ratelimit: Option<Arc<Ratelimiter>>) {
-    let admin = filters::admin(ratelimit);
+pub async fn http(
+    config: Config,
+    ratelimit: Option<Arc<Ratelimiter>>,
+    component_ratelimits: ComponentRatelimits,
+) {
+    let admin = filters::admin(ratelimit, component_ratelimits);
 
     let addr = config
         .general()
@@ -26,11 +35,13 @@ mod filters {
     /// The combined set of admin endpoint filters
     pub fn admin(
         ratelimit: Option<Arc<Ratelimiter>>,
+        component_ratelimits: ComponentRatelimits,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         prometheus_stats()
             .or(human_stats())
             .or(json_stats())
             .or(update_ratelimit(ratelimit))
+            .or(update_component_ratelimit(component_ratelimits))
             .or(quitquitquit())
     }
 
@@ -77,6 +88,7 @@ mod filters {
     // TODO(bmartin): we should probably pass the rate in the body
 
     /// An endpoint that allows realtime adjustment of the workload ratelimit.
+    /// The rate must be greater than zero.
     ///
     /// PUT /ratelimit/:rate
     pub fn update_ratelimit(
@@ -95,6 +107,20 @@ mod filters {
         warp::any().map(move || ratelimit.clone())
     }
 
+    /// An endpoint that allows realtime adjustment of the ratelimit for a
+    /// workload component which has its own ratelimit. The rate must be
+    /// greater than zero.
+    ///
+    /// PUT /ratelimit/:name/:rate
+    pub fn update_component_ratelimit(
+        ratelimits: ComponentRatelimits,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("ratelimit" / String / u64)
+            .and(warp::put())
+            .and(warp::any().map(move || ratelimits.clone()))
+            .and_then(handlers::update_component_ratelimit)
+    }
+
     /// An endpoint that allows early termination of the test.
     ///
     /// POST /quitquitquit
@@ -145,40 +171,64 @@ pub mod handlers {
                 continue;
             }
 
-            let name = metric.name();
+            // metrics which belong to a family, such as the per-topic metrics,
+            // are exposed with the family name and their other metadata as
+            // labels
+            let metadata = metric.metadata();
+            let (name, labels) = match metadata.get("metric") {
+                Some(family) => {
+                    let mut labels: Vec<String> = metadata
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .filter(|(k, _)| k != "metric")
+                        .map(|(k, v)| format!("{k}=\"{v}\""))
+                        .collect();
+                    labels.sort();
+                    (family.to_string(), labels)
+                }
+                None => (metric.name().to_string(), Vec::new()),
+            };
+
+            let series = if labels.is_empty() {
+                name.clone()
+            } else {
+                format!("{name}{{{}}}", labels.join(","))
+            };
 
             match metric.value() {
                 Some(Value::Counter(value)) => {
                     if let Some(description) = metric.description() {
                         data.push(format!(
-                            "# TYPE {name} counter\n# HELP {name} {description}\n{name} {value}"
+                            "# TYPE {name} counter\n# HELP {name} {description}\n{series} {value}"
                         ));
                     } else {
-                        data.push(format!("# TYPE {name} counter\n{name} {value}"));
+                        data.push(format!("# TYPE {name} counter\n{series} {value}"));
                     }
                 }
                 Some(Value::Gauge(value)) => {
                     if let Some(description) = metric.description() {
                         data.push(format!(
-                            "# TYPE {name} gauge\n# HELP {name} {description}\n{name} {value}"
+                            "# TYPE {name} gauge\n# HELP {name} {description}\n{series} {value}"
                         ));
                     } else {
-                        data.push(format!("# TYPE {name} gauge\n{name} {value}"));
+                        data.push(format!("# TYPE {name} gauge\n{series} {value}"));
                     }
                 }
                 Some(Value::Other(_)) => {
                     let percentiles = metrics_snapshot.percentiles(metric.name());
 
                     for (_label, percentile, value) in percentiles {
+                        let mut labels = labels.clone();
+                        labels.push(format!("percentile=\"{:02}\"", percentile));
+                        let labels = labels.join(",");
+
                         if let Some(description) = metric.description() {
                             data.push(format!(
-                                "# TYPE {name} gauge\n# HELP {name} {description}\n{name}{{percentile=\"{:02}\"}} {value} {timestamp}",
-                                percentile,
+                                "# TYPE {name} gauge\n# HELP {name} {description}\n{name}{{{labels}}} {value} {timestamp}",
                             ));
                         } else {
                             data.push(format!(
-                                "# TYPE {name} gauge\n{name}{{percentile=\"{:02}\"}} {value} {timestamp}",
-                                percentile,
+                                "# TYPE {name} gauge\n{name}{{{labels}}} {value} {timestamp}",
                             ));
                         }
                     }
@@ -232,23 +282,32 @@ pub mod handlers {
         rate: u64,
         ratelimit: Option<Arc<Ratelimiter>>,
     ) -> Result<impl warp::Reply, Infallible> {
+        if rate == 0 {
+            return Ok(StatusCode::BAD_REQUEST);
+        }
+
         if let Some(r) = ratelimit {
-            let amount = (rate as f64 / 1_000_000.0).ceil() as u64;
             RATELIMIT_CURR.set(rate as i64);
+            set_ratelimit(&r, rate);
 
-            // even though we might not have nanosecond level clock resolution,
-            // by using a nanosecond level duration, we achieve more accurate
-            // ratelimits.
-            let interval = Duration::from_nanos(1_000_000_000 / (rate / amount));
+            Ok(StatusCode::OK)
+        } else {
+            Ok(StatusCode::NOT_FOUND)
+        }
+    }
 
-            let capacity = std::cmp::max(100, amount);
+    pub async fn update_component_ratelimit(
+        name: String,
+        rate: u64,
+        ratelimits: ComponentRatelimits,
+    ) -> Result<impl warp::Reply, Infallible> {
+        // a rate of zero can not be converted into a refill interval
+        if rate == 0 {
+            return Ok(StatusCode::BAD_REQUEST);
+        }
 
-            r.set_max_tokens(capacity)
-                .expect("failed to set max tokens");
-            r.set_refill_interval(interval)
-                .expect("failed to set refill interval");
-            r.set_refill_amount(amount)
-                .expect("failed to set refill amount");
+        if let Some(r) = ratelimits.get(&name) {
+            set_ratelimit(r, rate);
 
             Ok(StatusCode::OK)
         } else {
@@ -256,6 +315,24 @@ pub mod handlers {
         }
     }
 
+    fn set_ratelimit(r: &Ratelimiter, rate: u64) {
+        let amount = (rate as f64 / 1_000_000.0).ceil() as u64;
+
+        // even though we might not have nanosecond level clock resolution,
+        // by using a nanosecond level duration, we achieve more accurate
+        // ratelimits.
+        let interval = Duration::from_nanos(1_000_000_000 / (rate / amount));
+
+        let capacity = std::cmp::max(100, amount);
+
+        r.set_max_tokens(capacity)
+            .expect("failed to set max tokens");
+        r.set_refill_interval(interval)
+            .expect("failed to set refill interval");
+        r.set_refill_amount(amount)
+            .expect("failed to set refill amount");
+    }
+
     /// A handler which sets the running state to false and returns an empty
     /// response.
     pub async fn quitquitquit() -> Result<impl warp::Reply, Infallible> {
@@ -300,3 +377,37 @@ pub async fn human_formatted_stats() -> Vec<String> {
 
     data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use warp::http::StatusCode;
+    use warp::Reply;
+
+    #[tokio::test]
+    async fn update_component_ratelimit() {
+        let ratelimiter = Arc::new(
+            Ratelimiter::builder(1, Duration::from_millis(1))
+                .max_tokens(100)
+                .build()
+                .unwrap(),
+        );
+
+        let ratelimits: ComponentRatelimits =
+            Arc::new(HashMap::from([("cache".to_string(), ratelimiter.clone())]));
+
+        let status = |reply: Result<_, Infallible>| reply.unwrap().into_response().status();
+
+        // a rate of zero is rejected instead of panicking
+        let reply = handlers::update_component_ratelimit("cache".into(), 0, ratelimits.clone());
+        assert_eq!(status(reply.await), StatusCode::BAD_REQUEST);
+
+        let reply = handlers::update_component_ratelimit("other".into(), 1000, ratelimits.clone());
+        assert_eq!(status(reply.await), StatusCode::NOT_FOUND);
+
+        let reply = handlers::update_component_ratelimit("cache".into(), 2000, ratelimits);
+        assert_eq!(status(reply.await), StatusCode::OK);
+        assert_eq!(ratelimiter.refill_interval(), Duration::from_micros(500));
+    }
+}
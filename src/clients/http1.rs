@@ -5,6 +5,12 @@ use http_body_util::Empty;
 use hyper::header::{HeaderName, HeaderValue};
 use hyper::{Request, Uri};
 
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[Verb::Get],
+    multi_get: false,
+};
+
 /// Launch tasks with one conncetion per task as http/1.1 is not mux'd
 pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
     debug!("launching http1 protocol tasks");
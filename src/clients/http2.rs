@@ -32,6 +32,12 @@ impl<T> Queue<T> {
 
 // launch a pool manager and worker tasks since HTTP/2.0 is mux'ed we prepare
 // senders in the pool manager and pass them over a queue to our worker tasks
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[Verb::Get],
+    multi_get: false,
+};
+
 pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
     debug!("launching http2 protocol tasks");
 
@@ -9,10 +9,13 @@ impl From<&workload::client::Add> for RequestWithValidator {
                 (*other.key).to_owned().into_boxed_slice(),
                 (*other.value).to_owned().into_boxed_slice(),
                 0,
-                Ttl::none(),
+                ttl(other.ttl),
                 false,
-            ),
+            )
+            .into(),
             validator: Box::new(validate_response),
+            timeout: &ADD_TIMEOUT,
+            followup: None,
         }
     }
 }
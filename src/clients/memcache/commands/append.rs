@@ -0,0 +1,40 @@
+/// Appends data to the value for a key in the cache. The key must already exist.
+use super::*;
+
+impl From<&workload::client::Append> for RequestWithValidator {
+    fn from(other: &workload::client::Append) -> Self {
+        APPEND.increment();
+        RequestWithValidator {
+            request: Request::append(
+                (*other.key).to_owned().into_boxed_slice(),
+                (*other.value).to_owned().into_boxed_slice(),
+                0,
+                Ttl::none(),
+                false,
+            )
+            .into(),
+            validator: Box::new(validate_response),
+            timeout: &APPEND_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Stored(_) => {
+            APPEND_STORED.increment();
+            APPEND_OK.increment();
+            Ok(())
+        }
+        Response::NotStored(_) => {
+            APPEND_NOT_STORED.increment();
+            APPEND_OK.increment();
+            Ok(())
+        }
+        _ => {
+            APPEND_EX.increment();
+            Err(())
+        }
+    }
+}
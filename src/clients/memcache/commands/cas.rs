@@ -0,0 +1,61 @@
+/// Read the CAS token for a key with a `gets` and then set the value for the
+/// key using the token. The `cas` is only sent if the `gets` is a hit.
+use super::*;
+
+impl From<&workload::client::Cas> for RequestWithValidator {
+    fn from(other: &workload::client::Cas) -> Self {
+        GETS.increment();
+
+        let key: Box<[u8]> = (*other.key).to_owned().into_boxed_slice();
+        let value: Box<[u8]> = other.value.clone().into_boxed_slice();
+        let ttl = ttl(other.ttl);
+
+        RequestWithValidator {
+            request: Request::gets(vec![key.clone()].into_boxed_slice()).into(),
+            validator: Box::new(gets::validate_response),
+            timeout: &GETS_TIMEOUT,
+            followup: Some(Box::new(move |response| {
+                let token = match response {
+                    Response::Values(values) => values.values().first()?.cas()?,
+                    _ => {
+                        return None;
+                    }
+                };
+
+                CAS.increment();
+                Some(RequestWithValidator {
+                    request: Request::cas(key.clone(), value.clone(), 0, ttl, token, false).into(),
+                    validator: Box::new(validate_response),
+                    timeout: &CAS_TIMEOUT,
+                    followup: None,
+                })
+            })),
+        }
+    }
+}
+
+/// A conflict with another update to the key is counted separately, but is not
+/// an error.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Stored(_) => {
+            CAS_STORED.increment();
+            CAS_OK.increment();
+            Ok(())
+        }
+        Response::Exists(_) => {
+            CAS_EXISTS.increment();
+            CAS_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            CAS_NOT_FOUND.increment();
+            CAS_OK.increment();
+            Ok(())
+        }
+        _ => {
+            CAS_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/// Decrements the numeric value for a key in the cache. The key must already exist.
+use super::*;
+
+impl From<&workload::client::Decrement> for RequestWithValidator {
+    fn from(other: &workload::client::Decrement) -> Self {
+        DECR.increment();
+        RequestWithValidator {
+            request: Request::decr(
+                (*other.key).to_owned().into_boxed_slice(),
+                other.amount,
+                false,
+            )
+            .into(),
+            validator: Box::new(validate_response),
+            timeout: &DECR_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Numeric(_) => {
+            DECR_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            DECR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            DECR_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -5,8 +5,10 @@ impl From<&workload::client::Delete> for RequestWithValidator {
     fn from(other: &workload::client::Delete) -> Self {
         DELETE.increment();
         RequestWithValidator {
-            request: Request::delete((*other.key).to_owned().into_boxed_slice(), false),
+            request: Request::delete((*other.key).to_owned().into_boxed_slice(), false).into(),
             validator: Box::new(validate_response),
+            timeout: &DELETE_TIMEOUT,
+            followup: None,
         }
     }
 }
@@ -7,8 +7,11 @@ impl From<&workload::client::Get> for RequestWithValidator {
         RequestWithValidator {
             request: Request::get(
                 vec![(*other.key).to_owned().into_boxed_slice()].into_boxed_slice(),
-            ),
+            )
+            .into(),
             validator: Box::new(validate_response),
+            timeout: &GET_TIMEOUT,
+            followup: None,
         }
     }
 }
@@ -23,6 +26,7 @@ pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
                 RESPONSE_HIT.increment();
                 GET_KEY_HIT.increment();
             }
+            GET_OK.increment();
             Ok(())
         }
         _ => {
@@ -0,0 +1,37 @@
+/// Retrieve the value for a key and update its TTL with `gat`.
+use super::*;
+
+impl From<&workload::client::GetAndTouch> for RequestWithValidator {
+    fn from(other: &workload::client::GetAndTouch) -> Self {
+        GAT.increment();
+        RequestWithValidator {
+            request: MemcacheRequest::Gat {
+                key: (*other.key).to_owned().into_boxed_slice(),
+                exptime: exptime(other.ttl),
+            },
+            validator: Box::new(validate_response),
+            timeout: &GAT_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Values(values) => {
+            if values.values().is_empty() {
+                RESPONSE_MISS.increment();
+                GAT_MISS.increment();
+            } else {
+                RESPONSE_HIT.increment();
+                GAT_HIT.increment();
+            }
+            GAT_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GAT_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// Retrieve the value and CAS token for a key in the cache.
+use super::*;
+
+impl From<&workload::client::Gets> for RequestWithValidator {
+    fn from(other: &workload::client::Gets) -> Self {
+        GETS.increment();
+        RequestWithValidator {
+            request: Request::gets(
+                vec![(*other.key).to_owned().into_boxed_slice()].into_boxed_slice(),
+            )
+            .into(),
+            validator: Box::new(validate_response),
+            timeout: &GETS_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Values(values) => {
+            if values.values().is_empty() {
+                RESPONSE_MISS.increment();
+                GETS_KEY_MISS.increment();
+            } else {
+                RESPONSE_HIT.increment();
+                GETS_KEY_HIT.increment();
+            }
+            GETS_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GETS_EX.increment();
+            Err(())
+        }
+    }
+}
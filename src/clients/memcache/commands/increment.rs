@@ -0,0 +1,36 @@
+/// Increments the numeric value for a key in the cache. The key must already exist.
+use super::*;
+
+impl From<&workload::client::Increment> for RequestWithValidator {
+    fn from(other: &workload::client::Increment) -> Self {
+        INCR.increment();
+        RequestWithValidator {
+            request: Request::incr(
+                (*other.key).to_owned().into_boxed_slice(),
+                other.amount,
+                false,
+            )
+            .into(),
+            validator: Box::new(validate_response),
+            timeout: &INCR_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Numeric(_) => {
+            INCR_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            INCR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            INCR_EX.increment();
+            Err(())
+        }
+    }
+}
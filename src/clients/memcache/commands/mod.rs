@@ -1,7 +1,31 @@
 use super::*;
 
 mod add;
+mod append;
+mod cas;
+mod decrement;
 mod delete;
 mod get;
+mod get_and_touch;
+mod gets;
+mod increment;
+mod multi_get;
+mod prepend;
 mod replace;
 mod set;
+mod touch;
+
+/// Returns the TTL for a request. The expiration time is written as-is, so a
+/// TTL of more than 30 days is sent as the time at which the item expires.
+fn ttl(ttl: Option<Duration>) -> Ttl {
+    match ttl {
+        Some(ttl) => Ttl::new(exptime(Some(ttl)) as i64, TimeType::Delta),
+        None => Ttl::none(),
+    }
+}
+
+/// Returns the expiration time for a request which is composed by this client.
+/// Zero is no expiration.
+fn exptime(ttl: Option<Duration>) -> u32 {
+    ttl.map(memcache_exptime).unwrap_or(0)
+}
@@ -0,0 +1,47 @@
+/// Retrieve the values for multiple keys in the cache.
+use super::*;
+
+impl From<&workload::client::MultiGet> for RequestWithValidator {
+    fn from(other: &workload::client::MultiGet) -> Self {
+        GET.increment();
+
+        let keys = other.keys.len() as u64;
+
+        RequestWithValidator {
+            request: Request::get(
+                other
+                    .keys
+                    .iter()
+                    .map(|k| (**k).to_owned().into_boxed_slice())
+                    .collect(),
+            )
+            .into(),
+            validator: Box::new(move |response| validate_response(keys, response)),
+            timeout: &GET_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+/// Hits and misses are counted for each key, while the request itself counts
+/// as a single success or exception.
+pub fn validate_response(keys: u64, response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Values(values) => {
+            // only the keys which were found are returned
+            let hits = values.values().len() as u64;
+            let misses = keys.saturating_sub(hits);
+
+            RESPONSE_HIT.add(hits);
+            RESPONSE_MISS.add(misses);
+            GET_KEY_HIT.add(hits);
+            GET_KEY_MISS.add(misses);
+            GET_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GET_EX.increment();
+            Err(())
+        }
+    }
+}
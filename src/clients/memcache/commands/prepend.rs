@@ -0,0 +1,40 @@
+/// Prepends data to the value for a key in the cache. The key must already exist.
+use super::*;
+
+impl From<&workload::client::Prepend> for RequestWithValidator {
+    fn from(other: &workload::client::Prepend) -> Self {
+        PREPEND.increment();
+        RequestWithValidator {
+            request: Request::prepend(
+                (*other.key).to_owned().into_boxed_slice(),
+                (*other.value).to_owned().into_boxed_slice(),
+                0,
+                Ttl::none(),
+                false,
+            )
+            .into(),
+            validator: Box::new(validate_response),
+            timeout: &PREPEND_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Stored(_) => {
+            PREPEND_STORED.increment();
+            PREPEND_OK.increment();
+            Ok(())
+        }
+        Response::NotStored(_) => {
+            PREPEND_NOT_STORED.increment();
+            PREPEND_OK.increment();
+            Ok(())
+        }
+        _ => {
+            PREPEND_EX.increment();
+            Err(())
+        }
+    }
+}
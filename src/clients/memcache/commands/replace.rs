@@ -9,10 +9,13 @@ impl From<&workload::client::Replace> for RequestWithValidator {
                 (*other.key).to_owned().into_boxed_slice(),
                 (*other.value).to_owned().into_boxed_slice(),
                 0,
-                Ttl::none(),
+                ttl(other.ttl),
                 false,
-            ),
+            )
+            .into(),
             validator: Box::new(validate_response),
+            timeout: &REPLACE_TIMEOUT,
+            followup: None,
         }
     }
 }
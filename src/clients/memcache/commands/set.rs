@@ -10,10 +10,13 @@ impl From<&workload::client::Set> for RequestWithValidator {
                 (*other.key).to_owned().into_boxed_slice(),
                 (*other.value).to_owned().into_boxed_slice(),
                 0,
-                Ttl::none(),
+                ttl(other.ttl),
                 false,
-            ),
+            )
+            .into(),
             validator: Box::new(validate_response),
+            timeout: &SET_TIMEOUT,
+            followup: None,
         }
     }
 }
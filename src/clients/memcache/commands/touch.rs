@@ -0,0 +1,39 @@
+/// Update the TTL for a key with `touch`. No value is returned.
+use super::*;
+
+impl From<&workload::client::Touch> for RequestWithValidator {
+    fn from(other: &workload::client::Touch) -> Self {
+        TOUCH.increment();
+        RequestWithValidator {
+            request: MemcacheRequest::Touch {
+                key: (*other.key).to_owned().into_boxed_slice(),
+                exptime: exptime(other.ttl),
+            },
+            validator: Box::new(validate_response),
+            timeout: &TOUCH_TIMEOUT,
+            followup: None,
+        }
+    }
+}
+
+/// A `TOUCHED` response is parsed as `Stored`, see `parse_touch`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Stored(_) => {
+            RESPONSE_HIT.increment();
+            TOUCH_HIT.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            RESPONSE_MISS.increment();
+            TOUCH_MISS.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        _ => {
+            TOUCH_EX.increment();
+            Err(())
+        }
+    }
+}
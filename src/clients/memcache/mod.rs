@@ -1,16 +1,104 @@
+use super::pipeline::{self, Codec, Connection, Inflight, Received};
 use super::*;
-use crate::net::Connector;
-use protocol_memcache::{Compose, Parse, Request, Response, Ttl};
+use protocol_memcache::{Compose, Parse, Request, Response, ResponseParser, TimeType, Ttl};
 use session::{Buf, BufMut, Buffer};
-use std::borrow::{Borrow, BorrowMut};
+use std::borrow::Borrow;
 
 mod commands;
 
+/// A request for the server. The `touch` and `gat` commands are not supported
+/// by `protocol_memcache` and are composed by this client.
+enum MemcacheRequest {
+    Protocol(Request),
+    Touch { key: Box<[u8]>, exptime: u32 },
+    Gat { key: Box<[u8]>, exptime: u32 },
+}
+
+impl From<Request> for MemcacheRequest {
+    fn from(other: Request) -> Self {
+        Self::Protocol(other)
+    }
+}
+
+impl MemcacheRequest {
+    fn compose(&self, buffer: &mut Buffer) {
+        match self {
+            Self::Protocol(request) => {
+                request.compose(buffer);
+            }
+            Self::Touch { key, exptime } => {
+                buffer.put_slice(b"touch ");
+                buffer.put_slice(key);
+                buffer.put_slice(format!(" {exptime}\r\n").as_bytes());
+            }
+            Self::Gat { key, exptime } => {
+                buffer.put_slice(format!("gat {exptime} ").as_bytes());
+                buffer.put_slice(key);
+                buffer.put_slice(b"\r\n");
+            }
+        }
+    }
+
+    /// Parses the response to this request, returning the response and the
+    /// number of bytes consumed.
+    fn parse(&self, parser: &ResponseParser, buffer: &[u8]) -> Result<(Response, usize)> {
+        if let Self::Touch { .. } = self {
+            return parse_touch(buffer);
+        }
+
+        let response = parser.parse(buffer)?;
+        let consumed = response.consumed();
+
+        Ok((response.into_inner(), consumed))
+    }
+}
+
+/// Parses the response to a `touch`. A `TOUCHED` response is returned as
+/// `Stored`, as there is no response type for it in `protocol_memcache`.
+fn parse_touch(buffer: &[u8]) -> Result<(Response, usize)> {
+    let end = match buffer.windows(2).position(|w| w == b"\r\n") {
+        Some(end) => end,
+        None => return Err(Error::from(ErrorKind::WouldBlock)),
+    };
+
+    let response = match &buffer[..end] {
+        b"TOUCHED" => Response::stored(false),
+        b"NOT_FOUND" => Response::not_found(false),
+        _ => Response::error(),
+    };
+
+    Ok((response, end + 2))
+}
+
 struct RequestWithValidator {
-    request: Request,
+    request: MemcacheRequest,
     validator: Box<dyn Fn(Response) -> std::result::Result<(), ()> + Send>,
+    timeout: &'static metriken::Counter,
+    // builds a request which depends on the response and is sent once the
+    // response is received, such as the `cas` which follows a `gets`
+    followup: Option<Box<dyn Fn(&Response) -> Option<RequestWithValidator> + Send>>,
 }
 
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[
+        Verb::Add,
+        Verb::Get,
+        Verb::Set,
+        Verb::Delete,
+        Verb::Replace,
+        Verb::Increment,
+        Verb::Decrement,
+        Verb::Gets,
+        Verb::Cas,
+        Verb::Append,
+        Verb::Prepend,
+        Verb::Touch,
+        Verb::GetAndTouch,
+    ],
+    multi_get: true,
+};
+
 /// Launch tasks with one conncetion per task as memcache protocol is not mux-enabled.
 pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
     debug!("launching memcache protocol tasks");
@@ -18,7 +106,10 @@ pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiv
     // create one task per connection
     for _ in 0..config.client().unwrap().poolsize() {
         for endpoint in config.target().endpoints() {
-            runtime.spawn(task(
+            runtime.spawn(pipeline::task(
+                MemcacheCodec {
+                    parser: ResponseParser {},
+                },
                 work_receiver.clone(),
                 endpoint.clone(),
                 config.clone(),
@@ -27,170 +118,54 @@ pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiv
     }
 }
 
-#[allow(clippy::slow_vector_initialization)]
-async fn task(work_receiver: Receiver<WorkItem>, endpoint: String, config: Config) -> Result<()> {
-    let connector = Connector::new(&config)?;
-
-    // we would not be creating a memcache client task if we didn't have a
-    // client config, so this unwrap will succeed.
-    let client_config = config.client().unwrap();
-
-    let mut stream = None;
-    let parser = protocol_memcache::ResponseParser {};
-    let mut read_buffer = Buffer::new(client_config.read_buffer_size());
-    let mut write_buffer = Buffer::new(client_config.write_buffer_size());
-
-    while RUNNING.load(Ordering::Relaxed) {
-        if stream.is_none() {
-            CONNECT.increment();
-            stream = match timeout(
-                client_config.connect_timeout(),
-                connector.connect(&endpoint),
-            )
-            .await
-            {
-                Ok(Ok(s)) => {
-                    CONNECT_OK.increment();
-                    CONNECT_CURR.increment();
-                    Some(s)
-                }
-                Ok(Err(_)) => {
-                    CONNECT_EX.increment();
-                    sleep(Duration::from_millis(100)).await;
-                    continue;
-                }
-                Err(_) => {
-                    CONNECT_TIMEOUT.increment();
-                    sleep(Duration::from_millis(100)).await;
-                    continue;
-                }
-            }
-        }
-
-        let mut s = stream.take().unwrap();
-
-        let work_item = work_receiver
-            .recv()
-            .await
-            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
-
-        REQUEST.increment();
-
-        // check if we should reconnect
-        if work_item == WorkItem::Reconnect {
-            CONNECT_CURR.decrement();
-            continue;
-        }
-
-        let request = RequestWithValidator::try_from(&work_item);
-
-        // skip unsupported work items
-        if request.is_err() {
-            stream = Some(s);
-            continue;
-        }
-
-        let request = request.unwrap();
-
-        // compose request
-        REQUEST_OK.increment();
-        request.request.compose(&mut write_buffer);
+struct MemcacheCodec {
+    parser: ResponseParser,
+}
 
-        // send request
-        let start = Instant::now();
-        s.write_all(write_buffer.borrow()).await?;
+impl Codec for MemcacheCodec {
+    type Request = RequestWithValidator;
 
-        // clear the buffers
-        write_buffer.clear();
-        read_buffer.clear();
+    const CLOSE_ON_EXCEPTION: bool = true;
 
-        // read until response or timeout
-        let response = loop {
-            let remaining_time = client_config
-                .request_timeout()
-                .as_millis()
-                .saturating_sub(start.elapsed().as_millis());
-            if remaining_time == 0 {
-                break Err(ResponseError::Timeout);
-            }
+    async fn connect(&mut self, _connection: &mut Connection) -> Result<()> {
+        Ok(())
+    }
 
-            match timeout(
-                Duration::from_millis(remaining_time as _),
-                s.read(read_buffer.borrow_mut()),
-            )
-            .await
-            {
-                Ok(Ok(n)) => {
-                    unsafe {
-                        read_buffer.advance_mut(n);
-                    }
-                    match parser.parse(read_buffer.borrow()) {
-                        Ok(resp) => {
-                            let consumed = resp.consumed();
-                            let resp = resp.into_inner();
-
-                            read_buffer.advance(consumed);
-
-                            break Ok(resp);
-                        }
-                        Err(e) => match e.kind() {
-                            ErrorKind::WouldBlock => {}
-                            _ => {
-                                break Err(ResponseError::Exception);
-                            }
-                        },
-                    }
-                }
-                Ok(Err(_)) => {
-                    break Err(ResponseError::Exception);
-                }
-                Err(_) => {
-                    break Err(ResponseError::Timeout);
-                }
-            }
-        };
+    fn request(&mut self, request: ClientRequest) -> std::result::Result<Self::Request, ()> {
+        RequestWithValidator::try_from(&request)
+    }
 
-        let stop = Instant::now();
+    fn compose(&mut self, request: &mut Self::Request, buffer: &mut Buffer) {
+        request.request.compose(buffer);
+    }
 
-        match response {
-            Ok(response) => {
-                let latency_ns = stop.duration_since(start).as_nanos() as u64;
+    fn receive(
+        &mut self,
+        request: &mut Self::Request,
+        buffer: &mut Buffer,
+    ) -> Result<Received<Self::Request>> {
+        let (response, consumed) = request.request.parse(&self.parser, (*buffer).borrow())?;
 
-                // check if the response is valid
-                if (request.validator)(response).is_err() {
-                    // increment error stats, connection will be dropped
-                    RESPONSE_EX.increment();
-                    CONNECT_CURR.increment();
-                } else {
-                    // increment success stats and latency
-                    RESPONSE_OK.increment();
+        buffer.advance(consumed);
 
-                    let _ = RESPONSE_LATENCY.increment(latency_ns);
+        let followup = request
+            .followup
+            .as_ref()
+            .and_then(|followup| followup(&response));
 
-                    // preserve the connection for the next request
-                    stream = Some(s);
-                }
-            }
-            Err(ResponseError::Exception) => {
-                // use validate response to record the exception
-                let _ = (request.validator)(Response::error());
+        Ok(Received::Complete((request.validator)(response), followup))
+    }
+}
 
-                // increment error stats and allow connection to be dropped
-                RESPONSE_EX.increment();
-                CONNECT_CURR.decrement();
-            }
-            Err(ResponseError::Timeout) => {
-                // increment error stats and allow connection to be dropped
-                RESPONSE_TIMEOUT.increment();
-                CONNECT_CURR.decrement();
-            }
-            Err(ResponseError::Ratelimited) | Err(ResponseError::BackendTimeout) => {
-                unimplemented!();
-            }
-        }
+impl Inflight for RequestWithValidator {
+    fn exception(self) {
+        // use validate response to record the exception
+        let _ = (self.validator)(Response::error());
     }
 
-    Ok(())
+    fn timeout(&self) -> &'static metriken::Counter {
+        self.timeout
+    }
 }
 
 impl From<&workload::client::Delete> for Request {
@@ -200,19 +175,40 @@ impl From<&workload::client::Delete> for Request {
     }
 }
 
-impl TryFrom<&WorkItem> for RequestWithValidator {
+impl TryFrom<&ClientRequest> for RequestWithValidator {
     type Error = ();
-    fn try_from(other: &WorkItem) -> std::result::Result<RequestWithValidator, ()> {
+    fn try_from(other: &ClientRequest) -> std::result::Result<RequestWithValidator, ()> {
         match other {
-            WorkItem::Request { request, .. } => match request {
-                ClientRequest::Add(r) => Ok(Self::from(r)),
-                ClientRequest::Get(r) => Ok(Self::from(r)),
-                ClientRequest::Delete(r) => Ok(Self::from(r)),
-                ClientRequest::Replace(r) => Ok(Self::from(r)),
-                ClientRequest::Set(r) => Ok(Self::from(r)),
-                _ => Err(()),
-            },
+            ClientRequest::Add(r) => Ok(Self::from(r)),
+            ClientRequest::Append(r) => Ok(Self::from(r)),
+            ClientRequest::Cas(r) => Ok(Self::from(r)),
+            ClientRequest::Decrement(r) => Ok(Self::from(r)),
+            ClientRequest::Get(r) => Ok(Self::from(r)),
+            ClientRequest::GetAndTouch(r) => Ok(Self::from(r)),
+            ClientRequest::Gets(r) => Ok(Self::from(r)),
+            ClientRequest::Increment(r) => Ok(Self::from(r)),
+            ClientRequest::MultiGet(r) => Ok(Self::from(r)),
+            ClientRequest::Delete(r) => Ok(Self::from(r)),
+            ClientRequest::Prepend(r) => Ok(Self::from(r)),
+            ClientRequest::Replace(r) => Ok(Self::from(r)),
+            ClientRequest::Set(r) => Ok(Self::from(r)),
+            ClientRequest::Touch(r) => Ok(Self::from(r)),
             _ => Err(()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = clients::tests::config(dir.path(), Protocol::Memcache);
+
+        for request in clients::tests::requests(&config) {
+            assert!(RequestWithValidator::try_from(&request).is_ok());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// Adds a key-value pair to the cache if it does not already exist.
+use super::*;
+
+pub fn add(options: &Options, other: &workload::client::Add) -> RequestWithValidator {
+    ADD.increment();
+    RequestWithValidator {
+        request: Request::add(other.key.clone(), other.value.clone(), ttl(other.ttl))
+            .quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &ADD_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            ADD_STORED.increment();
+            Ok(())
+        }
+        Status::KeyExists | Status::NotStored => {
+            ADD_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            ADD_EX.increment();
+            Err(())
+        }
+    }
+}
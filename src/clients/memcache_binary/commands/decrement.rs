@@ -0,0 +1,30 @@
+/// Decrements the value for a key. The request fails if the key does not exist.
+use super::*;
+
+pub fn decrement(options: &Options, other: &workload::client::Decrement) -> RequestWithValidator {
+    DECR.increment();
+    RequestWithValidator {
+        request: Request::decrement(other.key.clone(), other.amount).quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &DECR_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful update is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            DECR_OK.increment();
+            Ok(())
+        }
+        Status::KeyNotFound => {
+            DECR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            DECR_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// Removes a key from the cache.
+use super::*;
+
+pub fn delete(options: &Options, other: &workload::client::Delete) -> RequestWithValidator {
+    DELETE.increment();
+    RequestWithValidator {
+        request: Request::delete(other.key.clone()).quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &DELETE_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful delete is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            DELETE_DELETED.increment();
+            Ok(())
+        }
+        Status::KeyNotFound => {
+            DELETE_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            DELETE_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,33 @@
+/// Retrieve the value of a key in the cache using `GET` or `GETQ`.
+use super::*;
+
+pub fn get(options: &Options, other: &workload::client::Get) -> RequestWithValidator {
+    GET.increment();
+    RequestWithValidator {
+        request: Request::get(other.key.clone()).quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &GET_TIMEOUT,
+    }
+}
+
+/// In quiet mode a miss is suppressed and is indicated by a bare `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError if !response.is_noop() => {
+            RESPONSE_HIT.increment();
+            GET_KEY_HIT.increment();
+            GET_OK.increment();
+            Ok(())
+        }
+        Status::NoError | Status::KeyNotFound => {
+            RESPONSE_MISS.increment();
+            GET_KEY_MISS.increment();
+            GET_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GET_EX.increment();
+            Err(())
+        }
+    }
+}
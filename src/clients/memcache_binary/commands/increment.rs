@@ -0,0 +1,30 @@
+/// Increments the value for a key. The request fails if the key does not exist.
+use super::*;
+
+pub fn increment(options: &Options, other: &workload::client::Increment) -> RequestWithValidator {
+    INCR.increment();
+    RequestWithValidator {
+        request: Request::increment(other.key.clone(), other.amount).quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &INCR_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful update is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            INCR_OK.increment();
+            Ok(())
+        }
+        Status::KeyNotFound => {
+            INCR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            INCR_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use super::*;
+
+mod add;
+mod decrement;
+mod delete;
+mod get;
+mod increment;
+mod replace;
+mod set;
+
+pub use add::add;
+pub use decrement::decrement;
+pub use delete::delete;
+pub use get::get;
+pub use increment::increment;
+pub use replace::replace;
+pub use set::set;
+
+// Returns the expiration for a storage request, zero means that the item does
+// not expire. A TTL of more than 30 days is sent as the time at which the item
+// expires.
+fn ttl(ttl: Option<Duration>) -> u32 {
+    ttl.map(memcache_exptime).unwrap_or(0)
+}
@@ -0,0 +1,31 @@
+/// Replaces the value for a key in the cache if it already exists.
+use super::*;
+
+pub fn replace(options: &Options, other: &workload::client::Replace) -> RequestWithValidator {
+    REPLACE.increment();
+    RequestWithValidator {
+        request: Request::replace(other.key.clone(), other.value.clone(), ttl(other.ttl))
+            .quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &REPLACE_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            REPLACE_STORED.increment();
+            Ok(())
+        }
+        Status::KeyNotFound | Status::NotStored => {
+            REPLACE_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            REPLACE_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// Sets a key-value pair in the cache.
+use super::*;
+
+pub fn set(options: &Options, other: &workload::client::Set) -> RequestWithValidator {
+    SET.increment();
+    RequestWithValidator {
+        request: Request::set(other.key.clone(), other.value.clone(), ttl(other.ttl))
+            .quiet(options.quiet),
+        validator: Box::new(validate_response),
+        timeout: &SET_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `NOOP`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response.status() {
+        Status::NoError => {
+            SET_STORED.increment();
+            Ok(())
+        }
+        Status::NotStored => {
+            SET_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            SET_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use super::pipeline::{self, Codec, Connection, Inflight, Received};
+use super::*;
+use session::{Buf, Buffer};
+use std::borrow::Borrow;
+
+mod commands;
+mod protocol;
+
+use protocol::{Request, Response, ResponseParser, Status};
+
+struct RequestWithValidator {
+    request: Request,
+    validator: Box<dyn Fn(Response) -> std::result::Result<(), ()> + Send>,
+    timeout: &'static metriken::Counter,
+}
+
+/// Options for composing binary requests, these are derived from the client
+/// config once per task.
+struct Options {
+    quiet: bool,
+}
+
+impl Options {
+    fn new(config: &Config) -> Self {
+        // we would not be creating a memcache client task if we didn't have a
+        // client config, so this unwrap will succeed.
+        let client_config = config.client().unwrap();
+
+        Self {
+            quiet: client_config.memcache_binary_quiet(),
+        }
+    }
+}
+
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[
+        Verb::Add,
+        Verb::Get,
+        Verb::Set,
+        Verb::Delete,
+        Verb::Replace,
+        Verb::Increment,
+        Verb::Decrement,
+    ],
+    multi_get: false,
+};
+
+/// Launch tasks with one conncetion per task as memcache protocol is not mux-enabled.
+pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    debug!("launching memcache binary protocol tasks");
+
+    // create one task per connection
+    for _ in 0..config.client().unwrap().poolsize() {
+        for endpoint in config.target().endpoints() {
+            runtime.spawn(pipeline::task(
+                BinaryCodec::new(&config),
+                work_receiver.clone(),
+                endpoint.clone(),
+                config.clone(),
+            ));
+        }
+    }
+}
+
+struct BinaryCodec {
+    options: Options,
+    parser: ResponseParser,
+    // in quiet mode, holds a response to the oldest request until the `NOOP`
+    // which marks the end of its responses is received
+    pending: Option<Response>,
+    // each request is assigned an opaque which the server copies into the
+    // response, this is used to check that responses match the requests
+    opaque: u32,
+    // the SASL request which authenticates each connection, if credentials
+    // are configured
+    auth: Option<Request>,
+    auth_timeout: Duration,
+}
+
+impl BinaryCodec {
+    fn new(config: &Config) -> Self {
+        // we would not be creating a memcache client task if we didn't have a
+        // client config, so this unwrap will succeed.
+        let client_config = config.client().unwrap();
+
+        let auth = match (
+            client_config.memcache_binary_username(),
+            client_config.memcache_binary_password(),
+        ) {
+            (Some(username), Some(password)) => Some(Request::sasl_plain(username, password)),
+            _ => None,
+        };
+
+        Self {
+            options: Options::new(config),
+            parser: ResponseParser::new(),
+            pending: None,
+            opaque: 0,
+            auth,
+            auth_timeout: client_config.connect_timeout(),
+        }
+    }
+}
+
+impl Codec for BinaryCodec {
+    type Request = RequestWithValidator;
+
+    const CLOSE_ON_EXCEPTION: bool = true;
+
+    async fn connect(&mut self, connection: &mut Connection) -> Result<()> {
+        self.pending = None;
+
+        // authenticate before the connection is used for requests
+        if let Some(request) = &self.auth {
+            match timeout(
+                self.auth_timeout,
+                authenticate(connection, request, &self.parser),
+            )
+            .await
+            {
+                Ok(Ok(())) => {
+                    MEMCACHE_BINARY_AUTH_OK.increment();
+                }
+                _ => {
+                    MEMCACHE_BINARY_AUTH_EX.increment();
+                    return Err(Error::new(ErrorKind::Other, "authentication failed"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request(&mut self, request: ClientRequest) -> std::result::Result<Self::Request, ()> {
+        RequestWithValidator::new(&self.options, &request)
+    }
+
+    fn compose(&mut self, request: &mut Self::Request, buffer: &mut Buffer) {
+        self.opaque = self.opaque.wrapping_add(1);
+        request.request.set_opaque(self.opaque);
+        request.request.compose(buffer);
+    }
+
+    fn receive(
+        &mut self,
+        request: &mut Self::Request,
+        buffer: &mut Buffer,
+    ) -> Result<Received<Self::Request>> {
+        let response = self.parser.parse((*buffer).borrow())?;
+        let consumed = response.consumed();
+        let response = response.into_inner();
+
+        buffer.advance(consumed);
+
+        match complete(&request.request, &mut self.pending, response) {
+            Ok(Some(response)) => Ok(Received::Complete((request.validator)(response), None)),
+            Ok(None) => Ok(Received::Pending),
+            Err(_) => Err(Error::new(
+                ErrorKind::InvalidData,
+                "responses are out of sync with the requests",
+            )),
+        }
+    }
+}
+
+impl Inflight for RequestWithValidator {
+    fn exception(self) {
+        // use validate response to record the exception
+        let _ = (self.validator)(Response::error());
+    }
+
+    fn timeout(&self) -> &'static metriken::Counter {
+        self.timeout
+    }
+}
+
+// Matches a response to the oldest outstanding request. Returns the response
+// once the request is complete, or `None` if the request is quiet and the
+// `NOOP` which completes it has not been received yet. Returns an error if
+// the responses are out of sync with the requests.
+fn complete(
+    request: &Request,
+    pending: &mut Option<Response>,
+    response: Response,
+) -> std::result::Result<Option<Response>, ()> {
+    // the server copies the opaque into the response, so a mismatch means
+    // that the responses are out of sync
+    if response.opaque() != request.opaque() {
+        return Err(());
+    }
+
+    if !request.is_quiet() {
+        return Ok(Some(response));
+    }
+
+    // a quiet request is complete once the `NOOP` is received, which may or
+    // may not follow a response to the request
+    if response.is_noop() {
+        Ok(Some(pending.take().unwrap_or(response)))
+    } else if pending.is_none() {
+        *pending = Some(response);
+        Ok(None)
+    } else {
+        // more than one response for a request
+        Err(())
+    }
+}
+
+// Performs SASL authentication on a newly established connection.
+async fn authenticate(
+    connection: &mut Connection,
+    request: &Request,
+    parser: &ResponseParser,
+) -> Result<()> {
+    request.compose(connection.write_buffer());
+    connection.flush().await?;
+
+    loop {
+        connection.fill().await?;
+
+        let read_buffer = connection.read_buffer();
+
+        match parser.parse((*read_buffer).borrow()) {
+            Ok(response) => {
+                let consumed = response.consumed();
+                let response = response.into_inner();
+
+                read_buffer.advance(consumed);
+
+                if response.status() == Status::NoError {
+                    return Ok(());
+                }
+
+                return Err(Error::new(ErrorKind::Other, "authentication failed"));
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+}
+
+impl RequestWithValidator {
+    fn new(options: &Options, request: &ClientRequest) -> std::result::Result<Self, ()> {
+        match request {
+            ClientRequest::Add(r) => Ok(commands::add(options, r)),
+            ClientRequest::Get(r) => Ok(commands::get(options, r)),
+            ClientRequest::Delete(r) => Ok(commands::delete(options, r)),
+            ClientRequest::Replace(r) => Ok(commands::replace(options, r)),
+            ClientRequest::Set(r) => Ok(commands::set(options, r)),
+            ClientRequest::Increment(r) => Ok(commands::increment(options, r)),
+            ClientRequest::Decrement(r) => Ok(commands::decrement(options, r)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::Connector;
+    use protocol::Opcode;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // A response header with the given status and a body of the given length.
+    fn encode(opcode: Opcode, status: u16, body_len: u32, opaque: u32) -> Vec<u8> {
+        let mut buffer = vec![0x81, opcode as u8, 0, 0, 0, 0];
+        buffer.extend_from_slice(&status.to_be_bytes());
+        buffer.extend_from_slice(&body_len.to_be_bytes());
+        buffer.extend_from_slice(&opaque.to_be_bytes());
+        buffer.extend_from_slice(&[0; 8]);
+        buffer.resize(24 + body_len as usize, b'x');
+        buffer
+    }
+
+    fn response(opcode: Opcode, status: u16, opaque: u32) -> Response {
+        ResponseParser::new()
+            .parse(&encode(opcode, status, 0, opaque))
+            .unwrap()
+            .into_inner()
+    }
+
+    fn request(quiet: bool, opaque: u32) -> Request {
+        let mut request = Request::get(Arc::from(&b"key"[..])).quiet(quiet);
+        request.set_opaque(opaque);
+        request
+    }
+
+    #[test]
+    fn opaque_mismatch() {
+        let mut pending = None;
+
+        let other = response(Opcode::Get, 0, 2);
+        assert!(complete(&request(false, 1), &mut pending, other.clone()).is_err());
+        assert!(complete(&request(true, 1), &mut pending, other).is_err());
+
+        let hit = response(Opcode::Get, 0, 1);
+        assert_eq!(
+            complete(&request(false, 1), &mut pending, hit.clone()),
+            Ok(Some(hit))
+        );
+    }
+
+    // a quiet request which gets the common response, such as a miss for
+    // `GETQ`, is completed by the `NOOP` alone
+    #[test]
+    fn quiet_noop() {
+        let mut pending = None;
+
+        let noop = response(Opcode::NoOp, 0, 3);
+        assert_eq!(
+            complete(&request(true, 3), &mut pending, noop.clone()),
+            Ok(Some(noop))
+        );
+        assert!(pending.is_none());
+    }
+
+    // a response to a quiet request is held until the `NOOP` is received
+    #[test]
+    fn quiet_drain() {
+        let mut pending = None;
+        let request = request(true, 4);
+
+        let hit = response(Opcode::GetQ, 0, 4);
+        assert_eq!(complete(&request, &mut pending, hit.clone()), Ok(None));
+        assert_eq!(pending, Some(hit.clone()));
+
+        let noop = response(Opcode::NoOp, 0, 4);
+        assert_eq!(complete(&request, &mut pending, noop), Ok(Some(hit)));
+        assert!(pending.is_none());
+    }
+
+    #[test]
+    fn quiet_extra_response() {
+        let mut pending = None;
+        let request = request(true, 5);
+
+        let hit = response(Opcode::GetQ, 0, 5);
+        assert_eq!(complete(&request, &mut pending, hit.clone()), Ok(None));
+        assert!(complete(&request, &mut pending, hit).is_err());
+    }
+
+    // Runs the authentication against a server which replies with the status
+    // in two parts, and returns the request the server received.
+    async fn authenticate_with_status(status: u16) -> (Result<()>, Vec<u8>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            // the header and the body of `\0user\0secret` with the `PLAIN` key
+            let mut request = vec![0; 24 + 17];
+            stream.read_exact(&mut request).await.unwrap();
+
+            let response = encode(Opcode::SaslAuth, status, 13, 0);
+            stream.write_all(&response[..10]).await.unwrap();
+            stream.flush().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            stream.write_all(&response[10..]).await.unwrap();
+
+            request
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = clients::tests::config(dir.path(), Protocol::MemcacheBinary);
+
+        let stream = Connector::new(&config)
+            .unwrap()
+            .connect(&addr)
+            .await
+            .unwrap();
+        let mut connection = Connection::new(stream, &config);
+
+        let result = authenticate(
+            &mut connection,
+            &Request::sasl_plain("user", "secret"),
+            &ResponseParser::new(),
+        )
+        .await;
+
+        // the whole response is consumed
+        assert_eq!(connection.read_buffer().remaining(), 0);
+
+        (result, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn authenticate_plain() {
+        let (result, request) = authenticate_with_status(0x0000).await;
+
+        assert!(result.is_ok());
+        assert_eq!(&request[..2], &[0x80, Opcode::SaslAuth as u8]);
+        assert_eq!(&request[24..], b"PLAIN\0user\0secret");
+    }
+
+    #[tokio::test]
+    async fn authenticate_failed() {
+        let (result, _) = authenticate_with_status(0x0020).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = clients::tests::config(dir.path(), Protocol::MemcacheBinary);
+        let options = Options::new(&config);
+
+        for request in clients::tests::requests(&config) {
+            assert!(RequestWithValidator::new(&options, &request).is_ok());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// An implementation of the memcache binary protocol which operates directly
+// on byte buffers. Each request and response begins with a fixed size header
+// which includes an opaque value that the server copies into the response.
+
+mod request;
+mod response;
+
+pub use request::Request;
+pub use response::{Response, ResponseParser, Status};
+
+/// The size of a request or response header.
+const HEADER_LEN: usize = 24;
+
+/// The magic byte for requests.
+const REQUEST_MAGIC: u8 = 0x80;
+
+/// The magic byte for responses.
+const RESPONSE_MAGIC: u8 = 0x81;
+
+/// The opcodes which are used by the client. Quiet variants suppress the
+/// common response for a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Opcode {
+    Get = 0x00,
+    Set = 0x01,
+    Add = 0x02,
+    Replace = 0x03,
+    Delete = 0x04,
+    Increment = 0x05,
+    Decrement = 0x06,
+    GetQ = 0x09,
+    NoOp = 0x0a,
+    SetQ = 0x11,
+    AddQ = 0x12,
+    ReplaceQ = 0x13,
+    DeleteQ = 0x14,
+    IncrementQ = 0x15,
+    DecrementQ = 0x16,
+    SaslAuth = 0x21,
+}
+
+impl Opcode {
+    /// Returns the quiet variant of the opcode, if there is one.
+    fn quiet(self) -> Option<Self> {
+        match self {
+            Self::Get => Some(Self::GetQ),
+            Self::Set => Some(Self::SetQ),
+            Self::Add => Some(Self::AddQ),
+            Self::Replace => Some(Self::ReplaceQ),
+            Self::Delete => Some(Self::DeleteQ),
+            Self::Increment => Some(Self::IncrementQ),
+            Self::Decrement => Some(Self::DecrementQ),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a successful parse. Contains the parsed item and the number
+/// of bytes which were consumed from the buffer.
+pub struct ParseOk<T> {
+    inner: T,
+    consumed: usize,
+}
+
+impl<T> ParseOk<T> {
+    pub fn new(inner: T, consumed: usize) -> Self {
+        Self { inner, consumed }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+}
@@ -0,0 +1,307 @@
+use super::{Opcode, HEADER_LEN, REQUEST_MAGIC};
+use session::BufMut;
+use std::sync::Arc;
+
+/// A binary protocol request. The opaque value is assigned when the request
+/// is sent and is used to match the response to the request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    opcode: Opcode,
+    extras: Extras,
+    key: Option<Arc<[u8]>>,
+    value: Option<Vec<u8>>,
+    opaque: u32,
+    quiet: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Extras {
+    None,
+    /// flags and expiration for set, add, and replace
+    Storage {
+        flags: u32,
+        expiry: u32,
+    },
+    /// delta, initial value, and expiration for increment and decrement
+    Arithmetic {
+        delta: u64,
+        initial: u64,
+        expiry: u32,
+    },
+}
+
+// an expiration for increment and decrement which causes the request to fail
+// if the key does not exist instead of storing the initial value
+const NO_CREATE: u32 = 0xffff_ffff;
+
+impl Request {
+    pub fn get(key: Arc<[u8]>) -> Self {
+        Self::new(Opcode::Get, Extras::None, Some(key), None)
+    }
+
+    pub fn set(key: Arc<[u8]>, value: Vec<u8>, ttl: u32) -> Self {
+        Self::storage(Opcode::Set, key, value, ttl)
+    }
+
+    pub fn add(key: Arc<[u8]>, value: Vec<u8>, ttl: u32) -> Self {
+        Self::storage(Opcode::Add, key, value, ttl)
+    }
+
+    pub fn replace(key: Arc<[u8]>, value: Vec<u8>, ttl: u32) -> Self {
+        Self::storage(Opcode::Replace, key, value, ttl)
+    }
+
+    pub fn delete(key: Arc<[u8]>) -> Self {
+        Self::new(Opcode::Delete, Extras::None, Some(key), None)
+    }
+
+    pub fn increment(key: Arc<[u8]>, delta: u64) -> Self {
+        Self::arithmetic(Opcode::Increment, key, delta)
+    }
+
+    pub fn decrement(key: Arc<[u8]>, delta: u64) -> Self {
+        Self::arithmetic(Opcode::Decrement, key, delta)
+    }
+
+    /// SASL authentication using the `PLAIN` mechanism.
+    pub fn sasl_plain(username: &str, password: &str) -> Self {
+        let mut value = Vec::with_capacity(username.len() + password.len() + 2);
+        value.push(0);
+        value.extend_from_slice(username.as_bytes());
+        value.push(0);
+        value.extend_from_slice(password.as_bytes());
+
+        Self::new(
+            Opcode::SaslAuth,
+            Extras::None,
+            Some(Arc::from(&b"PLAIN"[..])),
+            Some(value),
+        )
+    }
+
+    fn storage(opcode: Opcode, key: Arc<[u8]>, value: Vec<u8>, ttl: u32) -> Self {
+        let extras = Extras::Storage {
+            flags: 0,
+            expiry: ttl,
+        };
+
+        Self::new(opcode, extras, Some(key), Some(value))
+    }
+
+    fn arithmetic(opcode: Opcode, key: Arc<[u8]>, delta: u64) -> Self {
+        let extras = Extras::Arithmetic {
+            delta,
+            initial: 0,
+            expiry: NO_CREATE,
+        };
+
+        Self::new(opcode, extras, Some(key), None)
+    }
+
+    fn new(opcode: Opcode, extras: Extras, key: Option<Arc<[u8]>>, value: Option<Vec<u8>>) -> Self {
+        Self {
+            opcode,
+            extras,
+            key,
+            value,
+            opaque: 0,
+            quiet: false,
+        }
+    }
+
+    /// Uses the quiet variant of the request, if there is one. The server
+    /// suppresses the common response, so a `NOOP` with the same opaque is
+    /// sent after the request. The response to the `NOOP` marks the end of the
+    /// response for the request.
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet && self.opcode.quiet().is_some();
+        self
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    pub fn set_opaque(&mut self, opaque: u32) {
+        self.opaque = opaque;
+    }
+
+    pub fn opaque(&self) -> u32 {
+        self.opaque
+    }
+
+    /// Writes the request into the buffer and returns the number of bytes
+    /// written.
+    pub fn compose<B: BufMut>(&self, dst: &mut B) -> usize {
+        let opcode = if self.quiet {
+            self.opcode.quiet().unwrap_or(self.opcode)
+        } else {
+            self.opcode
+        };
+
+        let extras_len = match self.extras {
+            Extras::None => 0,
+            Extras::Storage { .. } => 8,
+            Extras::Arithmetic { .. } => 20,
+        };
+
+        let key_len = self.key.as_ref().map(|k| k.len()).unwrap_or(0);
+        let value_len = self.value.as_ref().map(|v| v.len()).unwrap_or(0);
+        let body_len = extras_len + key_len + value_len;
+
+        header(dst, opcode, key_len, extras_len, body_len, self.opaque);
+
+        match self.extras {
+            Extras::None => {}
+            Extras::Storage { flags, expiry } => {
+                dst.put_slice(&flags.to_be_bytes());
+                dst.put_slice(&expiry.to_be_bytes());
+            }
+            Extras::Arithmetic {
+                delta,
+                initial,
+                expiry,
+            } => {
+                dst.put_slice(&delta.to_be_bytes());
+                dst.put_slice(&initial.to_be_bytes());
+                dst.put_slice(&expiry.to_be_bytes());
+            }
+        }
+
+        if let Some(key) = &self.key {
+            dst.put_slice(key);
+        }
+
+        if let Some(value) = &self.value {
+            dst.put_slice(value);
+        }
+
+        let mut len = HEADER_LEN + body_len;
+
+        if self.quiet {
+            header(dst, Opcode::NoOp, 0, 0, 0, self.opaque);
+            len += HEADER_LEN;
+        }
+
+        len
+    }
+}
+
+// Writes a request header. The data type, vbucket id, and CAS are always zero.
+fn header<B: BufMut>(
+    dst: &mut B,
+    opcode: Opcode,
+    key_len: usize,
+    extras_len: usize,
+    body_len: usize,
+    opaque: u32,
+) {
+    dst.put_slice(&[REQUEST_MAGIC, opcode as u8]);
+    dst.put_slice(&(key_len as u16).to_be_bytes());
+    dst.put_slice(&[extras_len as u8, 0]);
+    dst.put_slice(&0_u16.to_be_bytes());
+    dst.put_slice(&(body_len as u32).to_be_bytes());
+    dst.put_slice(&opaque.to_be_bytes());
+    dst.put_slice(&0_u64.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use session::Buffer;
+    use std::borrow::Borrow;
+
+    fn compose(request: &Request) -> Vec<u8> {
+        let mut buffer = Buffer::new(1024);
+        let len = request.compose(&mut buffer);
+
+        let bytes: &[u8] = buffer.borrow();
+        assert_eq!(len, bytes.len());
+
+        bytes.to_vec()
+    }
+
+    fn key() -> Arc<[u8]> {
+        Arc::from(&b"key"[..])
+    }
+
+    // The expected request header.
+    fn header(opcode: u8, key_len: u16, extras_len: u8, body_len: u32, opaque: u32) -> Vec<u8> {
+        let mut header = vec![REQUEST_MAGIC, opcode];
+        header.extend_from_slice(&key_len.to_be_bytes());
+        header.extend_from_slice(&[extras_len, 0, 0, 0]);
+        header.extend_from_slice(&body_len.to_be_bytes());
+        header.extend_from_slice(&opaque.to_be_bytes());
+        header.extend_from_slice(&[0; 8]);
+        header
+    }
+
+    #[test]
+    fn get() {
+        let mut request = Request::get(key());
+        request.set_opaque(7);
+
+        let mut expected = header(0x00, 3, 0, 3, 7);
+        expected.extend_from_slice(b"key");
+
+        assert_eq!(expected.len(), HEADER_LEN + 3);
+        assert_eq!(compose(&request), expected);
+    }
+
+    #[test]
+    fn storage() {
+        let request = Request::add(key(), b"value".to_vec(), 3600);
+
+        let mut expected = header(0x02, 3, 8, 16, 0);
+        expected.extend_from_slice(&0_u32.to_be_bytes());
+        expected.extend_from_slice(&3600_u32.to_be_bytes());
+        expected.extend_from_slice(b"key");
+        expected.extend_from_slice(b"value");
+
+        assert_eq!(compose(&request), expected);
+    }
+
+    // increment and decrement fail on a miss instead of creating the key
+    #[test]
+    fn arithmetic() {
+        let request = Request::decrement(key(), 2);
+
+        let mut expected = header(0x06, 3, 20, 23, 0);
+        expected.extend_from_slice(&2_u64.to_be_bytes());
+        expected.extend_from_slice(&0_u64.to_be_bytes());
+        expected.extend_from_slice(&[0xff; 4]);
+        expected.extend_from_slice(b"key");
+
+        assert_eq!(compose(&request), expected);
+    }
+
+    // a quiet request is sent with the quiet opcode and is followed by a
+    // `NOOP` with the same opaque
+    #[test]
+    fn quiet() {
+        let mut request = Request::delete(key()).quiet(true);
+        request.set_opaque(42);
+
+        assert!(request.is_quiet());
+
+        let mut expected = header(0x14, 3, 0, 3, 42);
+        expected.extend_from_slice(b"key");
+        expected.extend_from_slice(&header(0x0a, 0, 0, 0, 42));
+
+        assert_eq!(compose(&request), expected);
+    }
+
+    #[test]
+    fn sasl_plain() {
+        let request = Request::sasl_plain("user", "secret").quiet(true);
+
+        // there is no quiet variant of an authentication request
+        assert!(!request.is_quiet());
+
+        let mut expected = header(0x21, 5, 0, 17, 0);
+        expected.extend_from_slice(b"PLAIN");
+        expected.extend_from_slice(b"\0user\0secret");
+
+        assert_eq!(compose(&request), expected);
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Opcode, ParseOk, HEADER_LEN, RESPONSE_MAGIC};
+use std::io::{Error, ErrorKind};
+
+/// A binary protocol response. The body is skipped as only the status is of
+/// interest to the client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    opcode: u8,
+    status: Status,
+    opaque: u32,
+}
+
+/// The status of a response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    NoError,
+    KeyNotFound,
+    KeyExists,
+    ValueTooLarge,
+    InvalidArguments,
+    NotStored,
+    NonNumeric,
+    AuthError,
+    AuthContinue,
+    UnknownCommand,
+    OutOfMemory,
+    Other(u16),
+}
+
+impl From<u16> for Status {
+    fn from(other: u16) -> Self {
+        match other {
+            0x0000 => Self::NoError,
+            0x0001 => Self::KeyNotFound,
+            0x0002 => Self::KeyExists,
+            0x0003 => Self::ValueTooLarge,
+            0x0004 => Self::InvalidArguments,
+            0x0005 => Self::NotStored,
+            0x0006 => Self::NonNumeric,
+            0x0020 => Self::AuthError,
+            0x0021 => Self::AuthContinue,
+            0x0081 => Self::UnknownCommand,
+            0x0082 => Self::OutOfMemory,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl Response {
+    /// A response which is used to record an exception for a request which
+    /// will not receive a response.
+    pub fn error() -> Self {
+        Self {
+            opcode: Opcode::NoOp as u8,
+            status: Status::Other(u16::MAX),
+            opaque: 0,
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn opaque(&self) -> u32 {
+        self.opaque
+    }
+
+    /// Returns true if this is the response to a `NOOP`. For a quiet request,
+    /// a successful `NOOP` without a preceding response indicates that the
+    /// common response was suppressed.
+    pub fn is_noop(&self) -> bool {
+        self.opcode == Opcode::NoOp as u8 && self.status == Status::NoError
+    }
+}
+
+/// An incremental parser for binary protocol responses. Returns an error with
+/// the kind `WouldBlock` if the buffer does not yet contain a complete
+/// response.
+#[derive(Clone, Copy, Default)]
+pub struct ResponseParser {}
+
+impl ResponseParser {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn parse(&self, buffer: &[u8]) -> Result<ParseOk<Response>, Error> {
+        if buffer.len() < HEADER_LEN {
+            return Err(Error::from(ErrorKind::WouldBlock));
+        }
+
+        if buffer[0] != RESPONSE_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "bad magic"));
+        }
+
+        let opcode = buffer[1];
+        let status = u16::from_be_bytes([buffer[6], buffer[7]]);
+        let body_len = u32::from_be_bytes([buffer[8], buffer[9], buffer[10], buffer[11]]);
+        let opaque = u32::from_be_bytes([buffer[12], buffer[13], buffer[14], buffer[15]]);
+
+        let consumed = HEADER_LEN + body_len as usize;
+
+        if buffer.len() < consumed {
+            return Err(Error::from(ErrorKind::WouldBlock));
+        }
+
+        let response = Response {
+            opcode,
+            status: Status::from(status),
+            opaque,
+        };
+
+        Ok(ParseOk::new(response, consumed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A response with the header and a body of the given length.
+    fn encode(opcode: u8, status: u16, body_len: u32, opaque: u32) -> Vec<u8> {
+        let mut buffer = vec![RESPONSE_MAGIC, opcode, 0, 0, 0, 0];
+        buffer.extend_from_slice(&status.to_be_bytes());
+        buffer.extend_from_slice(&body_len.to_be_bytes());
+        buffer.extend_from_slice(&opaque.to_be_bytes());
+        buffer.extend_from_slice(&[0; 8]);
+        buffer.resize(HEADER_LEN + body_len as usize, b'x');
+        buffer
+    }
+
+    fn parse(buffer: &[u8]) -> Result<(Response, usize), Error> {
+        let ok = ResponseParser::new().parse(buffer)?;
+        let consumed = ok.consumed();
+
+        Ok((ok.into_inner(), consumed))
+    }
+
+    #[test]
+    fn framing() {
+        let buffer = encode(Opcode::Get as u8, 0, 12, 9);
+
+        // the header and body must both be received
+        for len in 0..buffer.len() {
+            let e = parse(&buffer[..len]).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::WouldBlock, "prefix of {len} bytes");
+        }
+
+        let (response, consumed) = parse(&buffer).unwrap();
+
+        assert_eq!(consumed, HEADER_LEN + 12);
+        assert_eq!(response.status(), Status::NoError);
+        assert_eq!(response.opaque(), 9);
+        assert!(!response.is_noop());
+    }
+
+    // the body of one response is skipped and the next is left in the buffer
+    #[test]
+    fn pipelined() {
+        let mut buffer = encode(Opcode::GetQ as u8, 0, 5, 1);
+        buffer.extend_from_slice(&encode(Opcode::NoOp as u8, 0, 0, 1));
+
+        let (first, consumed) = parse(&buffer).unwrap();
+        assert_eq!(first.opaque(), 1);
+        assert!(!first.is_noop());
+
+        let (second, n) = parse(&buffer[consumed..]).unwrap();
+        assert!(second.is_noop());
+        assert_eq!(consumed + n, buffer.len());
+    }
+
+    #[test]
+    fn status() {
+        for (status, expected) in [
+            (0x0001, Status::KeyNotFound),
+            (0x0002, Status::KeyExists),
+            (0x0005, Status::NotStored),
+            (0x0006, Status::NonNumeric),
+            (0x0020, Status::AuthError),
+            (0x0081, Status::UnknownCommand),
+            (0x1234, Status::Other(0x1234)),
+        ] {
+            let (response, _) = parse(&encode(Opcode::Set as u8, status, 0, 0)).unwrap();
+            assert_eq!(response.status(), expected);
+        }
+
+        // a failed `NOOP` does not complete a quiet request
+        let (response, _) = parse(&encode(Opcode::NoOp as u8, 0x0081, 0, 0)).unwrap();
+        assert!(!response.is_noop());
+        assert!(!Response::error().is_noop());
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut buffer = encode(Opcode::Get as u8, 0, 0, 0);
+        buffer[0] = REQUEST_MAGIC;
+
+        assert_eq!(parse(&buffer).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
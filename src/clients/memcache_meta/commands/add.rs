@@ -0,0 +1,39 @@
+/// Adds a key-value pair to the cache if it does not already exist, using `ms` with the `ME` mode.
+use super::*;
+
+pub fn add(options: &Options, other: &workload::client::Add) -> RequestWithValidator {
+    ADD.increment();
+
+    let mut request = Request::set(other.key.clone(), other.value.clone())
+        .flag(Flag::Mode(b'E'))
+        .quiet(options.quiet);
+
+    if let Some(ttl) = ttl(other.ttl) {
+        request = request.flag(ttl);
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &ADD_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Header(_) | Response::NoOp => {
+            ADD_STORED.increment();
+            Ok(())
+        }
+        Response::NotStored(_) => {
+            ADD_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            ADD_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// Decrements the value for a key using `ma` with the `MD` mode.
+use super::*;
+
+pub fn decrement(options: &Options, other: &workload::client::Decrement) -> RequestWithValidator {
+    DECR.increment();
+
+    let request = Request::arithmetic(other.key.clone())
+        .flag(Flag::Mode(b'D'))
+        .flag(Flag::Numeric(b'D', other.amount))
+        .flags(&options.arithmetic_flags)
+        .quiet(options.quiet);
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &DECR_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful update is suppressed and is indicated by a bare
+/// `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Value(_) | Response::Header(_) | Response::NoOp => {
+            DECR_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            DECR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            DECR_EX.increment();
+            Err(())
+        }
+    }
+}
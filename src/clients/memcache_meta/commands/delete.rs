@@ -0,0 +1,45 @@
+/// Removes a key from the cache using `md`.
+use super::*;
+
+pub fn delete(options: &Options, other: &workload::client::Delete) -> RequestWithValidator {
+    DELETE.increment();
+
+    let mut request = Request::delete(other.key.clone()).quiet(options.quiet);
+
+    // with stale-while-revalidate the item is marked as stale instead of being
+    // removed, so that the next reader wins the right to recache it
+    if let Some(threshold) = options.stale_while_revalidate {
+        request = request
+            .flag(Flag::Bare(b'I'))
+            .flag(Flag::Numeric(b'T', threshold));
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &DELETE_TIMEOUT,
+    }
+}
+
+/// In quiet mode both a successful delete and a miss are suppressed, so a bare
+/// `MN` is counted separately as the key was either deleted or not found.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Header(_) => {
+            DELETE_DELETED.increment();
+            Ok(())
+        }
+        Response::NoOp => {
+            DELETE_QUIET.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            DELETE_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            DELETE_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,53 @@
+/// Retrieve the value of a key in the cache using `mg`.
+use super::*;
+
+pub fn get(options: &Options, other: &workload::client::Get) -> RequestWithValidator {
+    GET.increment();
+
+    let mut request = Request::get(other.key.clone())
+        .flags(&options.flags)
+        .quiet(options.quiet);
+
+    // ask to win the right to recache the item once its remaining TTL falls
+    // below the threshold
+    if let Some(threshold) = options.stale_while_revalidate {
+        request = request.flag(Flag::Numeric(b'R', threshold));
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &GET_TIMEOUT,
+    }
+}
+
+/// In quiet mode a miss is suppressed and is indicated by a bare `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Value(flags) | Response::Header(flags) => {
+            if flags.contains(b'W') {
+                MEMCACHE_META_WIN.increment();
+            }
+            if flags.contains(b'X') {
+                MEMCACHE_META_STALE.increment();
+            }
+            if flags.contains(b'Z') {
+                MEMCACHE_META_WIN_SENT.increment();
+            }
+            RESPONSE_HIT.increment();
+            GET_KEY_HIT.increment();
+            GET_OK.increment();
+            Ok(())
+        }
+        Response::Miss | Response::NoOp => {
+            RESPONSE_MISS.increment();
+            GET_KEY_MISS.increment();
+            GET_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GET_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,49 @@
+/// Retrieve the value for a key and update its TTL using `mg` with the `v` and
+/// `T` flags.
+use super::*;
+
+pub fn get_and_touch(
+    options: &Options,
+    other: &workload::client::GetAndTouch,
+) -> RequestWithValidator {
+    GAT.increment();
+
+    let mut request = Request::get(other.key.clone())
+        .flags(&options.flags)
+        .flag(ttl(other.ttl).unwrap_or(Flag::Numeric(b'T', 0)))
+        .quiet(options.quiet);
+
+    // the value is always returned, even if it is not one of the configured
+    // flags
+    if !options.flags.contains(&Flag::Bare(b'v')) {
+        request = request.flag(Flag::Bare(b'v'));
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &GAT_TIMEOUT,
+    }
+}
+
+/// In quiet mode a miss is suppressed and is indicated by a bare `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Value(_) => {
+            RESPONSE_HIT.increment();
+            GAT_HIT.increment();
+            GAT_OK.increment();
+            Ok(())
+        }
+        Response::Miss | Response::NoOp => {
+            RESPONSE_MISS.increment();
+            GAT_MISS.increment();
+            GAT_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GAT_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// Increments the value for a key using `ma` with the `MI` mode.
+use super::*;
+
+pub fn increment(options: &Options, other: &workload::client::Increment) -> RequestWithValidator {
+    INCR.increment();
+
+    let request = Request::arithmetic(other.key.clone())
+        .flag(Flag::Mode(b'I'))
+        .flag(Flag::Numeric(b'D', other.amount))
+        .flags(&options.arithmetic_flags)
+        .quiet(options.quiet);
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &INCR_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful update is suppressed and is indicated by a bare
+/// `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Value(_) | Response::Header(_) | Response::NoOp => {
+            INCR_OK.increment();
+            Ok(())
+        }
+        Response::NotFound(_) => {
+            INCR_NOT_FOUND.increment();
+            Ok(())
+        }
+        _ => {
+            INCR_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+mod add;
+mod decrement;
+mod delete;
+mod get;
+mod get_and_touch;
+mod increment;
+mod replace;
+mod set;
+mod touch;
+
+pub use add::add;
+pub use decrement::decrement;
+pub use delete::delete;
+pub use get::get;
+pub use get_and_touch::get_and_touch;
+pub use increment::increment;
+pub use replace::replace;
+pub use set::set;
+pub use touch::touch;
+
+// Returns the TTL flag for a request, if the request has a TTL. A TTL of more
+// than 30 days is sent as the time at which the item expires.
+fn ttl(ttl: Option<Duration>) -> Option<Flag> {
+    ttl.map(|ttl| Flag::Numeric(b'T', memcache_exptime(ttl) as u64))
+}
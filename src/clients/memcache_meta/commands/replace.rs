@@ -0,0 +1,39 @@
+/// Replaces the value for a key in the cache if it already exists, using `ms` with the `MR` mode.
+use super::*;
+
+pub fn replace(options: &Options, other: &workload::client::Replace) -> RequestWithValidator {
+    REPLACE.increment();
+
+    let mut request = Request::set(other.key.clone(), other.value.clone())
+        .flag(Flag::Mode(b'R'))
+        .quiet(options.quiet);
+
+    if let Some(ttl) = ttl(other.ttl) {
+        request = request.flag(ttl);
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &REPLACE_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Header(_) | Response::NoOp => {
+            REPLACE_STORED.increment();
+            Ok(())
+        }
+        Response::NotStored(_) => {
+            REPLACE_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            REPLACE_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,39 @@
+/// Sets a key-value pair in the cache, using `ms` with the `MS` mode.
+use super::*;
+
+pub fn set(options: &Options, other: &workload::client::Set) -> RequestWithValidator {
+    SET.increment();
+
+    let mut request = Request::set(other.key.clone(), other.value.clone())
+        .flag(Flag::Mode(b'S'))
+        .quiet(options.quiet);
+
+    if let Some(ttl) = ttl(other.ttl) {
+        request = request.flag(ttl);
+    }
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &SET_TIMEOUT,
+    }
+}
+
+/// In quiet mode a successful store is suppressed and is indicated by a bare
+/// `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Header(_) | Response::NoOp => {
+            SET_STORED.increment();
+            Ok(())
+        }
+        Response::NotStored(_) => {
+            SET_NOT_STORED.increment();
+            Ok(())
+        }
+        _ => {
+            SET_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/// Update the TTL for a key using `mg` with the `T` flag. No value is returned.
+use super::*;
+
+pub fn touch(options: &Options, other: &workload::client::Touch) -> RequestWithValidator {
+    TOUCH.increment();
+
+    let request = Request::get(other.key.clone())
+        .flag(ttl(other.ttl).unwrap_or(Flag::Numeric(b'T', 0)))
+        .quiet(options.quiet);
+
+    RequestWithValidator {
+        request,
+        validator: Box::new(validate_response),
+        timeout: &TOUCH_TIMEOUT,
+    }
+}
+
+/// In quiet mode a miss is suppressed and is indicated by a bare `MN`.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Header(_) => {
+            RESPONSE_HIT.increment();
+            TOUCH_HIT.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        Response::Miss | Response::NoOp => {
+            RESPONSE_MISS.increment();
+            TOUCH_MISS.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        _ => {
+            TOUCH_EX.increment();
+            Err(())
+        }
+    }
+}
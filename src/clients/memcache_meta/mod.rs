@@ -0,0 +1,208 @@
+use super::pipeline::{self, Codec, Connection, Inflight, Received};
+use super::*;
+use session::{Buf, Buffer};
+use std::borrow::Borrow;
+
+mod commands;
+mod protocol;
+
+use protocol::{Flag, Request, Response, ResponseParser};
+
+struct RequestWithValidator {
+    request: Request,
+    validator: Box<dyn Fn(Response) -> std::result::Result<(), ()> + Send>,
+    timeout: &'static metriken::Counter,
+}
+
+/// Options for composing meta requests, these are derived from the client
+/// config once per task.
+struct Options {
+    /// return flags for `mg`
+    flags: Vec<Flag>,
+    /// return flags for `ma`, which does not support all of the `mg` flags
+    arithmetic_flags: Vec<Flag>,
+    quiet: bool,
+    stale_while_revalidate: Option<u64>,
+}
+
+impl Options {
+    fn new(config: &Config) -> Self {
+        // we would not be creating a memcache client task if we didn't have a
+        // client config, so this unwrap will succeed.
+        let client_config = config.client().unwrap();
+
+        let flags: Vec<Flag> = client_config
+            .memcache_meta_flags()
+            .iter()
+            .map(|flag| match flag {
+                MemcacheMetaFlag::Value => Flag::Bare(b'v'),
+                MemcacheMetaFlag::Ttl => Flag::Bare(b't'),
+                MemcacheMetaFlag::Cas => Flag::Bare(b'c'),
+                MemcacheMetaFlag::Key => Flag::Bare(b'k'),
+                MemcacheMetaFlag::Size => Flag::Bare(b's'),
+            })
+            .collect();
+
+        let arithmetic_flags = flags
+            .iter()
+            .filter(|flag| **flag != Flag::Bare(b's'))
+            .copied()
+            .collect();
+
+        Self {
+            flags,
+            arithmetic_flags,
+            quiet: client_config.memcache_meta_quiet(),
+            stale_while_revalidate: client_config.memcache_meta_stale_while_revalidate(),
+        }
+    }
+}
+
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[
+        Verb::Add,
+        Verb::Get,
+        Verb::Set,
+        Verb::Delete,
+        Verb::Replace,
+        Verb::Increment,
+        Verb::Decrement,
+        Verb::Touch,
+        Verb::GetAndTouch,
+    ],
+    multi_get: false,
+};
+
+/// Launch tasks with one conncetion per task as memcache protocol is not mux-enabled.
+pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    debug!("launching memcache meta protocol tasks");
+
+    // create one task per connection
+    for _ in 0..config.client().unwrap().poolsize() {
+        for endpoint in config.target().endpoints() {
+            runtime.spawn(pipeline::task(
+                MetaCodec::new(&config),
+                work_receiver.clone(),
+                endpoint.clone(),
+                config.clone(),
+            ));
+        }
+    }
+}
+
+struct MetaCodec {
+    options: Options,
+    parser: ResponseParser,
+    // in quiet mode, holds a response to the oldest request until the `MN`
+    // which marks the end of its responses is received
+    pending: Option<Response>,
+}
+
+impl MetaCodec {
+    fn new(config: &Config) -> Self {
+        Self {
+            options: Options::new(config),
+            parser: ResponseParser::new(),
+            pending: None,
+        }
+    }
+}
+
+impl Codec for MetaCodec {
+    type Request = RequestWithValidator;
+
+    const CLOSE_ON_EXCEPTION: bool = true;
+
+    async fn connect(&mut self, _connection: &mut Connection) -> Result<()> {
+        self.pending = None;
+
+        Ok(())
+    }
+
+    fn request(&mut self, request: ClientRequest) -> std::result::Result<Self::Request, ()> {
+        RequestWithValidator::new(&self.options, &request)
+    }
+
+    fn compose(&mut self, request: &mut Self::Request, buffer: &mut Buffer) {
+        request.request.compose(buffer);
+    }
+
+    fn receive(
+        &mut self,
+        request: &mut Self::Request,
+        buffer: &mut Buffer,
+    ) -> Result<Received<Self::Request>> {
+        let response = self.parser.parse((*buffer).borrow())?;
+        let consumed = response.consumed();
+        let response = response.into_inner();
+
+        buffer.advance(consumed);
+
+        // a quiet request is complete once the `MN` is received, which may or
+        // may not follow a response to the request
+        let response = if request.request.is_quiet() {
+            match response {
+                Response::NoOp => self.pending.take().unwrap_or(Response::NoOp),
+                response if self.pending.is_none() => {
+                    self.pending = Some(response);
+                    return Ok(Received::Pending);
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "more than one response for a request",
+                    ));
+                }
+            }
+        } else {
+            response
+        };
+
+        Ok(Received::Complete((request.validator)(response), None))
+    }
+}
+
+impl Inflight for RequestWithValidator {
+    fn exception(self) {
+        // use validate response to record the exception
+        let _ = (self.validator)(Response::Error(Vec::new()));
+    }
+
+    fn timeout(&self) -> &'static metriken::Counter {
+        self.timeout
+    }
+}
+
+impl RequestWithValidator {
+    fn new(options: &Options, request: &ClientRequest) -> std::result::Result<Self, ()> {
+        match request {
+            ClientRequest::Add(r) => Ok(commands::add(options, r)),
+            ClientRequest::Get(r) => Ok(commands::get(options, r)),
+            ClientRequest::GetAndTouch(r) => Ok(commands::get_and_touch(options, r)),
+            ClientRequest::Delete(r) => Ok(commands::delete(options, r)),
+            ClientRequest::Replace(r) => Ok(commands::replace(options, r)),
+            ClientRequest::Set(r) => Ok(commands::set(options, r)),
+            ClientRequest::Touch(r) => Ok(commands::touch(options, r)),
+            ClientRequest::Increment(r) => Ok(commands::increment(options, r)),
+            ClientRequest::Decrement(r) => Ok(commands::decrement(options, r)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = clients::tests::config(dir.path(), Protocol::MemcacheMeta);
+        let options = Options::new(&config);
+
+        for request in clients::tests::requests(&config) {
+            assert!(RequestWithValidator::new(&options, &request).is_ok());
+        }
+    }
+}
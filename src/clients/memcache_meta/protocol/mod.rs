@@ -0,0 +1,30 @@
+// An implementation of the memcache meta protocol which operates directly on
+// byte buffers. Only the subset of commands which are used by the client are
+// supported: `mg`, `ms`, `md`, `ma`, and `mn`.
+
+mod request;
+mod response;
+
+pub use request::{Flag, Request};
+pub use response::{Response, ResponseParser};
+
+/// The result of a successful parse. Contains the parsed item and the number
+/// of bytes which were consumed from the buffer.
+pub struct ParseOk<T> {
+    inner: T,
+    consumed: usize,
+}
+
+impl<T> ParseOk<T> {
+    pub fn new(inner: T, consumed: usize) -> Self {
+        Self { inner, consumed }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+}
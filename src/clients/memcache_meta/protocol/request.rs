@@ -0,0 +1,219 @@
+use session::BufMut;
+use std::io::Write;
+use std::sync::Arc;
+
+/// A meta request. Each request operates on a single key and may carry a set
+/// of flags which modify its behavior and what is returned by the server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    command: &'static [u8],
+    key: Arc<[u8]>,
+    value: Option<Vec<u8>>,
+    flags: Vec<Flag>,
+    quiet: bool,
+}
+
+/// A flag for a meta request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flag {
+    /// A flag without a token, eg: `v` to return the value
+    Bare(u8),
+    /// A flag with a numeric token, eg: `T30` to set a TTL
+    Numeric(u8, u64),
+    /// The mode switch, eg: `ME` to use add semantics for `ms`
+    Mode(u8),
+}
+
+impl Request {
+    /// Meta get, `mg <key> <flags>*`
+    pub fn get(key: Arc<[u8]>) -> Self {
+        Self::new(b"mg", key, None)
+    }
+
+    /// Meta set, `ms <key> <datalen> <flags>*` followed by the data
+    pub fn set(key: Arc<[u8]>, value: Vec<u8>) -> Self {
+        Self::new(b"ms", key, Some(value))
+    }
+
+    /// Meta delete, `md <key> <flags>*`
+    pub fn delete(key: Arc<[u8]>) -> Self {
+        Self::new(b"md", key, None)
+    }
+
+    /// Meta arithmetic, `ma <key> <flags>*`
+    pub fn arithmetic(key: Arc<[u8]>) -> Self {
+        Self::new(b"ma", key, None)
+    }
+
+    fn new(command: &'static [u8], key: Arc<[u8]>, value: Option<Vec<u8>>) -> Self {
+        Self {
+            command,
+            key,
+            value,
+            flags: Vec::new(),
+            quiet: false,
+        }
+    }
+
+    /// Appends a flag to the request.
+    pub fn flag(mut self, flag: Flag) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    /// Appends a sequence of flags to the request.
+    pub fn flags(mut self, flags: &[Flag]) -> Self {
+        self.flags.extend_from_slice(flags);
+        self
+    }
+
+    /// Uses quiet mode for this request. The server suppresses the common
+    /// response, so a `mn` is sent after the request. The response to the
+    /// `mn` marks the end of the response for the request.
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Writes the request into the buffer and returns the number of bytes
+    /// written.
+    pub fn compose<B: BufMut>(&self, dst: &mut B) -> usize {
+        let mut len = self.command.len() + self.key.len() + 3;
+
+        dst.put_slice(self.command);
+        dst.put_slice(b" ");
+        dst.put_slice(&self.key);
+
+        if let Some(value) = &self.value {
+            len += token(dst, b' ', None, value.len() as u64);
+        }
+
+        for flag in &self.flags {
+            len += match flag {
+                Flag::Bare(f) => {
+                    dst.put_slice(&[b' ', *f]);
+                    2
+                }
+                Flag::Numeric(f, v) => token(dst, b' ', Some(*f), *v),
+                Flag::Mode(m) => {
+                    dst.put_slice(&[b' ', b'M', *m]);
+                    3
+                }
+            };
+        }
+
+        if self.quiet {
+            dst.put_slice(b" q");
+            len += 2;
+        }
+
+        dst.put_slice(b"\r\n");
+
+        if let Some(value) = &self.value {
+            dst.put_slice(value);
+            dst.put_slice(b"\r\n");
+            len += value.len() + 2;
+        }
+
+        if self.quiet {
+            dst.put_slice(b"mn\r\n");
+            len += 4;
+        }
+
+        len
+    }
+}
+
+// Writes a separator followed by an optional flag and a number, formatting the
+// number on the stack. Returns the number of bytes written.
+fn token<B: BufMut>(dst: &mut B, separator: u8, flag: Option<u8>, value: u64) -> usize {
+    let mut buffer = [0; 20];
+    let mut cursor: &mut [u8] = &mut buffer[..];
+    let _ = write!(cursor, "{value}");
+    let remaining = cursor.len();
+    let number = &buffer[..(20 - remaining)];
+
+    dst.put_slice(&[separator]);
+
+    if let Some(flag) = flag {
+        dst.put_slice(&[flag]);
+    }
+
+    dst.put_slice(number);
+
+    1 + flag.is_some() as usize + number.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use session::Buffer;
+    use std::borrow::Borrow;
+
+    fn compose(request: &Request) -> Vec<u8> {
+        let mut buffer = Buffer::new(1024);
+        let len = request.compose(&mut buffer);
+
+        let bytes: &[u8] = buffer.borrow();
+        assert_eq!(len, bytes.len());
+
+        bytes.to_vec()
+    }
+
+    fn key() -> Arc<[u8]> {
+        Arc::from(&b"key"[..])
+    }
+
+    #[test]
+    fn get() {
+        assert_eq!(compose(&Request::get(key())), b"mg key\r\n");
+
+        let request = Request::get(key())
+            .flags(&[Flag::Bare(b'v'), Flag::Bare(b'f')])
+            .flag(Flag::Numeric(b'R', 30));
+
+        assert_eq!(compose(&request), b"mg key v f R30\r\n");
+    }
+
+    #[test]
+    fn set() {
+        let request = Request::set(key(), b"value".to_vec())
+            .flag(Flag::Numeric(b'T', 3600))
+            .flag(Flag::Mode(b'E'));
+
+        assert_eq!(compose(&request), b"ms key 5 T3600 ME\r\nvalue\r\n");
+
+        // the length of the data is used, so it may contain a CRLF
+        let request = Request::set(key(), b"\r\n".to_vec());
+
+        assert_eq!(compose(&request), b"ms key 2\r\n\r\n\r\n");
+    }
+
+    #[test]
+    fn arithmetic() {
+        let request = Request::arithmetic(key())
+            .flag(Flag::Mode(b'D'))
+            .flag(Flag::Numeric(b'D', u64::MAX));
+
+        assert_eq!(compose(&request), b"ma key MD D18446744073709551615\r\n");
+    }
+
+    // a quiet request is followed by a `mn`, after any data
+    #[test]
+    fn quiet() {
+        let request = Request::delete(key()).quiet(true);
+
+        assert!(request.is_quiet());
+        assert_eq!(compose(&request), b"md key q\r\nmn\r\n");
+
+        let request = Request::set(key(), b"hi".to_vec())
+            .flag(Flag::Numeric(b'T', 1))
+            .quiet(true);
+
+        assert_eq!(compose(&request), b"ms key 2 T1 q\r\nhi\r\nmn\r\n");
+    }
+}
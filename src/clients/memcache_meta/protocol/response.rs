@@ -0,0 +1,237 @@
+use super::ParseOk;
+use std::io::{Error, ErrorKind};
+
+/// A response to a meta request. The data of a value response is skipped as
+/// only the flags returned with it are of interest to the client.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response {
+    /// `VA <size> <flags>*\r\n<data>\r\n`
+    Value(Flags),
+    /// `HD <flags>*\r\n`
+    Header(Flags),
+    /// `EN\r\n`
+    Miss,
+    /// `NS <flags>*\r\n`
+    NotStored(Flags),
+    /// `EX <flags>*\r\n`
+    Exists(Flags),
+    /// `NF <flags>*\r\n`
+    NotFound(Flags),
+    /// `MN\r\n`, marks the end of a pipeline of quiet requests
+    NoOp,
+    /// `ERROR`, `CLIENT_ERROR <message>`, or `SERVER_ERROR <message>`
+    Error(Vec<u8>),
+}
+
+/// The return flags of a response. Each flag is a single byte which may be
+/// followed by a token, eg: `t30` or `W`. Only the flags are retained.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flags {
+    inner: Vec<u8>,
+}
+
+impl Flags {
+    /// Returns true if the flag is present in the response.
+    pub fn contains(&self, flag: u8) -> bool {
+        self.inner.contains(&flag)
+    }
+}
+
+/// An incremental parser for meta responses. Returns an error with the kind
+/// `WouldBlock` if the buffer does not yet contain a complete response.
+#[derive(Clone, Copy, Default)]
+pub struct ResponseParser {}
+
+impl ResponseParser {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn parse(&self, buffer: &[u8]) -> Result<ParseOk<Response>, Error> {
+        let (line, mut consumed) = line(buffer)?;
+
+        let mut tokens = line.split(|b| *b == b' ').filter(|t| !t.is_empty());
+
+        let code = tokens.next().ok_or_else(|| invalid("empty response"))?;
+
+        let response = match code {
+            b"VA" => {
+                let size: usize = tokens
+                    .next()
+                    .and_then(|t| std::str::from_utf8(t).ok())
+                    .and_then(|t| t.parse().ok())
+                    .ok_or_else(|| invalid("bad value size"))?;
+
+                let flags = flags(tokens);
+
+                if buffer.len() < consumed + size + 2 {
+                    return Err(incomplete());
+                }
+
+                if &buffer[(consumed + size)..(consumed + size + 2)] != b"\r\n" {
+                    return Err(invalid("value is not terminated"));
+                }
+
+                consumed += size + 2;
+
+                Response::Value(flags)
+            }
+            b"HD" => Response::Header(flags(tokens)),
+            b"EN" => Response::Miss,
+            b"NS" => Response::NotStored(flags(tokens)),
+            b"EX" => Response::Exists(flags(tokens)),
+            b"NF" => Response::NotFound(flags(tokens)),
+            b"MN" => Response::NoOp,
+            b"ERROR" | b"CLIENT_ERROR" | b"SERVER_ERROR" => Response::Error(line.to_vec()),
+            _ => {
+                return Err(invalid("unknown response code"));
+            }
+        };
+
+        Ok(ParseOk::new(response, consumed))
+    }
+}
+
+fn flags<'a>(tokens: impl Iterator<Item = &'a [u8]>) -> Flags {
+    Flags {
+        inner: tokens.map(|t| t[0]).collect(),
+    }
+}
+
+fn incomplete() -> Error {
+    Error::from(ErrorKind::WouldBlock)
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+// Returns the content of the first line in the buffer without the trailing
+// CRLF along with the number of bytes consumed, including the CRLF.
+fn line(buffer: &[u8]) -> Result<(&[u8], usize), Error> {
+    match buffer.windows(2).position(|w| w == b"\r\n") {
+        Some(end) => Ok((&buffer[..end], end + 2)),
+        None => Err(incomplete()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(buffer: &[u8]) -> Result<(Response, usize), Error> {
+        let ok = ResponseParser::new().parse(buffer)?;
+        let consumed = ok.consumed();
+
+        Ok((ok.into_inner(), consumed))
+    }
+
+    fn flags(flags: &[u8]) -> Flags {
+        Flags {
+            inner: flags.to_vec(),
+        }
+    }
+
+    // Every prefix of a complete response is incomplete, and the complete
+    // response consumes the whole buffer.
+    fn check(buffer: &[u8], expected: Response) {
+        for len in 0..buffer.len() {
+            let e = parse(&buffer[..len]).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::WouldBlock, "prefix of {len} bytes");
+        }
+
+        assert_eq!(parse(buffer).unwrap(), (expected, buffer.len()));
+    }
+
+    #[test]
+    fn value() {
+        check(b"VA 5\r\nhello\r\n", Response::Value(Flags::default()));
+        check(b"VA 0 f0\r\n\r\n", Response::Value(flags(b"f")));
+
+        // the size is used to skip the data, which may contain a CRLF
+        check(b"VA 4 t30 W\r\n\r\n\r\n\r\n", Response::Value(flags(b"tW")));
+    }
+
+    #[test]
+    fn value_flags() {
+        let (response, _) = parse(b"VA 1 t-1 W Z X\r\nx\r\n").unwrap();
+
+        match response {
+            Response::Value(flags) => {
+                assert!(flags.contains(b'W'));
+                assert!(flags.contains(b'Z'));
+                assert!(flags.contains(b'X'));
+                assert!(flags.contains(b't'));
+                assert!(!flags.contains(b'-'));
+                assert!(!flags.contains(b'1'));
+            }
+            response => panic!("unexpected response: {response:?}"),
+        }
+    }
+
+    #[test]
+    fn status() {
+        check(b"HD\r\n", Response::Header(Flags::default()));
+        check(b"HD c123 k\r\n", Response::Header(flags(b"ck")));
+        check(b"EN\r\n", Response::Miss);
+        check(b"NS\r\n", Response::NotStored(Flags::default()));
+        check(b"EX c1\r\n", Response::Exists(flags(b"c")));
+        check(b"NF\r\n", Response::NotFound(Flags::default()));
+        check(b"MN\r\n", Response::NoOp);
+    }
+
+    #[test]
+    fn errors() {
+        check(b"ERROR\r\n", Response::Error(b"ERROR".to_vec()));
+        check(
+            b"CLIENT_ERROR bad command line format\r\n",
+            Response::Error(b"CLIENT_ERROR bad command line format".to_vec()),
+        );
+        check(
+            b"SERVER_ERROR out of memory\r\n",
+            Response::Error(b"SERVER_ERROR out of memory".to_vec()),
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        for buffer in [
+            &b"\r\n"[..],
+            b"XX\r\n",
+            b"VA\r\n",
+            b"VA abc\r\n",
+            b"VA 2\r\nabc\r\n",
+        ] {
+            let e = parse(buffer).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::InvalidData, "{buffer:?}");
+        }
+    }
+
+    // Responses for pipelined quiet requests. A quiet `mg` hit returns the
+    // value before the `MN`, a quiet `ms` which succeeds returns only the
+    // `MN`, and a quiet `md` which misses returns the `NF` before the `MN`.
+    #[test]
+    fn quiet_sequence() {
+        let buffer = b"VA 2 f\r\nhi\r\nMN\r\nMN\r\nNF\r\nMN\r\nEN";
+
+        let expected = [
+            Response::Value(flags(b"f")),
+            Response::NoOp,
+            Response::NoOp,
+            Response::NotFound(Flags::default()),
+            Response::NoOp,
+        ];
+
+        let mut consumed = 0;
+
+        for expected in expected {
+            let (response, n) = parse(&buffer[consumed..]).unwrap();
+            assert_eq!(response, expected);
+            consumed += n;
+        }
+
+        // the response to the next request has not been fully read yet
+        let e = parse(&buffer[consumed..]).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::WouldBlock);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::workload::ClientRequest;
 use crate::workload::ClientWorkItem as WorkItem;
+use crate::workload::Component;
 use crate::*;
 
 use ::momento::{MomentoError, MomentoErrorCode};
@@ -14,11 +15,174 @@ use std::time::Instant;
 mod http1;
 mod http2;
 mod memcache;
-mod momento;
+mod memcache_binary;
+mod memcache_meta;
+pub(crate) mod momento;
 mod ping;
-mod redis;
+mod pipeline;
+pub(crate) mod redis;
 
-pub fn launch_clients(config: &Config, work_receiver: Receiver<WorkItem>) -> Option<Runtime> {
+/// The verbs a client supports. This is used to reject workloads with
+/// commands the client would drop at runtime.
+pub struct Capabilities {
+    pub verbs: &'static [Verb],
+    /// Whether a `get` with a cardinality greater than one is supported.
+    pub multi_get: bool,
+}
+
+// the protocols which have a client for keyspaces, in the order they are listed
+const CLIENT_PROTOCOLS: &[(&str, Protocol)] = &[
+    ("http1", Protocol::Http1),
+    ("http2", Protocol::Http2),
+    ("memcache", Protocol::Memcache),
+    ("memcache_binary", Protocol::MemcacheBinary),
+    ("memcache_meta", Protocol::MemcacheMeta),
+    ("momento", Protocol::Momento),
+    ("ping", Protocol::Ping),
+    ("resp", Protocol::Resp),
+];
+
+/// The name of a protocol as it is written in the config.
+pub fn protocol_name(protocol: Protocol) -> String {
+    CLIENT_PROTOCOLS
+        .iter()
+        .find(|(_, p)| *p == protocol)
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| format!("{protocol:?}").to_lowercase())
+}
+
+/// Returns the capabilities of the client for a protocol, or `None` if the
+/// protocol does not have a client for keyspaces.
+pub fn capabilities(protocol: Protocol) -> Option<&'static Capabilities> {
+    match protocol {
+        Protocol::Http1 => Some(&http1::CAPABILITIES),
+        Protocol::Http2 => Some(&http2::CAPABILITIES),
+        Protocol::Memcache => Some(&memcache::CAPABILITIES),
+        Protocol::MemcacheBinary => Some(&memcache_binary::CAPABILITIES),
+        Protocol::MemcacheMeta => Some(&memcache_meta::CAPABILITIES),
+        Protocol::Momento => Some(&momento::CAPABILITIES),
+        Protocol::Ping => Some(&ping::CAPABILITIES),
+        Protocol::Resp => Some(&redis::CAPABILITIES),
+        Protocol::Blabber | Protocol::Kafka | Protocol::Nats | Protocol::Mqtt => None,
+    }
+}
+
+/// Returns the reasons the client can not serve a command, including any of
+/// the commands it sends as a transaction. The result is empty if the command
+/// is supported.
+pub fn unsupported(capabilities: &Capabilities, command: &config::Command) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    if !capabilities.verbs.contains(&command.verb()) {
+        reasons.push(format!("`{}` is not supported", verb_name(command.verb())));
+    } else if command.verb() == Verb::Get && command.cardinality() > 1 && !capabilities.multi_get {
+        reasons.push("`get` with a cardinality greater than one is not supported".to_string());
+    }
+
+    for command in command.commands() {
+        reasons.extend(unsupported(capabilities, command));
+    }
+
+    reasons
+}
+
+/// The name of a verb as it is written in the config.
+pub fn verb_name(verb: Verb) -> String {
+    let mut name = String::new();
+
+    for c in format!("{verb:?}").chars() {
+        if c.is_ascii_uppercase() {
+            if !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+
+    name
+}
+
+// memcache treats an expiration time of more than 30 days as a Unix timestamp
+const MEMCACHE_MAX_RELATIVE_EXPTIME: u64 = 60 * 60 * 24 * 30;
+
+/// Returns the expiration time which is sent to a memcache server for a TTL.
+/// A TTL of up to 30 days is sent as the number of seconds until the item
+/// expires, and a longer TTL is sent as the Unix time at which it expires.
+pub fn memcache_exptime(ttl: Duration) -> u32 {
+    let secs = ttl.as_secs().max(1);
+
+    if secs <= MEMCACHE_MAX_RELATIVE_EXPTIME {
+        return secs as u32;
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or(0);
+
+    now.saturating_add(secs).min(i32::MAX as u64) as u32
+}
+
+/// Prints the verbs supported by each client protocol as a table.
+pub fn list_verbs() {
+    let verbs: std::collections::BTreeSet<Verb> = CLIENT_PROTOCOLS
+        .iter()
+        .filter_map(|(_, protocol)| capabilities(*protocol))
+        .flat_map(|c| c.verbs.iter().copied())
+        .collect();
+
+    let mut rows: Vec<(String, Vec<bool>)> = Vec::new();
+
+    for verb in verbs {
+        let supported = CLIENT_PROTOCOLS
+            .iter()
+            .map(|(_, protocol)| {
+                capabilities(*protocol)
+                    .map(|c| c.verbs.contains(&verb))
+                    .unwrap_or(false)
+            })
+            .collect();
+        rows.push((verb_name(verb), supported));
+
+        if verb == Verb::Get {
+            let supported = CLIENT_PROTOCOLS
+                .iter()
+                .map(|(_, protocol)| {
+                    capabilities(*protocol)
+                        .map(|c| c.multi_get)
+                        .unwrap_or(false)
+                })
+                .collect();
+            rows.push(("get (cardinality > 1)".to_string(), supported));
+        }
+    }
+
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    let mut header = format!("{:width$}", "verb");
+    for (name, _) in CLIENT_PROTOCOLS {
+        header.push_str(&format!("  {name}"));
+    }
+    println!("{header}");
+
+    for (verb, supported) in rows {
+        let mut line = format!("{verb:width$}");
+        for ((name, _), supported) in CLIENT_PROTOCOLS.iter().zip(supported) {
+            let mark = if supported { "x" } else { "-" };
+            let column = name.len();
+            line.push_str(&format!("  {mark:^column$}"));
+        }
+        println!("{line}");
+    }
+}
+
+pub fn launch_clients(
+    config: &Config,
+    work_receiver: Receiver<WorkItem>,
+    workload_components: &[Component],
+) -> Option<Runtime> {
     debug!("Launching clients...");
 
     config.client()?;
@@ -40,15 +204,24 @@ pub fn launch_clients(config: &Config, work_receiver: Receiver<WorkItem>) -> Opt
         Protocol::Memcache => {
             clients::memcache::launch_tasks(&mut client_rt, config.clone(), work_receiver)
         }
+        Protocol::MemcacheBinary => {
+            clients::memcache_binary::launch_tasks(&mut client_rt, config.clone(), work_receiver)
+        }
+        Protocol::MemcacheMeta => {
+            clients::memcache_meta::launch_tasks(&mut client_rt, config.clone(), work_receiver)
+        }
         Protocol::Momento => {
             clients::momento::launch_tasks(&mut client_rt, config.clone(), work_receiver)
         }
         Protocol::Ping => {
             clients::ping::launch_tasks(&mut client_rt, config.clone(), work_receiver)
         }
-        Protocol::Resp => {
-            clients::redis::launch_tasks(&mut client_rt, config.clone(), work_receiver)
-        }
+        Protocol::Resp => clients::redis::launch_tasks(
+            &mut client_rt,
+            config.clone(),
+            work_receiver,
+            workload_components,
+        ),
         Protocol::Kafka => {
             error!("keyspace is not supported for the kafka protocol");
             std::process::exit(1);
@@ -57,6 +230,14 @@ pub fn launch_clients(config: &Config, work_receiver: Receiver<WorkItem>) -> Opt
             error!("keyspace is not supported for the blabber protocol");
             std::process::exit(1);
         }
+        Protocol::Nats => {
+            error!("keyspace is not supported for the nats protocol");
+            std::process::exit(1);
+        }
+        Protocol::Mqtt => {
+            error!("keyspace is not supported for the mqtt protocol");
+            std::process::exit(1);
+        }
     }
 
     Some(client_rt)
@@ -82,3 +263,116 @@ impl From<MomentoError> for ResponseError {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Returns a config with a keyspace which has a command for each of the
+    /// verbs in the capabilities of the client for the protocol, and a `get`
+    /// with a cardinality greater than one if it is supported. The config and
+    /// the script it uses are written to the directory, which should be a
+    /// temporary directory that outlives the config.
+    pub fn config(dir: &std::path::Path, protocol: Protocol) -> Config {
+        let name = protocol_name(protocol);
+        let capabilities = capabilities(protocol).unwrap();
+
+        let script = dir.join(format!("rpc-perf-{name}.lua"));
+        std::fs::write(&script, "return 1").unwrap();
+
+        let mut commands: Vec<String> = capabilities
+            .verbs
+            .iter()
+            .map(|verb| {
+                let options = match verb {
+                    Verb::Eval => format!(", script = {:?}, values = 1", script),
+                    Verb::StreamTrim => ", truncate = 1".to_string(),
+                    Verb::Transaction => ", commands = [{ verb = \"get\" }]".to_string(),
+                    _ => String::new(),
+                };
+                format!("{{ verb = \"{}\"{options} }}", verb_name(*verb))
+            })
+            .collect();
+
+        if capabilities.multi_get {
+            commands.push("{ verb = \"get\", cardinality = 2 }".to_string());
+        }
+
+        let path = dir.join(format!("rpc-perf-{name}.toml"));
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[general]
+protocol = "{name}"
+interval = 1
+duration = 1
+admin = "127.0.0.1:9090"
+
+[debug]
+
+[target]
+endpoints = ["127.0.0.1:0"]
+
+[client]
+threads = 1
+poolsize = 1
+connect_timeout = 1000
+request_timeout = 1000
+
+[workload]
+threads = 1
+
+[workload.ratelimit]
+start = 1
+
+[[workload.keyspace]]
+nkeys = 16
+klen = 8
+vlen = 8
+inner_keys_nkeys = 16
+inner_keys_klen = 8
+ttl = "60s"
+commands = [{}]
+"#,
+                commands.join(",\n")
+            ),
+        )
+        .unwrap();
+
+        Config::new(path.to_str().unwrap())
+    }
+
+    /// Generates a request for each of the commands in the config. Clients
+    /// use this to check that they can build a request for everything they
+    /// claim to support.
+    pub fn requests(config: &Config) -> Vec<ClientRequest> {
+        let requests: Vec<ClientRequest> = workload::generators(config)
+            .iter()
+            .flat_map(|g| g.requests())
+            .collect();
+
+        assert_eq!(
+            requests.len(),
+            config.workload().keyspaces()[0].commands().len()
+        );
+
+        requests
+    }
+
+    #[test]
+    fn exptime() {
+        assert_eq!(memcache_exptime(Duration::from_millis(500)), 1);
+        assert_eq!(memcache_exptime(Duration::from_secs(60)), 60);
+        assert_eq!(memcache_exptime(Duration::from_secs(2592000)), 2592000);
+
+        // beyond 30 days the expiration is an absolute time
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let exptime = memcache_exptime(Duration::from_secs(2592001)) as u64;
+        assert!(exptime >= now + 2592001 && exptime <= now + 2592001 + 5);
+    }
+}
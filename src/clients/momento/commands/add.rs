@@ -0,0 +1,45 @@
+use super::*;
+
+use ::momento::cache::{SetIfAbsentRequest, SetIfAbsentResponse};
+
+/// Sets a key-value pair in the cache if the key does not already exist.
+pub async fn add(
+    client: &mut CacheClient,
+    config: &Config,
+    cache_name: &str,
+    request: workload::client::Add,
+) -> std::result::Result<(), ResponseError> {
+    ADD.increment();
+
+    let mut r = SetIfAbsentRequest::new(cache_name, &*request.key, request.value);
+
+    if let Some(ttl) = request.ttl {
+        r = r.ttl(ttl);
+    }
+
+    match timeout(
+        config.client().unwrap().request_timeout(),
+        client.send_request(r),
+    )
+    .await
+    {
+        Ok(Ok(r)) => match r {
+            SetIfAbsentResponse::Stored => {
+                ADD_STORED.increment();
+                Ok(())
+            }
+            SetIfAbsentResponse::NotStored => {
+                ADD_NOT_STORED.increment();
+                Ok(())
+            }
+        },
+        Ok(Err(e)) => {
+            ADD_EX.increment();
+            Err(e.into())
+        }
+        Err(_) => {
+            ADD_TIMEOUT.increment();
+            Err(ResponseError::Timeout)
+        }
+    }
+}
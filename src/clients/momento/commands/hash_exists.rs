@@ -0,0 +1,43 @@
+use super::*;
+
+use ::momento::cache::DictionaryGetFieldResponse;
+
+/// Checks if a field exists in a hash (dictionary).
+pub async fn hash_exists(
+    client: &mut CacheClient,
+    config: &Config,
+    cache_name: &str,
+    request: workload::client::HashExists,
+) -> std::result::Result<(), ResponseError> {
+    HASH_EXISTS.increment();
+
+    match timeout(
+        config.client().unwrap().request_timeout(),
+        client.dictionary_get_field(cache_name, &*request.key, &*request.field),
+    )
+    .await
+    {
+        Ok(Ok(r)) => match r {
+            DictionaryGetFieldResponse::Hit { .. } => {
+                HASH_EXISTS_OK.increment();
+                RESPONSE_HIT.increment();
+                HASH_EXISTS_HIT.increment();
+                Ok(())
+            }
+            DictionaryGetFieldResponse::Miss => {
+                HASH_EXISTS_OK.increment();
+                RESPONSE_MISS.increment();
+                HASH_EXISTS_MISS.increment();
+                Ok(())
+            }
+        },
+        Ok(Err(e)) => {
+            HASH_EXISTS_EX.increment();
+            Err(e.into())
+        }
+        Err(_) => {
+            HASH_EXISTS_TIMEOUT.increment();
+            Err(ResponseError::Timeout)
+        }
+    }
+}
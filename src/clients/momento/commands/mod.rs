@@ -2,9 +2,11 @@ use super::*;
 
 use paste::paste;
 
+mod add;
 mod delete;
 mod get;
 mod hash_delete;
+mod hash_exists;
 mod hash_get;
 mod hash_get_all;
 mod hash_increment;
@@ -16,6 +18,8 @@ mod list_pop_front;
 mod list_push_back;
 mod list_push_front;
 mod list_remove;
+mod multi_get;
+mod replace;
 mod set;
 mod set_add;
 mod set_members;
@@ -26,9 +30,11 @@ mod sorted_set_rank;
 mod sorted_set_remove;
 mod sorted_set_score;
 
+pub use add::*;
 pub use delete::*;
 pub use get::*;
 pub use hash_delete::*;
+pub use hash_exists::*;
 pub use hash_get::*;
 pub use hash_get_all::*;
 pub use hash_increment::*;
@@ -40,6 +46,8 @@ pub use list_pop_front::*;
 pub use list_push_back::*;
 pub use list_push_front::*;
 pub use list_remove::*;
+pub use multi_get::*;
+pub use replace::*;
 pub use set::*;
 pub use set_add::*;
 pub use set_members::*;
@@ -0,0 +1,58 @@
+use super::*;
+
+use ::momento::cache::GetResponse;
+use futures::future::join_all;
+
+/// Retrieve the values for multiple keys from the cache. This is not a batch
+/// request, each key is fetched with its own `get` and the gets are sent
+/// concurrently. Hits and misses are counted for each key, while the request
+/// itself counts as a single success or exception.
+pub async fn multi_get(
+    client: &mut CacheClient,
+    config: &Config,
+    cache_name: &str,
+    request: workload::client::MultiGet,
+) -> std::result::Result<(), ResponseError> {
+    GET.increment();
+
+    let client = &*client;
+
+    match timeout(
+        config.client().unwrap().request_timeout(),
+        join_all(
+            request
+                .keys
+                .iter()
+                .map(|key| client.get(cache_name, &**key)),
+        ),
+    )
+    .await
+    {
+        Ok(responses) => {
+            let mut hits = 0;
+            let mut misses = 0;
+
+            for response in responses {
+                match response {
+                    Ok(GetResponse::Hit { .. }) => hits += 1,
+                    Ok(GetResponse::Miss) => misses += 1,
+                    Err(e) => {
+                        GET_EX.increment();
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            GET_OK.increment();
+            RESPONSE_HIT.add(hits);
+            RESPONSE_MISS.add(misses);
+            GET_KEY_HIT.add(hits);
+            GET_KEY_MISS.add(misses);
+            Ok(())
+        }
+        Err(_) => {
+            GET_TIMEOUT.increment();
+            Err(ResponseError::Timeout)
+        }
+    }
+}
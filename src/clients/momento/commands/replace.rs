@@ -0,0 +1,45 @@
+use super::*;
+
+use ::momento::cache::{SetIfPresentRequest, SetIfPresentResponse};
+
+/// Sets a key-value pair in the cache only if the key already exists.
+pub async fn replace(
+    client: &mut CacheClient,
+    config: &Config,
+    cache_name: &str,
+    request: workload::client::Replace,
+) -> std::result::Result<(), ResponseError> {
+    REPLACE.increment();
+
+    let mut r = SetIfPresentRequest::new(cache_name, &*request.key, request.value);
+
+    if let Some(ttl) = request.ttl {
+        r = r.ttl(ttl);
+    }
+
+    match timeout(
+        config.client().unwrap().request_timeout(),
+        client.send_request(r),
+    )
+    .await
+    {
+        Ok(Ok(r)) => match r {
+            SetIfPresentResponse::Stored => {
+                REPLACE_STORED.increment();
+                Ok(())
+            }
+            SetIfPresentResponse::NotStored => {
+                REPLACE_NOT_STORED.increment();
+                Ok(())
+            }
+        },
+        Ok(Err(e)) => {
+            REPLACE_EX.increment();
+            Err(e.into())
+        }
+        Err(_) => {
+            REPLACE_TIMEOUT.increment();
+            Err(ResponseError::Timeout)
+        }
+    }
+}
@@ -1,12 +1,46 @@
 use super::*;
 
-use ::momento::cache::configurations::LowLatency;
+use ::momento::config::grpc_configuration::GrpcConfiguration;
+use ::momento::config::transport_strategy::TransportStrategy;
 use ::momento::*;
 
 mod commands;
 
 use commands::*;
 
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[
+        Verb::Add,
+        Verb::Get,
+        Verb::Set,
+        Verb::Delete,
+        Verb::Replace,
+        Verb::HashDelete,
+        Verb::HashExists,
+        Verb::HashGet,
+        Verb::HashGetAll,
+        Verb::HashIncrement,
+        Verb::HashSet,
+        Verb::ListPushFront,
+        Verb::ListPushBack,
+        Verb::ListFetch,
+        Verb::ListLength,
+        Verb::ListPopFront,
+        Verb::ListPopBack,
+        Verb::ListRemove,
+        Verb::SetAdd,
+        Verb::SetMembers,
+        Verb::SetRemove,
+        Verb::SortedSetAdd,
+        Verb::SortedSetRange,
+        Verb::SortedSetRank,
+        Verb::SortedSetRemove,
+        Verb::SortedSetScore,
+    ],
+    multi_get: true,
+};
+
 /// Launch tasks with one channel per task as gRPC is mux-enabled.
 pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
     debug!("launching momento protocol tasks");
@@ -16,24 +50,10 @@ pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiv
             let _guard = runtime.enter();
 
             // initialize the Momento cache client
-            if std::env::var("MOMENTO_API_KEY").is_err() {
-                eprintln!("environment variable `MOMENTO_API_KEY` is not set");
-                std::process::exit(1);
-            }
-
-            let credential_provider =
-                match CredentialProvider::from_env_var("MOMENTO_API_KEY".to_string()) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        eprintln!("MOMENTO_API_KEY key should be valid: {e}");
-                        std::process::exit(1);
-                    }
-                };
-
             match CacheClient::builder()
-                .default_ttl(Duration::from_secs(900))
-                .configuration(LowLatency::v1())
-                .credential_provider(credential_provider)
+                .default_ttl(config.momento().default_ttl())
+                .configuration(configuration::<::momento::cache::Configuration>(&config))
+                .credential_provider(credential_provider(&config))
                 .build()
             {
                 Ok(c) => c,
@@ -54,6 +74,96 @@ pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiv
     }
 }
 
+/// Returns the credential provider for the Momento clients. The API key is
+/// read from the `credential_file` if there is one, or else from the
+/// `MOMENTO_API_KEY` environment variable.
+pub(crate) fn credential_provider(config: &Config) -> CredentialProvider {
+    let momento = config.momento();
+
+    let credential_provider = match momento.credential_file() {
+        Some(path) => {
+            let key = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("failed to read momento credential file `{path}`: {e}");
+                std::process::exit(1);
+            });
+
+            CredentialProvider::from_string(key.trim().to_string())
+        }
+        None => {
+            if std::env::var("MOMENTO_API_KEY").is_err() {
+                eprintln!("environment variable `MOMENTO_API_KEY` is not set");
+                std::process::exit(1);
+            }
+
+            CredentialProvider::from_env_var("MOMENTO_API_KEY".to_string())
+        }
+    };
+
+    let credential_provider = match credential_provider {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("momento API key should be valid: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match momento.endpoint() {
+        Some(endpoint) => credential_provider.base_endpoint(endpoint),
+        None => credential_provider,
+    }
+}
+
+/// The configuration types of the Momento cache and topics clients, which
+/// are built the same way.
+pub(crate) trait ClientConfiguration: Sized {
+    fn profile(profile: MomentoProfile) -> Self;
+
+    fn deadline(deadline: Duration) -> Self;
+}
+
+macro_rules! client_configuration {
+    ($module:ident) => {
+        impl ClientConfiguration for ::momento::$module::Configuration {
+            fn profile(profile: MomentoProfile) -> Self {
+                use ::momento::$module::configurations::{InRegion, Laptop, LowLatency};
+
+                match profile {
+                    MomentoProfile::LowLatency => LowLatency::v1().into(),
+                    MomentoProfile::InRegion => InRegion::v1().into(),
+                    MomentoProfile::Laptop => Laptop::v1().into(),
+                }
+            }
+
+            fn deadline(deadline: Duration) -> Self {
+                Self::builder()
+                    .transport_strategy(
+                        TransportStrategy::builder().grpc_configuration(
+                            GrpcConfiguration::builder()
+                                .deadline(deadline)
+                                .enable_keep_alives_with_defaults(),
+                        ),
+                    )
+                    .into()
+            }
+        }
+    };
+}
+
+client_configuration!(cache);
+client_configuration!(topics);
+
+/// Returns the configuration for the Momento clients. This uses the deadline
+/// if there is one, or else the profile. Config validation ensures that only
+/// one of them is set.
+pub(crate) fn configuration<T: ClientConfiguration>(config: &Config) -> T {
+    let momento = config.momento();
+
+    match momento.deadline() {
+        Some(deadline) => T::deadline(deadline),
+        None => T::profile(momento.profile()),
+    }
+}
+
 async fn task(
     config: Config,
     // cache_name: String,
@@ -78,7 +188,10 @@ async fn task(
                 /*
                  * KEY-VALUE
                  */
+                ClientRequest::Add(r) => add(&mut client, &config, cache_name, r).await,
                 ClientRequest::Get(r) => get(&mut client, &config, cache_name, r).await,
+                ClientRequest::MultiGet(r) => multi_get(&mut client, &config, cache_name, r).await,
+                ClientRequest::Replace(r) => replace(&mut client, &config, cache_name, r).await,
                 ClientRequest::Set(r) => set(&mut client, &config, cache_name, r).await,
                 ClientRequest::Delete(r) => delete(&mut client, &config, cache_name, r).await,
 
@@ -88,6 +201,9 @@ async fn task(
                 ClientRequest::HashDelete(r) => {
                     hash_delete(&mut client, &config, cache_name, r).await
                 }
+                ClientRequest::HashExists(r) => {
+                    hash_exists(&mut client, &config, cache_name, r).await
+                }
                 ClientRequest::HashGet(r) => hash_get(&mut client, &config, cache_name, r).await,
                 ClientRequest::HashGetAll(r) => {
                     hash_get_all(&mut client, &config, cache_name, r).await
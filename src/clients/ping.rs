@@ -3,6 +3,14 @@ use crate::net::Connector;
 use protocol_ping::{Compose, Parse, Request, Response};
 use session::{Buf, BufMut, Buffer};
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::VecDeque;
+use tokio::time::sleep_until;
+
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[Verb::Ping],
+    multi_get: false,
+};
 
 /// Launch tasks with one conncetion per task as ping protocol is not mux-enabled.
 pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
@@ -30,11 +38,21 @@ async fn task(work_receiver: Receiver<WorkItem>, endpoint: String, config: Confi
     // there wasn't a client config.
     let client_config = config.client().unwrap();
 
+    let pipeline_depth = client_config.pipeline_depth();
+
     let mut stream = None;
     let parser = protocol_ping::ResponseParser::new();
     let mut read_buffer = Buffer::new(client_config.read_buffer_size());
     let mut write_buffer = Buffer::new(client_config.write_buffer_size());
 
+    // the send times for requests which are awaiting a response, in the order
+    // they were sent
+    let mut inflight: VecDeque<Instant> = VecDeque::with_capacity(pipeline_depth);
+
+    // set when a reconnect is requested, the connection is closed once all of
+    // the outstanding requests have completed
+    let mut reconnect = false;
+
     while RUNNING.load(Ordering::Relaxed) {
         if stream.is_none() {
             CONNECT.increment();
@@ -47,6 +65,7 @@ async fn task(work_receiver: Receiver<WorkItem>, endpoint: String, config: Confi
                 Ok(Ok(s)) => {
                     CONNECT_OK.increment();
                     CONNECT_CURR.increment();
+                    read_buffer.clear();
                     Some(s)
                 }
                 Ok(Err(_)) => {
@@ -64,144 +83,148 @@ async fn task(work_receiver: Receiver<WorkItem>, endpoint: String, config: Confi
 
         let mut s = stream.take().unwrap();
 
-        let work_item = work_receiver
-            .recv()
-            .await
-            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+        if reconnect && inflight.is_empty() {
+            reconnect = false;
+            CONNECT_CURR.sub(1);
+            continue;
+        }
 
-        REQUEST.increment();
+        // the oldest outstanding request is the first which may time out
+        let deadline = inflight
+            .front()
+            .map(|start| *start + client_config.request_timeout())
+            .unwrap_or_else(Instant::now);
 
-        // compose request into buffer
-        match &work_item {
-            WorkItem::Request { request, .. } => match request {
-                ClientRequest::Ping(_) => {
-                    Request::Ping.compose(&mut write_buffer);
-                }
-                _ => {
-                    REQUEST_UNSUPPORTED.increment();
-                    stream = Some(s);
-                    continue;
+        tokio::select! {
+            work_item = work_receiver.recv(), if !reconnect && inflight.len() < pipeline_depth => {
+                let work_item =
+                    work_item.map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+                REQUEST.increment();
+
+                // compose request into buffer
+                match &work_item {
+                    WorkItem::Request { request, .. } => match request {
+                        ClientRequest::Ping(_) => {
+                            Request::Ping.compose(&mut write_buffer);
+                        }
+                        _ => {
+                            REQUEST_UNSUPPORTED.increment();
+                            stream = Some(s);
+                            continue;
+                        }
+                    },
+                    WorkItem::Reconnect => {
+                        REQUEST_RECONNECT.increment();
+                        reconnect = true;
+                        stream = Some(s);
+                        continue;
+                    }
                 }
-            },
-            WorkItem::Reconnect => {
-                REQUEST_RECONNECT.increment();
-                continue;
-            }
-        }
 
-        REQUEST_OK.increment();
+                REQUEST_OK.increment();
 
-        // send request
-        let start = Instant::now();
-        s.write_all(write_buffer.borrow()).await?;
-        write_buffer.clear();
+                // send request
+                let start = Instant::now();
+                let result = s.write_all(write_buffer.borrow()).await;
+                write_buffer.clear();
 
-        // read until response or timeout
-        let mut remaining_time = client_config.request_timeout().as_nanos() as u64;
-        let response = loop {
-            match timeout(
-                Duration::from_millis(remaining_time / 1000000),
-                s.read(read_buffer.borrow_mut()),
-            )
-            .await
-            {
-                Ok(Ok(n)) => {
-                    unsafe {
+                inflight.push_back(start);
+
+                if result.is_err() {
+                    fail_inflight(&mut inflight, ResponseError::Exception);
+                    CONNECT_CURR.sub(1);
+                    continue;
+                }
+
+                // preserve the connection for the next request
+                stream = Some(s);
+            }
+            result = s.read(read_buffer.borrow_mut()), if !inflight.is_empty() => {
+                match result {
+                    Ok(n) if n > 0 => unsafe {
                         read_buffer.advance_mut(n);
+                    },
+                    _ => {
+                        // the connection is closed or has failed
+                        fail_inflight(&mut inflight, ResponseError::Exception);
+                        CONNECT_CURR.sub(1);
+                        continue;
                     }
+                }
+
+                let mut failed = false;
+
+                // there may be responses for multiple requests in the buffer
+                while !inflight.is_empty() {
                     match parser.parse(read_buffer.borrow()) {
                         Ok(resp) => {
                             let consumed = resp.consumed();
-                            let resp = resp.into_inner();
 
-                            read_buffer.advance(consumed);
+                            match resp.into_inner() {
+                                Response::Pong => {
+                                    PING_OK.increment();
+                                }
+                            }
 
-                            break Ok(resp);
+                            read_buffer.advance(consumed);
                         }
                         Err(e) => match e.kind() {
                             ErrorKind::WouldBlock => {
-                                let elapsed = start.elapsed().as_nanos() as u64;
-                                remaining_time = remaining_time.saturating_sub(elapsed);
-                                if remaining_time == 0 {
-                                    break Err(ResponseError::Timeout);
-                                }
+                                break;
                             }
                             _ => {
-                                break Err(ResponseError::Exception);
+                                failed = true;
+                                fail_inflight(&mut inflight, ResponseError::Exception);
+                                break;
                             }
                         },
                     }
-                }
-                Ok(Err(_)) => {
-                    break Err(ResponseError::Exception);
-                }
-                Err(_) => {
-                    break Err(ResponseError::Timeout);
-                }
-            }
-        };
 
-        let stop = Instant::now();
+                    let stop = Instant::now();
 
-        match response {
-            Ok(response) => {
-                // validate response
-                match work_item {
-                    WorkItem::Request { request, .. } => match request {
-                        ClientRequest::Ping(_) => match response {
-                            Response::Pong => {
-                                PING_OK.increment();
-                            }
-                        },
-                        _ => {
-                            error!("unexpected request");
-                            unimplemented!();
-                        }
-                    },
-                    _ => {
-                        error!("unexpected work item");
-                        unimplemented!();
-                    }
-                }
+                    // responses are returned in the order the requests were sent
+                    let start = inflight.pop_front().unwrap();
 
-                // preserve the connection for reuse
-                stream = Some(s);
+                    RESPONSE_OK.increment();
 
-                RESPONSE_OK.increment();
+                    let latency = stop.duration_since(start).as_nanos() as u64;
 
-                let latency = stop.duration_since(start).as_nanos() as u64;
+                    let _ = RESPONSE_LATENCY.increment(latency);
+                }
 
-                let _ = RESPONSE_LATENCY.increment(latency);
-            }
-            Err(ResponseError::Exception) => {
-                // record execption
-                match work_item {
-                    WorkItem::Request { request, .. } => match request {
-                        ClientRequest::Ping(_) => {
-                            PING_EX.increment();
-                        }
-                        _ => {
-                            error!("unexpected request");
-                            unimplemented!();
-                        }
-                    },
-                    _ => {
-                        error!("unexpected work item");
-                        unimplemented!();
-                    }
+                if failed || (inflight.is_empty() && read_buffer.remaining() > 0) {
+                    // a request failed or there is unexpected data, connection
+                    // will be dropped
+                    CONNECT_CURR.sub(1);
+                    continue;
                 }
 
-                CONNECT_CURR.sub(1);
+                // preserve the connection for reuse
+                stream = Some(s);
             }
-            Err(ResponseError::Timeout) => {
-                RESPONSE_TIMEOUT.increment();
+            _ = sleep_until(deadline.into()), if !inflight.is_empty() => {
+                fail_inflight(&mut inflight, ResponseError::Timeout);
                 CONNECT_CURR.sub(1);
             }
-            Err(ResponseError::Ratelimited) | Err(ResponseError::BackendTimeout) => {
-                unimplemented!();
-            }
         }
     }
 
     Ok(())
 }
+
+// Records the outcome for each of the outstanding requests, which will not
+// receive a response once the connection is dropped.
+fn fail_inflight(inflight: &mut VecDeque<Instant>, error: ResponseError) {
+    for _ in inflight.drain(..) {
+        match error {
+            ResponseError::Timeout => {
+                RESPONSE_TIMEOUT.increment();
+            }
+            _ => {
+                PING_EX.increment();
+                RESPONSE_EX.increment();
+            }
+        }
+    }
+}
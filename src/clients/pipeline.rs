@@ -0,0 +1,371 @@
+// A client task which pipelines requests on a single connection. This is
+// shared by the memcache and RESP clients, which differ only in how requests
+// are composed and how responses are matched to the requests. Those parts are
+// provided by an implementation of `Codec` for each protocol.
+
+use super::*;
+use crate::net::{Connector, Stream};
+use session::{Buf, BufMut, Buffer};
+use std::borrow::{Borrow, BorrowMut};
+use std::collections::VecDeque;
+use std::future::Future;
+use tokio::time::sleep_until;
+
+/// A request which has been sent and is awaiting a response.
+pub trait Inflight: Send {
+    /// Records an exception for the request, which will not receive a response
+    /// as the connection is being closed.
+    fn exception(self);
+
+    /// The counter for the verb which is incremented if the request times out.
+    fn timeout(&self) -> &'static metriken::Counter;
+}
+
+/// The result of reading a response for the oldest outstanding request.
+pub enum Received<T> {
+    /// A response was read but the request is not yet complete, for example a
+    /// quiet request which awaits the no-op that ends its responses.
+    Pending,
+    /// The request is complete. Holds the result of validating the response
+    /// and any request which depends on the response and is sent next.
+    Complete(std::result::Result<(), ()>, Option<T>),
+}
+
+/// The protocol for a pipelined client.
+pub trait Codec: Send + 'static {
+    type Request: Inflight;
+
+    /// Whether the connection is closed after a response which fails
+    /// validation or when data is received which no request is awaiting. This
+    /// is needed for protocols where an unexpected response means that the
+    /// responses can no longer be matched to the requests.
+    const CLOSE_ON_EXCEPTION: bool;
+
+    /// Prepares a newly established connection before it is used for requests,
+    /// for example by authenticating. Any state held for the previous
+    /// connection is reset. Implementations are responsible for bounding the
+    /// time this takes.
+    fn connect(&mut self, connection: &mut Connection) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the request for a work item, or an error if the client does not
+    /// support it.
+    fn request(&mut self, request: ClientRequest) -> std::result::Result<Self::Request, ()>;
+
+    /// Writes the request into the buffer.
+    fn compose(&mut self, request: &mut Self::Request, buffer: &mut Buffer);
+
+    /// Reads the next response for the request from the buffer and advances
+    /// the buffer past it. Returns an error with the kind `WouldBlock` if the
+    /// buffer does not yet hold a complete response.
+    fn receive(
+        &mut self,
+        request: &mut Self::Request,
+        buffer: &mut Buffer,
+    ) -> Result<Received<Self::Request>>;
+}
+
+/// A connection to the server along with its buffers.
+pub struct Connection {
+    stream: Stream,
+    read_buffer: Buffer,
+    write_buffer: Buffer,
+}
+
+impl Connection {
+    pub fn new(stream: Stream, config: &Config) -> Self {
+        // we would not be creating a client task if we didn't have a client
+        // config, so this unwrap will succeed.
+        let client_config = config.client().unwrap();
+
+        Self {
+            stream,
+            read_buffer: Buffer::new(client_config.read_buffer_size()),
+            write_buffer: Buffer::new(client_config.write_buffer_size()),
+        }
+    }
+
+    pub fn read_buffer(&mut self) -> &mut Buffer {
+        &mut self.read_buffer
+    }
+
+    pub fn write_buffer(&mut self) -> &mut Buffer {
+        &mut self.write_buffer
+    }
+
+    /// Writes the contents of the write buffer to the stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        let result = self.stream.write_all(self.write_buffer.borrow()).await;
+
+        self.write_buffer.clear();
+
+        result
+    }
+
+    /// Reads from the stream into the read buffer. Returns an error if the
+    /// connection has been closed.
+    pub async fn fill(&mut self) -> Result<()> {
+        let n = self.stream.read(self.read_buffer.borrow_mut()).await?;
+
+        if n == 0 {
+            return Err(Error::new(ErrorKind::ConnectionReset, "connection closed"));
+        }
+
+        unsafe {
+            self.read_buffer.advance_mut(n);
+        }
+
+        Ok(())
+    }
+}
+
+/// Sends the requests from the work receiver on a connection to the endpoint
+/// and validates their responses. Up to `pipeline_depth` requests may be
+/// outstanding at a time. The connection is re-established if it fails or a
+/// reconnect is requested.
+pub async fn task<C: Codec>(
+    mut codec: C,
+    work_receiver: Receiver<WorkItem>,
+    endpoint: String,
+    config: Config,
+) -> Result<()> {
+    let connector = Connector::new(&config)?;
+
+    // we would not be creating a client task if we didn't have a client
+    // config, so this unwrap will succeed.
+    let client_config = config.client().unwrap();
+
+    let pipeline_depth = client_config.pipeline_depth();
+
+    let mut connection = None;
+
+    // requests which have been sent and are awaiting a response, in the order
+    // they were sent, along with the time they were sent
+    let mut inflight: VecDeque<(C::Request, Instant)> = VecDeque::with_capacity(pipeline_depth);
+
+    // set when a reconnect is requested, the connection is closed once all of
+    // the outstanding requests have completed
+    let mut reconnect = false;
+
+    while RUNNING.load(Ordering::Relaxed) {
+        if connection.is_none() {
+            CONNECT.increment();
+            connection = match timeout(
+                client_config.connect_timeout(),
+                connector.connect(&endpoint),
+            )
+            .await
+            {
+                Ok(Ok(stream)) => {
+                    let mut c = Connection::new(stream, &config);
+
+                    match codec.connect(&mut c).await {
+                        Ok(()) => {
+                            CONNECT_OK.increment();
+                            CONNECT_CURR.increment();
+                            Some(c)
+                        }
+                        Err(e) => {
+                            trace!("error preparing connection: {e}");
+                            CONNECT_EX.increment();
+                            sleep(Duration::from_millis(100)).await;
+                            continue;
+                        }
+                    }
+                }
+                Ok(Err(e)) => {
+                    trace!("error connecting: {e}");
+                    CONNECT_EX.increment();
+                    sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+                Err(_) => {
+                    trace!("connect timeout");
+                    CONNECT_TIMEOUT.increment();
+                    sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            }
+        }
+
+        let mut c = connection.take().unwrap();
+
+        if reconnect && inflight.is_empty() {
+            reconnect = false;
+            CONNECT_CURR.decrement();
+            continue;
+        }
+
+        // the oldest outstanding request is the first which may time out
+        let deadline = inflight
+            .front()
+            .map(|(_, start)| *start + client_config.request_timeout())
+            .unwrap_or_else(Instant::now);
+
+        tokio::select! {
+            work_item = work_receiver.recv(), if !reconnect && inflight.len() < pipeline_depth => {
+                let work_item =
+                    work_item.map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+                REQUEST.increment();
+
+                let request = match work_item {
+                    WorkItem::Request { request, .. } => match codec.request(request) {
+                        Ok(request) => request,
+                        Err(_) => {
+                            REQUEST_UNSUPPORTED.increment();
+                            connection = Some(c);
+                            continue;
+                        }
+                    },
+                    WorkItem::Reconnect => {
+                        REQUEST_RECONNECT.increment();
+                        reconnect = true;
+                        connection = Some(c);
+                        continue;
+                    }
+                };
+
+                REQUEST_OK.increment();
+
+                if send(&mut codec, &mut c, &mut inflight, vec![request]).await.is_err() {
+                    fail_inflight(&mut inflight, ResponseError::Exception);
+                    CONNECT_CURR.decrement();
+                    continue;
+                }
+
+                // preserve the connection for the next request
+                connection = Some(c);
+            }
+            result = c.fill(), if !inflight.is_empty() => {
+                if result.is_err() {
+                    // the connection is closed or has failed
+                    fail_inflight(&mut inflight, ResponseError::Exception);
+                    CONNECT_CURR.decrement();
+                    continue;
+                }
+
+                let mut failed = false;
+
+                // requests which depend on the responses which were received
+                let mut followups = Vec::new();
+
+                // there may be responses for multiple requests in the buffer
+                while !inflight.is_empty() {
+                    let request = &mut inflight.front_mut().unwrap().0;
+
+                    let (result, followup) = match codec.receive(request, c.read_buffer()) {
+                        Ok(Received::Complete(result, followup)) => (result, followup),
+                        Ok(Received::Pending) => {
+                            continue;
+                        }
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                            break;
+                        }
+                        Err(_) => {
+                            failed = true;
+                            break;
+                        }
+                    };
+
+                    let stop = Instant::now();
+
+                    // responses are returned in the order the requests were sent
+                    let (_, start) = inflight.pop_front().unwrap();
+
+                    followups.extend(followup);
+
+                    if result.is_err() {
+                        RESPONSE_EX.increment();
+
+                        if C::CLOSE_ON_EXCEPTION {
+                            failed = true;
+                            break;
+                        }
+                    } else {
+                        RESPONSE_OK.increment();
+
+                        let latency_ns = stop.duration_since(start).as_nanos() as u64;
+                        let _ = RESPONSE_LATENCY.increment(latency_ns);
+                    }
+                }
+
+                // each request which follows a response is counted as a request
+                // whether or not it can be sent
+                REQUEST.add(followups.len() as _);
+
+                if failed
+                    || (C::CLOSE_ON_EXCEPTION && inflight.is_empty() && c.read_buffer().remaining() > 0)
+                {
+                    // a request failed or there is unexpected data, connection
+                    // will be dropped along with any requests which were to
+                    // follow
+                    let now = Instant::now();
+                    inflight.extend(followups.into_iter().map(|request| (request, now)));
+                    fail_inflight(&mut inflight, ResponseError::Exception);
+                    CONNECT_CURR.decrement();
+                    continue;
+                }
+
+                if !followups.is_empty() {
+                    REQUEST_OK.add(followups.len() as _);
+
+                    if send(&mut codec, &mut c, &mut inflight, followups).await.is_err() {
+                        fail_inflight(&mut inflight, ResponseError::Exception);
+                        CONNECT_CURR.decrement();
+                        continue;
+                    }
+                }
+
+                // preserve the connection for the next request
+                connection = Some(c);
+            }
+            _ = sleep_until(deadline.into()), if !inflight.is_empty() => {
+                // increment error stats and allow connection to be dropped
+                fail_inflight(&mut inflight, ResponseError::Timeout);
+                CONNECT_CURR.decrement();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Composes the requests and writes them to the connection. The requests are
+// outstanding from when they are written, even if the write fails.
+async fn send<C: Codec>(
+    codec: &mut C,
+    connection: &mut Connection,
+    inflight: &mut VecDeque<(C::Request, Instant)>,
+    requests: Vec<C::Request>,
+) -> Result<()> {
+    let mut composed = Vec::with_capacity(requests.len());
+
+    for mut request in requests {
+        codec.compose(&mut request, connection.write_buffer());
+        composed.push(request);
+    }
+
+    let start = Instant::now();
+    let result = connection.flush().await;
+
+    inflight.extend(composed.into_iter().map(|request| (request, start)));
+
+    result
+}
+
+// Records the outcome for each of the outstanding requests, which will not
+// receive a response once the connection is dropped.
+fn fail_inflight<T: Inflight>(inflight: &mut VecDeque<(T, Instant)>, error: ResponseError) {
+    for (request, _) in inflight.drain(..) {
+        match error {
+            ResponseError::Timeout => {
+                request.timeout().increment();
+                RESPONSE_TIMEOUT.increment();
+            }
+            _ => {
+                request.exception();
+                RESPONSE_EX.increment();
+            }
+        }
+    }
+}
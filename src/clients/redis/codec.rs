@@ -0,0 +1,307 @@
+use super::pipeline::{Codec, Connection, Inflight, Received};
+use super::protocol::{Request, Response, ResponseParser};
+use super::*;
+use session::{Buf, Buffer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The protocol for a connection to a RESP server which speaks either RESP2 or
+/// RESP3.
+///
+/// Push messages which are interleaved with replies are consumed here and used
+/// to maintain the state of a simulated near-cache when client tracking is
+/// enabled.
+pub struct RespCodec {
+    config: Config,
+    // scripts used by the workload, which are loaded on each connection
+    scripts: Vec<Arc<Script>>,
+    parser: ResponseParser,
+    // keys which a near-cache would currently hold, `None` unless client
+    // tracking is enabled
+    near_cache: Option<HashSet<Vec<u8>>>,
+    // the number of replies which have been received for the oldest request.
+    // Fused requests are pipelined behind the request, so a request completes
+    // once the replies for it and all of its fused requests have been received.
+    replies: usize,
+    // the result of validating the reply to the oldest request, and the
+    // request which follows it, held until the request is complete
+    completed: Option<(std::result::Result<(), ()>, Option<RequestWithValidator>)>,
+}
+
+impl RespCodec {
+    pub fn new(config: &Config, scripts: Vec<Arc<Script>>) -> Self {
+        Self {
+            config: config.clone(),
+            scripts,
+            parser: ResponseParser::new(),
+            near_cache: None,
+            replies: 0,
+            completed: None,
+        }
+    }
+
+    /// Performs the connection handshake. When RESP3 is enabled this sends
+    /// `HELLO 3` and then, if configured, turns on client tracking. Any scripts
+    /// used by the workload are then loaded.
+    async fn handshake(&mut self, connection: &mut Connection) -> Result<()> {
+        let client_config = self.config.client().unwrap();
+
+        let version = client_config.resp_version();
+        let tracking = client_config.resp_tracking();
+        let tracking_bcast = client_config.resp_tracking_bcast();
+
+        if version == 3 {
+            let request = Request::new("HELLO").arg(3_i64);
+
+            match self.command(connection, &request).await? {
+                Response::Map(_) => {}
+                _ => {
+                    return Err(Error::new(ErrorKind::Other, "server rejected HELLO 3"));
+                }
+            }
+        }
+
+        if tracking {
+            let mut request = Request::new("CLIENT").arg("TRACKING").arg("ON");
+
+            if tracking_bcast {
+                request = request.arg("BCAST");
+            }
+
+            match self.command(connection, &request).await? {
+                Response::SimpleString(_) => {}
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        "server rejected CLIENT TRACKING",
+                    ));
+                }
+            }
+
+            self.near_cache = Some(HashSet::new());
+        }
+
+        for script in self.scripts.clone() {
+            let request = Request::new("SCRIPT").arg("LOAD").arg(script.body.clone());
+
+            match self.command(connection, &request).await? {
+                Response::BulkString(_) => {}
+                _ => {
+                    return Err(Error::new(ErrorKind::Other, "server rejected SCRIPT LOAD"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Sends a single request and returns its reply. Any push messages received
+    // before the reply are handled and skipped.
+    async fn command(
+        &mut self,
+        connection: &mut Connection,
+        request: &Request,
+    ) -> Result<Response<'static>> {
+        request.compose(connection.write_buffer());
+        connection.flush().await?;
+
+        loop {
+            let read_buffer = connection.read_buffer();
+
+            // there may already be complete responses in the buffer
+            let parsed = match self.parser.parse((*read_buffer).borrow()) {
+                Ok(response) => Some((response.consumed(), response.into_inner().into_owned())),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => None,
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+
+            match parsed {
+                Some((consumed, response)) => {
+                    read_buffer.advance(consumed);
+
+                    match response {
+                        Response::Push(data) => self.push(data),
+                        response => return Ok(response),
+                    }
+                }
+                None => {
+                    connection.fill().await?;
+                }
+            }
+        }
+    }
+
+    // Handles a reply to the oldest request. The replies to the prefix are not
+    // validated, and the request completes once the replies to all of its fused
+    // requests have been received.
+    fn reply(
+        &mut self,
+        request: &RequestWithValidator,
+        response: Response,
+    ) -> Received<RequestWithValidator> {
+        let position = self.replies;
+        self.replies += 1;
+
+        if position == request.prefix.len() {
+            // values which were returned would now be held in a near-cache
+            if !request.near_cache_keys.is_empty() {
+                for key in request.near_cache_hits(&response) {
+                    self.near_cache_insert(&key);
+                }
+            }
+
+            let followup = request.followup.as_ref().and_then(|f| f(&response));
+
+            self.completed = Some(((request.validator)(response), followup));
+        } else if position > request.prefix.len() {
+            if response.is_error() {
+                FUSED_REQUEST_EX.increment();
+            } else {
+                FUSED_REQUEST_OK.increment();
+            }
+        }
+
+        if self.replies < request.prefix.len() + 1 + request.fused.len() {
+            return Received::Pending;
+        }
+
+        self.replies = 0;
+
+        let (result, followup) = self.completed.take().unwrap();
+
+        Received::Complete(result, followup)
+    }
+
+    /// Records whether a read of this key would have been served from a local
+    /// near-cache. Does nothing unless client tracking is enabled.
+    fn near_cache_lookup(&self, key: &[u8]) {
+        if let Some(near_cache) = &self.near_cache {
+            if near_cache.contains(key) {
+                NEAR_CACHE_HIT.increment();
+            } else {
+                NEAR_CACHE_MISS.increment();
+            }
+        }
+    }
+
+    /// Stores the key in the near-cache after a read has returned a value. The
+    /// key remains cached until the server sends an invalidation for it.
+    fn near_cache_insert(&mut self, key: &[u8]) {
+        if let Some(near_cache) = &mut self.near_cache {
+            if !near_cache.contains(key) {
+                near_cache.insert(key.to_vec());
+            }
+        }
+    }
+
+    fn push(&mut self, data: Vec<Response>) {
+        RESP_PUSH.increment();
+
+        // invalidation messages contain either the array of invalidated keys
+        // or a null when the server has flushed all of the keys
+        if let [Response::BulkString(kind), keys] = data.as_slice() {
+            if &kind[..] != b"invalidate" {
+                return;
+            }
+
+            RESP_INVALIDATE.increment();
+
+            match keys {
+                Response::Array(keys) => {
+                    RESP_INVALIDATE_KEYS.add(keys.len() as _);
+
+                    if let Some(near_cache) = &mut self.near_cache {
+                        for key in keys {
+                            if let Response::BulkString(key) = key {
+                                near_cache.remove(&key[..]);
+                            }
+                        }
+                    }
+                }
+                Response::Null => {
+                    RESP_INVALIDATE_FLUSH.increment();
+
+                    if let Some(near_cache) = &mut self.near_cache {
+                        near_cache.clear();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Codec for RespCodec {
+    type Request = RequestWithValidator;
+
+    // an error reply from the server does not leave the connection in a bad
+    // state, so it is preserved
+    const CLOSE_ON_EXCEPTION: bool = false;
+
+    async fn connect(&mut self, connection: &mut Connection) -> Result<()> {
+        self.near_cache = None;
+        self.replies = 0;
+        self.completed = None;
+
+        // the handshake consists of requests, so it is bounded by the request
+        // timeout
+        let request_timeout = self.config.client().unwrap().request_timeout();
+
+        timeout(request_timeout, self.handshake(connection))
+            .await
+            .map_err(|_| Error::new(ErrorKind::TimedOut, "handshake timeout"))?
+    }
+
+    fn request(&mut self, request: ClientRequest) -> std::result::Result<Self::Request, ()> {
+        RequestWithValidator::try_from(request)
+    }
+
+    fn compose(&mut self, request: &mut Self::Request, buffer: &mut Buffer) {
+        FUSED_REQUEST.add(request.fused.len() as _);
+
+        for key in &request.near_cache_keys {
+            self.near_cache_lookup(key);
+        }
+
+        for r in request.requests() {
+            r.compose(buffer);
+        }
+    }
+
+    fn receive(
+        &mut self,
+        request: &mut Self::Request,
+        buffer: &mut Buffer,
+    ) -> Result<Received<Self::Request>> {
+        let response = self.parser.parse((*buffer).borrow())?;
+        let consumed = response.consumed();
+
+        // the response borrows from the buffer, so it is handled before the
+        // buffer is advanced
+        let received = match response.into_inner() {
+            Response::Push(data) => {
+                self.push(data);
+                Received::Pending
+            }
+            response => self.reply(request, response),
+        };
+
+        buffer.advance(consumed);
+
+        Ok(received)
+    }
+}
+
+impl Inflight for RequestWithValidator {
+    fn exception(self) {
+        // use the validator to record the exception
+        let _ = (self.validator)(Response::Error(Vec::new().into()));
+    }
+
+    fn timeout(&self) -> &'static metriken::Counter {
+        self.timeout
+    }
+}
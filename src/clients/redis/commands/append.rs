@@ -0,0 +1,27 @@
+use super::*;
+
+/// Append data to the value for a key. A key which does not exist is created.
+impl From<workload::client::Append> for RequestWithValidator {
+    fn from(other: workload::client::Append) -> Self {
+        APPEND.increment();
+        RequestWithValidator::new(
+            Request::new("APPEND").arg(other.key).arg(other.value),
+            validate_response,
+            &APPEND_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            APPEND_STORED.increment();
+            APPEND_OK.increment();
+            Ok(())
+        }
+        _ => {
+            APPEND_EX.increment();
+            Err(())
+        }
+    }
+}
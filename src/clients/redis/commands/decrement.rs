@@ -0,0 +1,29 @@
+use super::*;
+
+/// Decrement the numeric value stored at a key. A key which does not exist is treated as
+/// holding `0`.
+impl From<workload::client::Decrement> for RequestWithValidator {
+    fn from(other: workload::client::Decrement) -> Self {
+        DECR.increment();
+        RequestWithValidator::new(
+            Request::new("DECRBY")
+                .arg(other.key)
+                .arg(other.amount as i64),
+            validate_response,
+            &DECR_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            DECR_OK.increment();
+            Ok(())
+        }
+        _ => {
+            DECR_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -2,29 +2,26 @@ use super::*;
 
 /// Delete a key from the cache. This will delete an entire Hash/Set/SortedSet
 /// if used in the same keyspace.
-pub async fn delete(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::Delete,
-) -> std::result::Result<(), ResponseError> {
-    DELETE.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.del::<&[u8], ()>(&request.key),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+impl From<workload::client::Delete> for RequestWithValidator {
+    fn from(other: workload::client::Delete) -> Self {
+        DELETE.increment();
+        RequestWithValidator::new(
+            Request::new("DEL").arg(other.key),
+            validate_response,
+            &DELETE_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
             DELETE_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             DELETE_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            DELETE_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
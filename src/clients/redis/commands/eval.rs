@@ -0,0 +1,60 @@
+use super::*;
+
+/// Calls a script which was loaded when the connection was established. If
+/// the server does not have the script, for example after `SCRIPT FLUSH` or a
+/// failover, the call is retried with `EVAL`, which also loads the script.
+impl From<workload::client::Eval> for RequestWithValidator {
+    fn from(other: workload::client::Eval) -> Self {
+        EVAL.increment();
+
+        let workload::client::Eval { script, keys, args } = other;
+
+        RequestWithValidator::new(
+            Request::new("EVALSHA")
+                .arg(script.sha.clone())
+                .arg(keys.len() as i64)
+                .args(keys.clone())
+                .args(args.clone()),
+            validate_response,
+            &EVAL_TIMEOUT,
+        )
+        .followup(move |response| match response {
+            Response::Error(message) if message.starts_with(b"NOSCRIPT") => {
+                Some(eval(&script, keys.clone(), args.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
+// Calls the script by sending the script itself.
+fn eval(script: &Script, keys: Vec<Arc<[u8]>>, args: Vec<Vec<u8>>) -> RequestWithValidator {
+    EVAL.increment();
+    RequestWithValidator::new(
+        Request::new("EVAL")
+            .arg(script.body.clone())
+            .arg(keys.len() as i64)
+            .args(keys)
+            .args(args),
+        validate_response,
+        &EVAL_TIMEOUT,
+    )
+}
+
+/// Any reply other than an error is a success, as the reply is determined by
+/// the script.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Error(message) => {
+            if message.starts_with(b"NOSCRIPT") {
+                EVAL_NOSCRIPT.increment();
+            }
+            EVAL_EX.increment();
+            Err(())
+        }
+        _ => {
+            EVAL_OK.increment();
+            Ok(())
+        }
+    }
+}
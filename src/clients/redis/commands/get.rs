@@ -1,35 +1,35 @@
 use super::*;
 
 /// Retrieve a key-value pair from the cache.
-pub async fn get(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::Get,
-) -> std::result::Result<(), ResponseError> {
-    GET.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.get::<&[u8], Option<Vec<u8>>>(&request.key),
-    )
-    .await
-    {
-        Ok(Ok(None)) => {
-            RESPONSE_MISS.increment();
-            GET_KEY_MISS.increment();
-            Ok(())
-        }
-        Ok(Ok(Some(_))) => {
+impl From<workload::client::Get> for RequestWithValidator {
+    fn from(other: workload::client::Get) -> Self {
+        GET.increment();
+        RequestWithValidator::new(
+            Request::new("GET").arg(other.key.clone()),
+            validate_response,
+            &GET_TIMEOUT,
+        )
+        .near_cache_keys(vec![other.key])
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::BulkString(_) => {
             RESPONSE_HIT.increment();
             GET_KEY_HIT.increment();
+            GET_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
-            GET_EX.increment();
-            Err(ResponseError::Exception)
+        Response::Null => {
+            RESPONSE_MISS.increment();
+            GET_KEY_MISS.increment();
+            GET_OK.increment();
+            Ok(())
         }
-        Err(_) => {
-            GET_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+        _ => {
+            GET_EX.increment();
+            Err(())
         }
     }
 }
@@ -0,0 +1,40 @@
+use super::*;
+
+/// Retrieve the value for a key and update its TTL. If there is no TTL, the
+/// existing TTL is removed.
+impl From<workload::client::GetAndTouch> for RequestWithValidator {
+    fn from(other: workload::client::GetAndTouch) -> Self {
+        GAT.increment();
+
+        let request = Request::new("GETEX").arg(other.key);
+
+        let request = match other.ttl {
+            Some(ttl) if ttl.subsec_nanos() == 0 => request.arg("EX").arg(ttl.as_secs() as i64),
+            Some(ttl) => request.arg("PX").arg(ttl.as_millis() as i64),
+            None => request.arg("PERSIST"),
+        };
+
+        RequestWithValidator::new(request, validate_response, &GAT_TIMEOUT)
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::BulkString(_) => {
+            RESPONSE_HIT.increment();
+            GAT_HIT.increment();
+            GAT_OK.increment();
+            Ok(())
+        }
+        Response::Null => {
+            RESPONSE_MISS.increment();
+            GAT_MISS.increment();
+            GAT_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GAT_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -1,30 +1,26 @@
 use super::*;
 
-/// Delete a field from a hash stored in the cache.
-pub async fn hash_delete(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::HashDelete,
-) -> std::result::Result<(), ResponseError> {
-    HASH_DELETE.increment();
-    let fields: Vec<&[u8]> = request.fields.iter().map(|v| v.borrow()).collect();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.hdel::<&[u8], Vec<&[u8]>, usize>(&request.key, fields),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+/// Delete one or more fields from a hash stored in the cache.
+impl From<workload::client::HashDelete> for RequestWithValidator {
+    fn from(other: workload::client::HashDelete) -> Self {
+        HASH_DELETE.increment();
+        RequestWithValidator::new(
+            Request::new("HDEL").arg(other.key).args(other.fields),
+            validate_response,
+            &HASH_DELETE_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
             HASH_DELETE_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             HASH_DELETE_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            HASH_DELETE_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
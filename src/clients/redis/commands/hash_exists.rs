@@ -1,37 +1,34 @@
 use super::*;
 
 /// Checks if a field exists in a hash.
-pub async fn hash_exists(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::HashExists,
-) -> std::result::Result<(), ResponseError> {
-    HASH_EXISTS.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.hexists::<&[u8], &[u8], bool>(&request.key, &request.field),
-    )
-    .await
-    {
-        Ok(Ok(true)) => {
+impl From<workload::client::HashExists> for RequestWithValidator {
+    fn from(other: workload::client::HashExists) -> Self {
+        HASH_EXISTS.increment();
+        RequestWithValidator::new(
+            Request::new("HEXISTS").arg(other.key).arg(other.field),
+            validate_response,
+            &HASH_EXISTS_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(1) => {
             RESPONSE_HIT.increment();
             HASH_EXISTS_HIT.increment();
             HASH_EXISTS_OK.increment();
             Ok(())
         }
-        Ok(Ok(false)) => {
+        Response::Integer(0) => {
             RESPONSE_MISS.increment();
             HASH_EXISTS_MISS.increment();
             HASH_EXISTS_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             HASH_EXISTS_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            HASH_EXISTS_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,79 +1,57 @@
 use super::*;
 
-/// Retrieves the value for on or more fields in a hash.
-pub async fn hash_get(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::HashGet,
-) -> std::result::Result<(), ResponseError> {
-    HASH_GET.increment();
+/// Retrieves the value for one or more fields in a hash.
+impl From<workload::client::HashGet> for RequestWithValidator {
+    fn from(other: workload::client::HashGet) -> Self {
+        HASH_GET.increment();
 
-    let result = if request.fields.len() == 1 {
-        match timeout(
-            config.client().unwrap().request_timeout(),
-            connection.hget::<&[u8], &[u8], Option<Vec<u8>>>(&request.key, &request.fields[0]),
+        let request = if other.fields.len() == 1 {
+            Request::new("HGET")
+        } else {
+            Request::new("HMGET")
+        };
+
+        RequestWithValidator::new(
+            request.arg(other.key).args(other.fields),
+            validate_response,
+            &HASH_GET_TIMEOUT,
         )
-        .await
-        {
-            Ok(Ok(Some(_))) => {
-                RESPONSE_HIT.increment();
-                HASH_GET_FIELD_HIT.increment();
-                Ok(())
-            }
-            Ok(Ok(None)) => {
-                RESPONSE_MISS.increment();
-                HASH_GET_FIELD_MISS.increment();
-                Ok(())
-            }
-            Ok(Err(_)) => Err(ResponseError::Exception),
-            Err(_) => Err(ResponseError::Timeout),
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        // reply to `HGET`
+        Response::BulkString(_) => {
+            RESPONSE_HIT.increment();
+            HASH_GET_FIELD_HIT.increment();
+            HASH_GET_OK.increment();
+            Ok(())
         }
-    } else {
-        let fields: Vec<&[u8]> = request.fields.iter().map(|f| &**f).collect();
-        match timeout(
-            config.client().unwrap().request_timeout(),
-            connection.hget::<&[u8], &[&[u8]], Option<Vec<Option<Vec<u8>>>>>(&request.key, &fields),
-        )
-        .await
-        {
-            Ok(Ok(Some(values))) => {
-                let mut hits = 0;
-                let mut misses = 0;
-                for value in values {
-                    if value.is_some() {
-                        hits += 1;
-                    } else {
-                        misses += 1;
-                    }
-                }
-                RESPONSE_HIT.add(hits);
-                RESPONSE_MISS.add(misses);
-                HASH_GET_FIELD_HIT.add(hits);
-                HASH_GET_FIELD_MISS.add(misses);
-                Ok(())
-            }
-            Ok(Ok(None)) => {
-                RESPONSE_MISS.add(fields.len() as _);
-                HASH_GET_FIELD_MISS.add(fields.len() as _);
-                Ok(())
-            }
-            Ok(Err(_)) => Err(ResponseError::Exception),
-            Err(_) => Err(ResponseError::Timeout),
+        Response::Null => {
+            RESPONSE_MISS.increment();
+            HASH_GET_FIELD_MISS.increment();
+            HASH_GET_OK.increment();
+            Ok(())
         }
-    };
+        // reply to `HMGET`
+        Response::Array(values) => {
+            let hits = values
+                .iter()
+                .filter(|v| matches!(v, Response::BulkString(_)))
+                .count() as u64;
+            let misses = values.len() as u64 - hits;
 
-    match result {
-        Ok(()) => {
+            RESPONSE_HIT.add(hits);
+            RESPONSE_MISS.add(misses);
+            HASH_GET_FIELD_HIT.add(hits);
+            HASH_GET_FIELD_MISS.add(misses);
             HASH_GET_OK.increment();
+            Ok(())
         }
-        Err(ResponseError::Exception) => {
+        _ => {
             HASH_GET_EX.increment();
+            Err(())
         }
-        Err(ResponseError::Timeout) => {
-            HASH_GET_TIMEOUT.increment();
-        }
-        _ => {}
     }
-
-    result
 }
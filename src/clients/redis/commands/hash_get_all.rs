@@ -1,37 +1,37 @@
 use super::*;
 
 /// Retrieve all fields for a hash.
-pub async fn hash_get_all(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::HashGetAll,
-) -> std::result::Result<(), ResponseError> {
-    HASH_GET_ALL.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.hgetall::<&[u8], Option<HashMap<Vec<u8>, Vec<u8>>>>(request.key.as_ref()),
-    )
-    .await
-    {
-        Ok(Ok(Some(_))) => {
-            RESPONSE_HIT.increment();
-            HASH_GET_ALL_OK.increment();
-            HASH_GET_ALL_HIT.increment();
-            Ok(())
-        }
-        Ok(Ok(None)) => {
-            RESPONSE_MISS.increment();
-            HASH_GET_ALL_OK.increment();
-            HASH_GET_ALL_MISS.increment();
-            Ok(())
-        }
-        Ok(Err(_)) => {
+impl From<workload::client::HashGetAll> for RequestWithValidator {
+    fn from(other: workload::client::HashGetAll) -> Self {
+        HASH_GET_ALL.increment();
+        RequestWithValidator::new(
+            Request::new("HGETALL").arg(other.key),
+            validate_response,
+            &HASH_GET_ALL_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    // RESP2 replies with a flat array of fields and values, RESP3 with a map.
+    // A hash which does not exist is returned as empty.
+    let empty = match response {
+        Response::Array(fields) => fields.is_empty(),
+        Response::Map(fields) => fields.is_empty(),
+        _ => {
             HASH_GET_ALL_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            HASH_GET_ALL_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            return Err(());
         }
+    };
+
+    if empty {
+        RESPONSE_MISS.increment();
+        HASH_GET_ALL_MISS.increment();
+    } else {
+        RESPONSE_HIT.increment();
+        HASH_GET_ALL_HIT.increment();
     }
+
+    HASH_GET_ALL_OK.increment();
+    Ok(())
 }
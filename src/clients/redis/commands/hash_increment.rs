@@ -1,76 +1,42 @@
 use super::*;
-use ::redis::RedisError;
-use std::result::Result;
-use tokio::time::error::Elapsed;
 
 /// Increment the value for a field within a hash.
 ///
-/// NOTE: if a TTL is specified for the keyspace, a second command is issued to
-/// set the ttl for the key if a TTL is not already set. The operation to set
-/// the expiration may fail and will not be retried. Both the `HINCR` and
-/// `EXPIRE`/`PEXPIRE` commands will count towards the request latency. The
+/// NOTE: if a TTL is specified for the keyspace, an `EXPIRE`/`PEXPIRE` is
+/// pipelined after the `HINCRBY` to set the ttl for the key if a TTL is not
+/// already set. Both replies count towards the request latency. The
 /// success/failure of the command to set the expiration does not count towards
 /// the request metrics (such as the number of requests, success rate, etc).
-pub async fn hash_increment(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::HashIncrement,
-) -> std::result::Result<(), ResponseError> {
-    HASH_INCR.increment();
-    let result = match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.hincr::<&[u8], &[u8], i64, i64>(&request.key, &request.field, request.amount),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
-            HASH_INCR_OK.increment();
-            Ok(())
-        }
-        Ok(Err(_)) => {
-            HASH_INCR_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            HASH_INCR_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
-        }
-    };
+impl From<workload::client::HashIncrement> for RequestWithValidator {
+    fn from(other: workload::client::HashIncrement) -> Self {
+        HASH_INCR.increment();
 
-    // If successful, we may need to set an expiration. This is best-effort only
-    if result.is_ok() && request.ttl.is_some() {
-        let ttl = request.ttl.unwrap();
+        let mut request = RequestWithValidator::new(
+            Request::new("HINCRBY")
+                .arg(other.key.clone())
+                .arg(other.field)
+                .arg(other.amount),
+            validate_response,
+            &HASH_INCR_TIMEOUT,
+        );
 
-        let (mut base_command, ttl) = if ttl.subsec_nanos() == 0 {
-            (::redis::cmd("EXPIRE"), ttl.as_secs())
-        } else {
-            (::redis::cmd("PEXPIRE"), ttl.as_nanos() as u64)
-        };
-
-        FUSED_REQUEST.increment();
+        if let Some(ttl) = other.ttl {
+            request = request.fuse(expire(other.key, ttl));
+        }
 
-        let fused_result: Result<Result<u64, RedisError>, Elapsed> = timeout(
-            config.client().unwrap().request_timeout(),
-            base_command
-                .arg(&*request.key)
-                .arg(ttl)
-                .arg("NX")
-                .query_async(connection),
-        )
-        .await;
+        request
+    }
+}
 
-        match fused_result {
-            Ok(Ok(_)) => {
-                FUSED_REQUEST_OK.increment();
-            }
-            Ok(Err(_)) => {
-                FUSED_REQUEST_TIMEOUT.increment();
-            }
-            Err(_) => {
-                FUSED_REQUEST_EX.increment();
-            }
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            HASH_INCR_OK.increment();
+            Ok(())
+        }
+        _ => {
+            HASH_INCR_EX.increment();
+            Err(())
         }
     }
-
-    result
 }
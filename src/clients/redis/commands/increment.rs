@@ -0,0 +1,29 @@
+use super::*;
+
+/// Increment the numeric value stored at a key. A key which does not exist is treated as
+/// holding `0`.
+impl From<workload::client::Increment> for RequestWithValidator {
+    fn from(other: workload::client::Increment) -> Self {
+        INCR.increment();
+        RequestWithValidator::new(
+            Request::new("INCRBY")
+                .arg(other.key)
+                .arg(other.amount as i64),
+            validate_response,
+            &INCR_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            INCR_OK.increment();
+            Ok(())
+        }
+        _ => {
+            INCR_EX.increment();
+            Err(())
+        }
+    }
+}
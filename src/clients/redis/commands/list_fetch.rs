@@ -1,29 +1,26 @@
 use super::*;
 
 /// Retrieve all elements from a list in the cache.
-pub async fn list_fetch(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::ListFetch,
-) -> std::result::Result<(), ResponseError> {
-    LIST_FETCH.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.lrange::<&[u8], Option<Vec<Vec<u8>>>>(request.key.as_ref(), 0, -1),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+impl From<workload::client::ListFetch> for RequestWithValidator {
+    fn from(other: workload::client::ListFetch) -> Self {
+        LIST_FETCH.increment();
+        RequestWithValidator::new(
+            Request::new("LRANGE").arg(other.key).arg(0_i64).arg(-1_i64),
+            validate_response,
+            &LIST_FETCH_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(_) => {
             LIST_FETCH_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             LIST_FETCH_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            LIST_FETCH_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,29 +1,26 @@
 use super::*;
 
 /// Retrieve the length of a list in the cache.
-pub async fn list_length(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::ListLength,
-) -> std::result::Result<(), ResponseError> {
-    LIST_LENGTH.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.llen::<&[u8], Option<u64>>(request.key.as_ref()),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+impl From<workload::client::ListLength> for RequestWithValidator {
+    fn from(other: workload::client::ListLength) -> Self {
+        LIST_LENGTH.increment();
+        RequestWithValidator::new(
+            Request::new("LLEN").arg(other.key),
+            validate_response,
+            &LIST_LENGTH_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
             LIST_LENGTH_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             LIST_LENGTH_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            LIST_LENGTH_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,29 +1,26 @@
 use super::*;
 
 /// Removes and returns the element from the back of a list.
-pub async fn list_pop_back(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::ListPopBack,
-) -> std::result::Result<(), ResponseError> {
-    LIST_POP_BACK.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.rpop::<&[u8], Option<Vec<u8>>>(request.key.as_ref(), None),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+impl From<workload::client::ListPopBack> for RequestWithValidator {
+    fn from(other: workload::client::ListPopBack) -> Self {
+        LIST_POP_BACK.increment();
+        RequestWithValidator::new(
+            Request::new("RPOP").arg(other.key),
+            validate_response,
+            &LIST_POP_BACK_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::BulkString(_) | Response::Null => {
             LIST_POP_BACK_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             LIST_POP_BACK_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            LIST_POP_BACK_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,29 +1,26 @@
 use super::*;
 
 /// Removes and returns an element from the front of a list.
-pub async fn list_pop_front(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::ListPopFront,
-) -> std::result::Result<(), ResponseError> {
-    LIST_POP_FRONT.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.lpop::<&[u8], Option<Vec<u8>>>(request.key.as_ref(), None),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+impl From<workload::client::ListPopFront> for RequestWithValidator {
+    fn from(other: workload::client::ListPopFront) -> Self {
+        LIST_POP_FRONT.increment();
+        RequestWithValidator::new(
+            Request::new("LPOP").arg(other.key),
+            validate_response,
+            &LIST_POP_FRONT_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::BulkString(_) | Response::Null => {
             LIST_POP_FRONT_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             LIST_POP_FRONT_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            LIST_POP_FRONT_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
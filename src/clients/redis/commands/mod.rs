@@ -1,20 +1,26 @@
 use super::*;
 
 mod add;
+mod append;
+mod decrement;
 mod delete;
+mod eval;
 mod get;
+mod get_and_touch;
 mod hash_delete;
 mod hash_exists;
 mod hash_get;
 mod hash_get_all;
 mod hash_increment;
 mod hash_set;
+mod increment;
 mod list_fetch;
 mod list_length;
 mod list_pop_back;
 mod list_pop_front;
 mod list_push_back;
 mod list_push_front;
+mod multi_get;
 mod ping;
 mod replace;
 mod set;
@@ -27,31 +33,59 @@ mod sorted_set_range;
 mod sorted_set_rank;
 mod sorted_set_remove;
 mod sorted_set_score;
+mod stream_add;
+mod stream_read;
+mod stream_read_group;
+mod stream_trim;
+mod touch;
+mod transaction;
 
-pub use add::*;
-pub use delete::*;
-pub use get::*;
-pub use hash_delete::*;
-pub use hash_exists::*;
-pub use hash_get::*;
-pub use hash_get_all::*;
-pub use hash_increment::*;
-pub use hash_set::*;
-pub use list_fetch::*;
-pub use list_length::*;
-pub use list_pop_back::*;
-pub use list_pop_front::*;
-pub use list_push_back::*;
-pub use list_push_front::*;
-pub use ping::*;
-pub use replace::*;
-pub use set::*;
-pub use set_add::*;
-pub use set_members::*;
-pub use set_remove::*;
-pub use sorted_set_add::*;
-pub use sorted_set_increment::*;
-pub use sorted_set_range::*;
-pub use sorted_set_rank::*;
-pub use sorted_set_remove::*;
-pub use sorted_set_score::*;
+use set::set_request;
+
+/// Builds a request which sets the expiration for a key if it does not already
+/// have one. This is fused onto requests which may create a collection.
+fn expire(key: Arc<[u8]>, ttl: Duration) -> Request {
+    if ttl.subsec_nanos() == 0 {
+        Request::new("EXPIRE")
+            .arg(key)
+            .arg(ttl.as_secs() as i64)
+            .arg("NX")
+    } else {
+        Request::new("PEXPIRE")
+            .arg(key)
+            .arg(ttl.as_millis() as i64)
+            .arg("NX")
+    }
+}
+
+/// The consumer group and consumer name used for reads from a stream.
+const STREAM_GROUP: &str = "rpc-perf";
+const STREAM_CONSUMER: &str = "rpc-perf";
+
+/// Returns the IDs of the entries in a reply to `XREAD` or `XREADGROUP` for a
+/// single stream. The reply is an array of streams for RESP2 and a map for
+/// RESP3, and is null if there are no entries.
+fn stream_entry_ids(response: &Response) -> Vec<Vec<u8>> {
+    let entries = match response {
+        Response::Array(streams) => match streams.first() {
+            Some(Response::Array(stream)) => stream.get(1),
+            _ => None,
+        },
+        Response::Map(streams) => streams.first().map(|(_, entries)| entries),
+        _ => None,
+    };
+
+    match entries {
+        Some(Response::Array(entries)) => entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Response::Array(entry) => match entry.first() {
+                    Some(Response::BulkString(id)) => Some(id.to_vec()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+/// Retrieve the values for multiple keys from the cache.
+impl From<workload::client::MultiGet> for RequestWithValidator {
+    fn from(other: workload::client::MultiGet) -> Self {
+        GET.increment();
+        RequestWithValidator::new(
+            Request::new("MGET").args(other.keys.iter().cloned()),
+            validate_response,
+            &GET_TIMEOUT,
+        )
+        .near_cache_keys(other.keys)
+    }
+}
+
+/// Hits and misses are counted for each key, while the request itself counts
+/// as a single success or exception.
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(values) => {
+            let hits = values
+                .iter()
+                .filter(|v| matches!(v, Response::BulkString(_)))
+                .count() as u64;
+            let misses = values.len() as u64 - hits;
+
+            RESPONSE_HIT.add(hits);
+            RESPONSE_MISS.add(misses);
+            GET_KEY_HIT.add(hits);
+            GET_KEY_MISS.add(misses);
+            GET_OK.increment();
+            Ok(())
+        }
+        _ => {
+            GET_EX.increment();
+            Err(())
+        }
+    }
+}
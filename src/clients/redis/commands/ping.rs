@@ -1,29 +1,22 @@
 use super::*;
 
 /// Sends a `PING` and expects a `PONG` response from the server.
-pub async fn ping(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    _request: workload::client::Ping,
-) -> std::result::Result<(), ResponseError> {
-    PING.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        ::redis::cmd("PING").query_async(connection),
-    )
-    .await
-    {
-        Ok(Ok(())) => {
+impl From<workload::client::Ping> for RequestWithValidator {
+    fn from(_other: workload::client::Ping) -> Self {
+        PING.increment();
+        RequestWithValidator::new(Request::new("PING"), validate_response, &PING_TIMEOUT)
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::SimpleString(s) if &s[..] == b"PONG" => {
             PING_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             PING_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            PING_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
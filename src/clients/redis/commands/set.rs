@@ -1,53 +1,38 @@
 use super::*;
 
 /// Sets a key-value pair in the cache.
-pub async fn set(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::Set,
-) -> std::result::Result<(), ResponseError> {
-    SET.increment();
-
-    let key = &*request.key;
-    let value = &*request.value;
-
-    let mut base_command = ::redis::cmd("SET");
-
-    let mut command = base_command.arg(key).arg(value);
-
-    if let Some(ttl) = request.ttl {
-        if ttl.subsec_nanos() == 0 {
-            command = base_command
-                .arg(key)
-                .arg("EX")
-                .arg(ttl.as_secs())
-                .arg(value);
-        } else {
-            command = base_command
-                .arg(key)
-                .arg("PX")
-                .arg(ttl.as_millis() as u64)
-                .arg(value);
-        }
+impl From<workload::client::Set> for RequestWithValidator {
+    fn from(other: workload::client::Set) -> Self {
+        SET.increment();
+        RequestWithValidator::new(
+            set_request(other.key, other.value, other.ttl),
+            validate_response,
+            &SET_TIMEOUT,
+        )
     }
+}
 
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        command.query_async(connection),
-    )
-    .await
-    {
-        Ok(Ok(())) => {
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::SimpleString(_) => {
             SET_STORED.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             SET_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            SET_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
+
+/// Builds a `SET` request with an optional expiration. Conditions such as `NX`
+/// may be appended by the caller.
+pub fn set_request(key: Arc<[u8]>, value: Vec<u8>, ttl: Option<Duration>) -> Request {
+    let request = Request::new("SET").arg(key).arg(value);
+
+    match ttl {
+        Some(ttl) if ttl.subsec_nanos() == 0 => request.arg("EX").arg(ttl.as_secs() as i64),
+        Some(ttl) => request.arg("PX").arg(ttl.as_millis() as i64),
+        None => request,
+    }
+}
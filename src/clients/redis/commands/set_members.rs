@@ -1,35 +1,34 @@
 use super::*;
 
 /// Return the members of a set.
-pub async fn set_members(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SetMembers,
-) -> std::result::Result<(), ResponseError> {
-    SET_MEMBERS.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.smembers::<&[u8], Option<Vec<Vec<u8>>>>(request.key.as_ref()),
-    )
-    .await
-    {
-        Ok(Ok(set)) => {
-            if set.is_some() {
-                RESPONSE_HIT.increment();
-            } else {
+impl From<workload::client::SetMembers> for RequestWithValidator {
+    fn from(other: workload::client::SetMembers) -> Self {
+        SET_MEMBERS.increment();
+        RequestWithValidator::new(
+            Request::new("SMEMBERS").arg(other.key),
+            validate_response,
+            &SET_MEMBERS_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    // RESP2 replies with an array, RESP3 with a set. A set which does not exist
+    // is returned as empty.
+    match response {
+        Response::Array(members) | Response::Set(members) => {
+            if members.is_empty() {
                 RESPONSE_MISS.increment();
+            } else {
+                RESPONSE_HIT.increment();
             }
 
             SET_MEMBERS_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             SET_MEMBERS_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            SET_MEMBERS_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,80 +1,43 @@
 use super::*;
-use ::redis::RedisError;
-use std::result::Result;
-use tokio::time::error::Elapsed;
 
 /// Increment the score for a member of a sorted set.
 ///
-/// NOTE: if a TTL is specified for the keyspace, a second command is issued to
-/// set the ttl for the key if a TTL is not already set. The operation to set
-/// the expiration may fail and will not be retried. Both the `ZINCR` and
-/// `EXPIRE`/`PEXPIRE` commands will count towards the request latency. The
+/// NOTE: if a TTL is specified for the keyspace, an `EXPIRE`/`PEXPIRE` is
+/// pipelined after the `ZINCRBY` to set the ttl for the key if a TTL is not
+/// already set. Both replies count towards the request latency. The
 /// success/failure of the command to set the expiration does not count towards
 /// the request metrics (such as the number of requests, success rate, etc).
-pub async fn sorted_set_increment(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SortedSetIncrement,
-) -> std::result::Result<(), ResponseError> {
-    SORTED_SET_INCR.increment();
-    let result = match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.zincr::<&[u8], &[u8], f64, String>(
-            &request.key,
-            &request.member,
-            request.amount,
-        ),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
-            SORTED_SET_INCR_OK.increment();
-            Ok(())
-        }
-        Ok(Err(_)) => {
-            SORTED_SET_INCR_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            SORTED_SET_INCR_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
-        }
-    };
+impl From<workload::client::SortedSetIncrement> for RequestWithValidator {
+    fn from(other: workload::client::SortedSetIncrement) -> Self {
+        SORTED_SET_INCR.increment();
 
-    // If successful, we may need to set an expiration. This is best-effort only
-    if result.is_ok() && request.ttl.is_some() {
-        let ttl = request.ttl.unwrap();
+        let mut request = RequestWithValidator::new(
+            Request::new("ZINCRBY")
+                .arg(other.key.clone())
+                .arg(other.amount)
+                .arg(other.member),
+            validate_response,
+            &SORTED_SET_INCR_TIMEOUT,
+        );
 
-        let (mut base_command, ttl) = if ttl.subsec_nanos() == 0 {
-            (::redis::cmd("EXPIRE"), ttl.as_secs())
-        } else {
-            (::redis::cmd("PEXPIRE"), ttl.as_nanos() as u64)
-        };
-
-        let fused_result: Result<Result<u64, RedisError>, Elapsed> = timeout(
-            config.client().unwrap().request_timeout(),
-            base_command
-                .arg(&*request.key)
-                .arg(ttl)
-                .arg("NX")
-                .query_async(connection),
-        )
-        .await;
+        if let Some(ttl) = other.ttl {
+            request = request.fuse(expire(other.key, ttl));
+        }
 
-        FUSED_REQUEST.increment();
+        request
+    }
+}
 
-        match fused_result {
-            Ok(Ok(_)) => {
-                FUSED_REQUEST_OK.increment();
-            }
-            Ok(Err(_)) => {
-                FUSED_REQUEST_TIMEOUT.increment();
-            }
-            Err(_) => {
-                FUSED_REQUEST_EX.increment();
-            }
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    // the new score is a bulk string with RESP2 and a double with RESP3
+    match response {
+        Response::BulkString(_) | Response::Double(_) => {
+            SORTED_SET_INCR_OK.increment();
+            Ok(())
+        }
+        _ => {
+            SORTED_SET_INCR_EX.increment();
+            Err(())
         }
     }
-
-    result
 }
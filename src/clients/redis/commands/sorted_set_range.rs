@@ -1,39 +1,32 @@
 use super::*;
 
 /// Performs a range query on a sorted set, returning the specified range of
-/// elements. Supports selecting a range of keys by index (rank).
-pub async fn sorted_set_range(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SortedSetRange,
-) -> std::result::Result<(), ResponseError> {
-    SORTED_SET_RANGE.increment();
+/// elements. Supports selecting a range of elements either by index (rank) or
+/// by score.
+impl From<workload::client::SortedSetRange> for RequestWithValidator {
+    fn from(other: workload::client::SortedSetRange) -> Self {
+        SORTED_SET_RANGE.increment();
 
-    let result = if !request.by_score {
-        timeout(
-            config.client().unwrap().request_timeout(),
-            connection.zrange::<&[u8], Vec<(Vec<u8>, f64)>>(
-                request.key.as_ref(),
-                request.start.unwrap_or(0).try_into().unwrap(),
-                request.end.unwrap_or(-1).try_into().unwrap(),
-            ),
-        )
-        .await
-    } else {
-        timeout(
-            config.client().unwrap().request_timeout(),
-            connection.zrangebyscore::<&[u8], f64, f64, Vec<(Vec<u8>, f64)>>(
-                request.key.as_ref(),
-                request.start.map(|v| v as f64).unwrap_or(f64::MIN),
-                request.end.map(|v| v as f64).unwrap_or(f64::MAX),
-            ),
-        )
-        .await
-    };
+        let request = if !other.by_score {
+            Request::new("ZRANGE")
+                .arg(other.key)
+                .arg(other.start.unwrap_or(0) as i64)
+                .arg(other.end.unwrap_or(-1) as i64)
+        } else {
+            Request::new("ZRANGEBYSCORE")
+                .arg(other.key)
+                .arg(other.start.map(|v| v as f64).unwrap_or(f64::NEG_INFINITY))
+                .arg(other.end.map(|v| v as f64).unwrap_or(f64::INFINITY))
+        };
 
-    match result {
-        Ok(Ok(set)) => {
-            if set.is_empty() {
+        RequestWithValidator::new(request, validate_response, &SORTED_SET_RANGE_TIMEOUT)
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(members) => {
+            if members.is_empty() {
                 RESPONSE_MISS.increment();
             } else {
                 RESPONSE_HIT.increment();
@@ -42,13 +35,9 @@ pub async fn sorted_set_range(
             SORTED_SET_RANGE_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             SORTED_SET_RANGE_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            SORTED_SET_RANGE_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
@@ -1,36 +1,32 @@
 use super::*;
 
 /// Returns the rank for a member in a sorted set.
-pub async fn sorted_set_rank(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SortedSetRank,
-) -> std::result::Result<(), ResponseError> {
-    SORTED_SET_RANK.increment();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection
-            .zrank::<&[u8], &[u8], Option<u64>>(request.key.as_ref(), request.member.as_ref()),
-    )
-    .await
-    {
-        Ok(Ok(rank)) => {
-            if rank.is_some() {
-                RESPONSE_HIT.increment();
-            } else {
-                RESPONSE_MISS.increment();
-            }
+impl From<workload::client::SortedSetRank> for RequestWithValidator {
+    fn from(other: workload::client::SortedSetRank) -> Self {
+        SORTED_SET_RANK.increment();
+        RequestWithValidator::new(
+            Request::new("ZRANK").arg(other.key).arg(other.member),
+            validate_response,
+            &SORTED_SET_RANK_TIMEOUT,
+        )
+    }
+}
 
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            RESPONSE_HIT.increment();
             SORTED_SET_RANK_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
-            SORTED_SET_RANK_EX.increment();
-            Err(ResponseError::Exception)
+        Response::Null => {
+            RESPONSE_MISS.increment();
+            SORTED_SET_RANK_OK.increment();
+            Ok(())
         }
-        Err(_) => {
-            SORTED_SET_RANK_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+        _ => {
+            SORTED_SET_RANK_EX.increment();
+            Err(())
         }
     }
 }
@@ -1,30 +1,26 @@
 use super::*;
 
-/// Removes a member from a sorted set.
-pub async fn sorted_set_remove(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SortedSetRemove,
-) -> std::result::Result<(), ResponseError> {
-    SORTED_SET_REMOVE.increment();
-    let members: Vec<&[u8]> = request.members.iter().map(|v| v.borrow()).collect();
-    match timeout(
-        config.client().unwrap().request_timeout(),
-        connection.zrem::<&[u8], Vec<&[u8]>, usize>(&request.key, members),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
+/// Removes one or more members from a sorted set.
+impl From<workload::client::SortedSetRemove> for RequestWithValidator {
+    fn from(other: workload::client::SortedSetRemove) -> Self {
+        SORTED_SET_REMOVE.increment();
+        RequestWithValidator::new(
+            Request::new("ZREM").arg(other.key).args(other.members),
+            validate_response,
+            &SORTED_SET_REMOVE_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
             SORTED_SET_REMOVE_OK.increment();
             Ok(())
         }
-        Ok(Err(_)) => {
+        _ => {
             SORTED_SET_REMOVE_EX.increment();
-            Err(ResponseError::Exception)
-        }
-        Err(_) => {
-            SORTED_SET_REMOVE_TIMEOUT.increment();
-            Err(ResponseError::Timeout)
+            Err(())
         }
     }
 }
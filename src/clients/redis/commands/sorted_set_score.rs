@@ -1,70 +1,50 @@
 use super::*;
 
 /// Returns the score of one or more members in a sorted set.
-pub async fn sorted_set_score(
-    connection: &mut Connection<net::Stream>,
-    config: &Config,
-    request: workload::client::SortedSetScore,
-) -> std::result::Result<(), ResponseError> {
-    SORTED_SET_SCORE.increment();
+impl From<workload::client::SortedSetScore> for RequestWithValidator {
+    fn from(other: workload::client::SortedSetScore) -> Self {
+        SORTED_SET_SCORE.increment();
 
-    let result = if request.members.len() == 1 {
-        match timeout(
-            config.client().unwrap().request_timeout(),
-            connection.zscore::<&[u8], &[u8], Option<f64>>(
-                request.key.as_ref(),
-                request.members[0].as_ref(),
-            ),
+        let request = if other.members.len() == 1 {
+            Request::new("ZSCORE")
+        } else {
+            Request::new("ZMSCORE")
+        };
+
+        RequestWithValidator::new(
+            request.arg(other.key).args(other.members),
+            validate_response,
+            &SORTED_SET_SCORE_TIMEOUT,
         )
-        .await
-        {
-            Ok(Ok(score)) => {
-                if score.is_some() {
-                    RESPONSE_HIT.increment();
-                } else {
-                    RESPONSE_MISS.increment();
-                }
-                Ok(())
-            }
-            Ok(Err(_)) => Err(ResponseError::Exception),
-            Err(_) => Err(ResponseError::Timeout),
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    // scores are bulk strings with RESP2 and doubles with RESP3
+    match response {
+        // reply to `ZSCORE`
+        Response::BulkString(_) | Response::Double(_) => {
+            RESPONSE_HIT.increment();
         }
-    } else {
-        let members: Vec<&[u8]> = request.members.iter().map(|v| v.borrow()).collect();
-        match timeout(
-            config.client().unwrap().request_timeout(),
-            connection
-                .zscore_multiple::<&[u8], &[u8], Vec<Option<f64>>>(request.key.as_ref(), &members),
-        )
-        .await
-        {
-            Ok(Ok(scores)) => {
-                for score in scores {
-                    if score.is_some() {
-                        RESPONSE_HIT.increment();
-                    } else {
-                        RESPONSE_MISS.increment();
-                    }
+        Response::Null => {
+            RESPONSE_MISS.increment();
+        }
+        // reply to `ZMSCORE`
+        Response::Array(scores) => {
+            for score in scores {
+                if score == Response::Null {
+                    RESPONSE_MISS.increment();
+                } else {
+                    RESPONSE_HIT.increment();
                 }
-                Ok(())
             }
-            Ok(Err(_)) => Err(ResponseError::Exception),
-            Err(_) => Err(ResponseError::Timeout),
-        }
-    };
-
-    match result {
-        Ok(_) => {
-            SORTED_SET_SCORE_OK.increment();
         }
-        Err(ResponseError::Exception) => {
+        _ => {
             SORTED_SET_SCORE_EX.increment();
+            return Err(());
         }
-        Err(ResponseError::Timeout) => {
-            SORTED_SET_SCORE_TIMEOUT.increment();
-        }
-        _ => {}
     }
 
-    result
+    SORTED_SET_SCORE_OK.increment();
+    Ok(())
 }
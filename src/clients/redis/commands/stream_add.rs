@@ -0,0 +1,36 @@
+use super::*;
+
+/// Appends an entry to a stream, optionally trimming the stream to an
+/// approximate maximum length.
+impl From<workload::client::StreamAdd> for RequestWithValidator {
+    fn from(other: workload::client::StreamAdd) -> Self {
+        STREAM_ADD.increment();
+
+        let mut request = Request::new("XADD").arg(other.key);
+
+        if let Some(maxlen) = other.maxlen {
+            request = request.arg("MAXLEN").arg("~").arg(maxlen as i64);
+        }
+
+        request = request.arg("*");
+
+        for (field, value) in other.fields {
+            request = request.arg(field).arg(value);
+        }
+
+        RequestWithValidator::new(request, validate_response, &STREAM_ADD_TIMEOUT)
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::BulkString(_) => {
+            STREAM_ADD_OK.increment();
+            Ok(())
+        }
+        _ => {
+            STREAM_ADD_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+/// Reads entries from the start of a stream.
+impl From<workload::client::StreamRead> for RequestWithValidator {
+    fn from(other: workload::client::StreamRead) -> Self {
+        STREAM_READ.increment();
+        RequestWithValidator::new(
+            Request::new("XREAD")
+                .arg("COUNT")
+                .arg(other.count as i64)
+                .arg("STREAMS")
+                .arg(other.key)
+                .arg("0"),
+            validate_response,
+            &STREAM_READ_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(_) | Response::Map(_) | Response::Null => {
+            if stream_entry_ids(&response).is_empty() {
+                RESPONSE_MISS.increment();
+                STREAM_READ_MISS.increment();
+            } else {
+                RESPONSE_HIT.increment();
+                STREAM_READ_HIT.increment();
+            }
+            STREAM_READ_OK.increment();
+            Ok(())
+        }
+        _ => {
+            STREAM_READ_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+/// Reads new entries from a stream as a member of the consumer group. Any
+/// entries which are read are then acknowledged. If the group does not exist,
+/// it is created instead.
+impl From<workload::client::StreamReadGroup> for RequestWithValidator {
+    fn from(other: workload::client::StreamReadGroup) -> Self {
+        STREAM_READ_GROUP.increment();
+
+        let key = other.key.clone();
+
+        RequestWithValidator::new(
+            Request::new("XREADGROUP")
+                .arg("GROUP")
+                .arg(STREAM_GROUP)
+                .arg(STREAM_CONSUMER)
+                .arg("COUNT")
+                .arg(other.count as i64)
+                .arg("STREAMS")
+                .arg(other.key)
+                .arg(">"),
+            validate_response,
+            &STREAM_READ_GROUP_TIMEOUT,
+        )
+        .followup(move |response| match response {
+            Response::Error(message) if message.starts_with(b"NOGROUP") => {
+                Some(create_group(key.clone()))
+            }
+            _ => {
+                let ids = stream_entry_ids(response);
+
+                if ids.is_empty() {
+                    None
+                } else {
+                    Some(ack(key.clone(), ids))
+                }
+            }
+        })
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(_) | Response::Map(_) | Response::Null => {
+            if stream_entry_ids(&response).is_empty() {
+                RESPONSE_MISS.increment();
+                STREAM_READ_GROUP_MISS.increment();
+            } else {
+                RESPONSE_HIT.increment();
+                STREAM_READ_GROUP_HIT.increment();
+            }
+            STREAM_READ_GROUP_OK.increment();
+            Ok(())
+        }
+        _ => {
+            STREAM_READ_GROUP_EX.increment();
+            Err(())
+        }
+    }
+}
+
+// Acknowledges entries which were read by the consumer group.
+fn ack(key: Arc<[u8]>, ids: Vec<Vec<u8>>) -> RequestWithValidator {
+    STREAM_ACK.increment();
+    RequestWithValidator::new(
+        Request::new("XACK").arg(key).arg(STREAM_GROUP).args(ids),
+        |response| match response {
+            Response::Integer(_) => {
+                STREAM_ACK_OK.increment();
+                Ok(())
+            }
+            _ => {
+                STREAM_ACK_EX.increment();
+                Err(())
+            }
+        },
+        &STREAM_ACK_TIMEOUT,
+    )
+}
+
+// Creates the consumer group, and the stream if it does not exist. Another
+// connection may have created the group first, which is not an error.
+fn create_group(key: Arc<[u8]>) -> RequestWithValidator {
+    STREAM_GROUP_CREATE.increment();
+    RequestWithValidator::new(
+        Request::new("XGROUP")
+            .arg("CREATE")
+            .arg(key)
+            .arg(STREAM_GROUP)
+            .arg("0")
+            .arg("MKSTREAM"),
+        |response| match response {
+            Response::SimpleString(_) => {
+                STREAM_GROUP_CREATE_OK.increment();
+                Ok(())
+            }
+            Response::Error(message) if message.starts_with(b"BUSYGROUP") => {
+                STREAM_GROUP_CREATE_OK.increment();
+                Ok(())
+            }
+            _ => {
+                STREAM_GROUP_CREATE_EX.increment();
+                Err(())
+            }
+        },
+        &STREAM_GROUP_CREATE_TIMEOUT,
+    )
+}
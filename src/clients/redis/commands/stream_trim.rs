@@ -0,0 +1,30 @@
+use super::*;
+
+/// Trims a stream to an approximate maximum length.
+impl From<workload::client::StreamTrim> for RequestWithValidator {
+    fn from(other: workload::client::StreamTrim) -> Self {
+        STREAM_TRIM.increment();
+        RequestWithValidator::new(
+            Request::new("XTRIM")
+                .arg(other.key)
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(other.maxlen as i64),
+            validate_response,
+            &STREAM_TRIM_TIMEOUT,
+        )
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(_) => {
+            STREAM_TRIM_OK.increment();
+            Ok(())
+        }
+        _ => {
+            STREAM_TRIM_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+/// Update the TTL for a key. If there is no TTL, the existing TTL is removed.
+impl From<workload::client::Touch> for RequestWithValidator {
+    fn from(other: workload::client::Touch) -> Self {
+        TOUCH.increment();
+
+        let request = match other.ttl {
+            Some(ttl) if ttl.subsec_nanos() == 0 => Request::new("EXPIRE")
+                .arg(other.key)
+                .arg(ttl.as_secs() as i64),
+            Some(ttl) => Request::new("PEXPIRE")
+                .arg(other.key)
+                .arg(ttl.as_millis() as i64),
+            None => Request::new("PERSIST").arg(other.key),
+        };
+
+        RequestWithValidator::new(request, validate_response, &TOUCH_TIMEOUT)
+    }
+}
+
+pub fn validate_response(response: Response) -> std::result::Result<(), ()> {
+    match response {
+        Response::Integer(1) => {
+            RESPONSE_HIT.increment();
+            TOUCH_HIT.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        Response::Integer(0) => {
+            RESPONSE_MISS.increment();
+            TOUCH_MISS.increment();
+            TOUCH_OK.increment();
+            Ok(())
+        }
+        _ => {
+            TOUCH_EX.increment();
+            Err(())
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use super::*;
+
+/// Sends the requests together as a `MULTI`/`EXEC` transaction. The `MULTI` and
+/// the queued requests are pipelined ahead of the `EXEC`, and each request is
+/// validated using its reply from the `EXEC`.
+///
+/// Requests which send a further request based on their reply, such as `eval`
+/// and `stream_read_group`, can not be used within a transaction as their
+/// reply is not known until the `EXEC`.
+impl TryFrom<workload::client::Transaction> for RequestWithValidator {
+    type Error = ();
+    fn try_from(
+        other: workload::client::Transaction,
+    ) -> std::result::Result<RequestWithValidator, ()> {
+        let mut prefix = vec![Request::new("MULTI")];
+        let mut validators = Vec::with_capacity(other.requests.len());
+        let mut near_cache_keys = Vec::new();
+        let mut near_cache_replies = Vec::new();
+
+        // the position of the reply for the next request in the `EXEC` reply
+        let mut position = 0;
+
+        for request in other.requests {
+            let request = RequestWithValidator::try_from(request)?;
+
+            if request.followup.is_some() || !request.prefix.is_empty() {
+                return Err(());
+            }
+
+            FUSED_REQUEST.add(request.fused.len() as _);
+
+            if !request.near_cache_keys.is_empty() {
+                near_cache_keys.extend(request.near_cache_keys.iter().cloned());
+                near_cache_replies.push((position, request.near_cache_keys));
+            }
+
+            position += 1 + request.fused.len();
+
+            validators.push((request.validator, request.fused.len()));
+            prefix.push(request.request);
+            prefix.extend(request.fused);
+        }
+
+        TRANSACTION.increment();
+
+        let mut request = RequestWithValidator::new(
+            Request::new("EXEC"),
+            move |response| validate_response(&validators, response),
+            &TRANSACTION_TIMEOUT,
+        )
+        .near_cache_keys(near_cache_keys);
+        request.prefix = prefix;
+        request.near_cache_replies = near_cache_replies;
+
+        Ok(request)
+    }
+}
+
+type Validator = Box<dyn Fn(Response) -> std::result::Result<(), ()> + Send>;
+
+/// Each request and its fused requests has a reply in the `EXEC` reply. If the
+/// transaction is aborted, the requests are recorded as exceptions.
+pub fn validate_response(
+    validators: &[(Validator, usize)],
+    response: Response,
+) -> std::result::Result<(), ()> {
+    match response {
+        Response::Array(replies) => {
+            let mut replies = replies.into_iter();
+            let mut result = Ok(());
+
+            for (validator, fused) in validators {
+                let reply = replies.next().unwrap_or(Response::Error(Vec::new().into()));
+
+                if validator(reply).is_err() {
+                    result = Err(());
+                }
+
+                for reply in replies.by_ref().take(*fused) {
+                    if reply.is_error() {
+                        FUSED_REQUEST_EX.increment();
+                    } else {
+                        FUSED_REQUEST_OK.increment();
+                    }
+                }
+            }
+
+            if result.is_ok() {
+                TRANSACTION_OK.increment();
+            } else {
+                TRANSACTION_EX.increment();
+            }
+
+            result
+        }
+        response => {
+            if let Response::Error(message) = &response {
+                if message.starts_with(b"EXECABORT") {
+                    TRANSACTION_EXECABORT.increment();
+                }
+            }
+
+            for (validator, _) in validators {
+                let _ = validator(Response::Error(Vec::new().into()));
+            }
+
+            TRANSACTION_EX.increment();
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workload::client::{Eval, Get, MultiGet, Script, Transaction};
+
+    fn key(key: &str) -> Arc<[u8]> {
+        key.as_bytes().into()
+    }
+
+    #[test]
+    fn near_cache_keys() {
+        let request = RequestWithValidator::try_from(Transaction {
+            requests: vec![
+                ClientRequest::Get(Get { key: key("a") }),
+                ClientRequest::MultiGet(MultiGet {
+                    keys: vec![key("b"), key("c")],
+                }),
+                ClientRequest::Get(Get { key: key("d") }),
+            ],
+        })
+        .unwrap();
+
+        assert_eq!(
+            request.near_cache_keys,
+            vec![key("a"), key("b"), key("c"), key("d")]
+        );
+
+        let response = Response::Array(vec![
+            Response::BulkString(b"1".to_vec().into()),
+            Response::Array(vec![
+                Response::Null,
+                Response::BulkString(b"3".to_vec().into()),
+            ]),
+            Response::Null,
+        ]);
+
+        assert_eq!(request.near_cache_hits(&response), vec![key("a"), key("c")]);
+
+        // an aborted transaction returns no values
+        let response = Response::Error(b"EXECABORT".to_vec().into());
+
+        assert!(request.near_cache_hits(&response).is_empty());
+    }
+
+    #[test]
+    fn followup() {
+        let script = Arc::new(Script {
+            sha: key("e0e1f9fabfc9d4800c877a703b823ac0578ff8db"),
+            body: key("return 1"),
+        });
+
+        let result = RequestWithValidator::try_from(Transaction {
+            requests: vec![ClientRequest::Eval(Eval {
+                script,
+                keys: vec![key("a")],
+                args: Vec::new(),
+            })],
+        });
+
+        assert!(result.is_err());
+    }
+}
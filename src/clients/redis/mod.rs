@@ -1,22 +1,190 @@
+use super::pipeline;
 use super::*;
-use crate::net::Connector;
-use ::redis::aio::Connection;
-use ::redis::{AsyncCommands, RedisConnectionInfo};
-use std::borrow::Borrow;
+use crate::workload::Script;
+use protocol::{Request, Response};
+use std::sync::Arc;
 
+mod codec;
 mod commands;
+pub(crate) mod protocol;
 
-use commands::*;
+use codec::RespCodec;
+
+struct RequestWithValidator {
+    // requests which are pipelined before the request and whose replies are
+    // not validated, such as the `MULTI` and the commands in a transaction
+    prefix: Vec<Request>,
+    request: Request,
+    // additional requests which are pipelined after the request, such as
+    // setting an expiration or truncating a list
+    fused: Vec<Request>,
+    // keys which would be held in a near-cache if the request is a read which
+    // results in a hit
+    near_cache_keys: Vec<Arc<[u8]>>,
+    // for a transaction, the position of the reply for each of its requests
+    // within the `EXEC` reply, along with the near-cache keys of the request
+    near_cache_replies: Vec<(usize, Vec<Arc<[u8]>>)>,
+    validator: Box<dyn Fn(Response) -> std::result::Result<(), ()> + Send>,
+    // builds a request which depends on the response and is sent once the
+    // response is received, such as acknowledging entries read from a stream
+    followup: Option<Box<dyn Fn(&Response) -> Option<RequestWithValidator> + Send>>,
+    // incremented if the request times out
+    timeout: &'static metriken::Counter,
+}
+
+impl RequestWithValidator {
+    fn new(
+        request: Request,
+        validator: impl Fn(Response) -> std::result::Result<(), ()> + Send + 'static,
+        timeout: &'static metriken::Counter,
+    ) -> Self {
+        Self {
+            prefix: Vec::new(),
+            request,
+            fused: Vec::new(),
+            near_cache_keys: Vec::new(),
+            near_cache_replies: Vec::new(),
+            validator: Box::new(validator),
+            followup: None,
+            timeout,
+        }
+    }
+
+    fn fuse(mut self, request: Request) -> Self {
+        self.fused.push(request);
+        self
+    }
+
+    fn near_cache_keys(mut self, keys: Vec<Arc<[u8]>>) -> Self {
+        self.near_cache_keys = keys;
+        self
+    }
+
+    fn followup(
+        mut self,
+        followup: impl Fn(&Response) -> Option<RequestWithValidator> + Send + 'static,
+    ) -> Self {
+        self.followup = Some(Box::new(followup));
+        self
+    }
+
+    // Returns all of the requests which are sent, in the order they are sent.
+    fn requests(&self) -> Vec<&Request> {
+        let mut requests = Vec::with_capacity(self.prefix.len() + 1 + self.fused.len());
+        requests.extend(self.prefix.iter());
+        requests.push(&self.request);
+        requests.extend(self.fused.iter());
+        requests
+    }
+
+    // Returns the keys whose values were returned in the response, which would
+    // now be held in a near-cache.
+    fn near_cache_hits(&self, response: &Response) -> Vec<Arc<[u8]>> {
+        if self.near_cache_replies.is_empty() {
+            return near_cache_hits(&self.near_cache_keys, response);
+        }
+
+        let mut hits = Vec::new();
+
+        if let Response::Array(replies) = response {
+            for (position, keys) in &self.near_cache_replies {
+                if let Some(reply) = replies.get(*position) {
+                    hits.extend(near_cache_hits(keys, reply));
+                }
+            }
+        }
+
+        hits
+    }
+}
+
+// Returns the keys for a read whose values were returned in the response.
+fn near_cache_hits(keys: &[Arc<[u8]>], response: &Response) -> Vec<Arc<[u8]>> {
+    match response {
+        Response::BulkString(_) => keys.iter().take(1).cloned().collect(),
+        Response::Array(values) => keys
+            .iter()
+            .zip(values)
+            .filter(|(_, value)| matches!(value, Response::BulkString(_)))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The verbs this client supports.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    verbs: &[
+        Verb::Ping,
+        Verb::Add,
+        Verb::Get,
+        Verb::Set,
+        Verb::Delete,
+        Verb::Replace,
+        Verb::Increment,
+        Verb::Decrement,
+        Verb::Touch,
+        Verb::GetAndTouch,
+        Verb::Append,
+        Verb::Transaction,
+        Verb::Eval,
+        Verb::HashDelete,
+        Verb::HashExists,
+        Verb::HashGet,
+        Verb::HashGetAll,
+        Verb::HashIncrement,
+        Verb::HashSet,
+        Verb::ListPushFront,
+        Verb::ListPushBack,
+        Verb::ListFetch,
+        Verb::ListLength,
+        Verb::ListPopFront,
+        Verb::ListPopBack,
+        Verb::SetAdd,
+        Verb::SetMembers,
+        Verb::SetRemove,
+        Verb::SortedSetAdd,
+        Verb::SortedSetRange,
+        Verb::SortedSetIncrement,
+        Verb::SortedSetRank,
+        Verb::SortedSetRemove,
+        Verb::SortedSetScore,
+        Verb::StreamAdd,
+        Verb::StreamRead,
+        Verb::StreamReadGroup,
+        Verb::StreamTrim,
+    ],
+    multi_get: true,
+};
 
 /// Launch tasks with one conncetion per task as RESP protocol is not mux-enabled.
-pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+pub fn launch_tasks(
+    runtime: &mut Runtime,
+    config: Config,
+    work_receiver: Receiver<WorkItem>,
+    workload_components: &[Component],
+) {
     debug!("launching resp protocol tasks");
 
+    // the scripts are loaded on each connection as it is established
+    let mut scripts: Vec<Arc<Script>> = Vec::new();
+
+    for component in workload_components {
+        if let Component::Keyspace(keyspace) = component {
+            for script in keyspace.scripts() {
+                if !scripts.contains(script) {
+                    scripts.push(script.clone());
+                }
+            }
+        }
+    }
+
     // create one task per "connection"
     // note: these may be channels instead of connections for multiplexed protocols
     for _ in 0..config.client().unwrap().poolsize() {
         for endpoint in config.target().endpoints() {
-            runtime.spawn(task(
+            runtime.spawn(pipeline::task(
+                RespCodec::new(&config, scripts.clone()),
                 work_receiver.clone(),
                 endpoint.clone(),
                 config.clone(),
@@ -25,167 +193,105 @@ pub fn launch_tasks(runtime: &mut Runtime, config: Config, work_receiver: Receiv
     }
 }
 
-#[allow(dead_code)]
-#[allow(clippy::slow_vector_initialization)]
-async fn task(work_receiver: Receiver<WorkItem>, endpoint: String, config: Config) -> Result<()> {
-    trace!("launching resp task for endpoint: {endpoint}");
-    let connector = Connector::new(&config)?;
-
-    let redis_connection_info = RedisConnectionInfo {
-        db: 0,
-        username: None,
-        password: None,
-    };
-
-    let mut connection = None;
-
-    while RUNNING.load(Ordering::Relaxed) {
-        if connection.is_none() {
-            CONNECT.increment();
-            connection = match timeout(
-                config.client().unwrap().connect_timeout(),
-                connector.connect(&endpoint),
-            )
-            .await
-            {
-                Ok(Ok(c)) => {
-                    CONNECT_OK.increment();
-                    CONNECT_CURR.increment();
-                    if let Ok(c) = ::redis::aio::Connection::new(&redis_connection_info, c).await {
-                        Some(c)
-                    } else {
-                        CONNECT_EX.increment();
-                        sleep(Duration::from_millis(100)).await;
-                        continue;
-                    }
-                }
-                Ok(Err(e)) => {
-                    trace!("error connecting: {e}");
-                    CONNECT_EX.increment();
-                    sleep(Duration::from_millis(100)).await;
-                    continue;
-                }
-                Err(_) => {
-                    trace!("connect timeout");
-                    CONNECT_TIMEOUT.increment();
-                    sleep(Duration::from_millis(100)).await;
-                    continue;
-                }
-            }
-        }
+impl TryFrom<ClientRequest> for RequestWithValidator {
+    type Error = ();
+    fn try_from(other: ClientRequest) -> std::result::Result<RequestWithValidator, ()> {
+        match other {
+            /*
+             * PING
+             */
+            ClientRequest::Ping(r) => Ok(Self::from(r)),
 
-        let mut con = connection.take().unwrap();
-        let work_item = work_receiver
-            .recv()
-            .await
-            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
-
-        REQUEST.increment();
-        let start = Instant::now();
-        let result = match work_item {
-            WorkItem::Request { request, .. } => match request {
-                /*
-                 * PING
-                 */
-                ClientRequest::Ping(r) => ping(&mut con, &config, r).await,
-
-                /*
-                 * KEY-VALUE
-                 */
-                ClientRequest::Add(r) => add(&mut con, &config, r).await,
-                ClientRequest::Delete(r) => delete(&mut con, &config, r).await,
-                ClientRequest::Get(r) => get(&mut con, &config, r).await,
-                ClientRequest::Replace(r) => replace(&mut con, &config, r).await,
-                ClientRequest::Set(r) => set(&mut con, &config, r).await,
-
-                /*
-                 * HASHES (DICTIONARIES)
-                 */
-                ClientRequest::HashDelete(r) => hash_delete(&mut con, &config, r).await,
-                ClientRequest::HashExists(r) => hash_exists(&mut con, &config, r).await,
-                ClientRequest::HashIncrement(r) => hash_increment(&mut con, &config, r).await,
-                // transparently issues either a `hget` or `hmget`
-                ClientRequest::HashGet(r) => hash_get(&mut con, &config, r).await,
-                ClientRequest::HashGetAll(r) => hash_get_all(&mut con, &config, r).await,
-                ClientRequest::HashSet(r) => hash_set(&mut con, &config, r).await,
-
-                /*
-                 * LISTS
-                 */
-                // To truncate, we must fuse an LTRIM at the end of the LPUSH
-                ClientRequest::ListPushFront(r) => list_push_front(&mut con, &config, r).await,
-                // To truncate, we must fuse an RTRIM at the end of the RPUSH
-                ClientRequest::ListPushBack(r) => list_push_back(&mut con, &config, r).await,
-                ClientRequest::ListFetch(r) => list_fetch(&mut con, &config, r).await,
-                ClientRequest::ListLength(r) => list_length(&mut con, &config, r).await,
-                ClientRequest::ListPopFront(r) => list_pop_front(&mut con, &config, r).await,
-                ClientRequest::ListPopBack(r) => list_pop_back(&mut con, &config, r).await,
-
-                /*
-                 * SETS
-                 */
-                ClientRequest::SetAdd(r) => set_add(&mut con, &config, r).await,
-                ClientRequest::SetMembers(r) => set_members(&mut con, &config, r).await,
-                ClientRequest::SetRemove(r) => set_remove(&mut con, &config, r).await,
-
-                /*
-                 * SORTED SETS
-                 */
-                ClientRequest::SortedSetAdd(r) => sorted_set_add(&mut con, &config, r).await,
-                ClientRequest::SortedSetRange(r) => sorted_set_range(&mut con, &config, r).await,
-                ClientRequest::SortedSetIncrement(r) => {
-                    sorted_set_increment(&mut con, &config, r).await
-                }
-                ClientRequest::SortedSetRemove(r) => sorted_set_remove(&mut con, &config, r).await,
-                ClientRequest::SortedSetScore(r) => sorted_set_score(&mut con, &config, r).await,
-                ClientRequest::SortedSetRank(r) => sorted_set_rank(&mut con, &config, r).await,
-
-                /*
-                 * UNSUPPORTED
-                 */
-                _ => {
-                    REQUEST_UNSUPPORTED.increment();
-                    connection = Some(con);
-                    continue;
-                }
-            },
-            WorkItem::Reconnect => {
-                CONNECT_CURR.sub(1);
-                continue;
-            }
-        };
+            /*
+             * KEY-VALUE
+             */
+            ClientRequest::Add(r) => Ok(Self::from(r)),
+            ClientRequest::Append(r) => Ok(Self::from(r)),
+            ClientRequest::Decrement(r) => Ok(Self::from(r)),
+            ClientRequest::Delete(r) => Ok(Self::from(r)),
+            ClientRequest::Get(r) => Ok(Self::from(r)),
+            ClientRequest::GetAndTouch(r) => Ok(Self::from(r)),
+            ClientRequest::Increment(r) => Ok(Self::from(r)),
+            ClientRequest::MultiGet(r) => Ok(Self::from(r)),
+            ClientRequest::Replace(r) => Ok(Self::from(r)),
+            ClientRequest::Set(r) => Ok(Self::from(r)),
+            ClientRequest::Touch(r) => Ok(Self::from(r)),
 
-        REQUEST_OK.increment();
+            /*
+             * TRANSACTIONS AND SCRIPTING
+             */
+            // each of the requests in a transaction must be supported
+            ClientRequest::Transaction(r) => Self::try_from(r),
+            ClientRequest::Eval(r) => Ok(Self::from(r)),
 
-        let stop = Instant::now();
+            /*
+             * STREAMS
+             */
+            ClientRequest::StreamAdd(r) => Ok(Self::from(r)),
+            ClientRequest::StreamRead(r) => Ok(Self::from(r)),
+            // acknowledges the entries which are read with a followup request
+            ClientRequest::StreamReadGroup(r) => Ok(Self::from(r)),
+            ClientRequest::StreamTrim(r) => Ok(Self::from(r)),
 
-        let latency_ns = stop.duration_since(start).as_nanos() as u64;
+            /*
+             * HASHES (DICTIONARIES)
+             */
+            ClientRequest::HashDelete(r) => Ok(Self::from(r)),
+            ClientRequest::HashExists(r) => Ok(Self::from(r)),
+            ClientRequest::HashIncrement(r) => Ok(Self::from(r)),
+            // transparently issues either a `hget` or `hmget`
+            ClientRequest::HashGet(r) => Ok(Self::from(r)),
+            ClientRequest::HashGetAll(r) => Ok(Self::from(r)),
+            ClientRequest::HashSet(r) => Ok(Self::from(r)),
 
-        match result {
-            Ok(_) => {
-                connection = Some(con);
-                RESPONSE_OK.increment();
+            /*
+             * LISTS
+             */
+            // To truncate, we must fuse an LTRIM at the end of the LPUSH
+            ClientRequest::ListPushFront(r) => Ok(Self::from(r)),
+            // To truncate, we must fuse an LTRIM at the end of the RPUSH
+            ClientRequest::ListPushBack(r) => Ok(Self::from(r)),
+            ClientRequest::ListFetch(r) => Ok(Self::from(r)),
+            ClientRequest::ListLength(r) => Ok(Self::from(r)),
+            ClientRequest::ListPopFront(r) => Ok(Self::from(r)),
+            ClientRequest::ListPopBack(r) => Ok(Self::from(r)),
 
-                let _ = RESPONSE_LATENCY.increment(latency_ns);
-            }
-            Err(ResponseError::Exception) => {
-                CONNECT_CURR.decrement();
-                RESPONSE_EX.increment();
-            }
-            Err(ResponseError::Timeout) => {
-                CONNECT_CURR.decrement();
-                RESPONSE_TIMEOUT.increment();
-            }
-            Err(ResponseError::Ratelimited) => {
-                RESPONSE_RATELIMITED.increment();
-                connection = Some(con);
-            }
-            Err(ResponseError::BackendTimeout) => {
-                RESPONSE_BACKEND_TIMEOUT.increment();
-                connection = Some(con);
-            }
+            /*
+             * SETS
+             */
+            ClientRequest::SetAdd(r) => Ok(Self::from(r)),
+            ClientRequest::SetMembers(r) => Ok(Self::from(r)),
+            ClientRequest::SetRemove(r) => Ok(Self::from(r)),
+
+            /*
+             * SORTED SETS
+             */
+            ClientRequest::SortedSetAdd(r) => Ok(Self::from(r)),
+            ClientRequest::SortedSetRange(r) => Ok(Self::from(r)),
+            ClientRequest::SortedSetIncrement(r) => Ok(Self::from(r)),
+            ClientRequest::SortedSetRemove(r) => Ok(Self::from(r)),
+            ClientRequest::SortedSetScore(r) => Ok(Self::from(r)),
+            ClientRequest::SortedSetRank(r) => Ok(Self::from(r)),
+
+            /*
+             * UNSUPPORTED
+             */
+            _ => Err(()),
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = clients::tests::config(dir.path(), Protocol::Resp);
+
+        for request in clients::tests::requests(&config) {
+            assert!(RequestWithValidator::try_from(request).is_ok());
+        }
+    }
 }
@@ -0,0 +1,29 @@
+// A native RESP (REdis Serialization Protocol) implementation which operates
+// directly on byte buffers. Both RESP2 and RESP3 response types are supported.
+
+mod request;
+mod response;
+
+pub use request::Request;
+pub use response::{Response, ResponseParser};
+
+/// The result of a successful parse. Contains the parsed item and the number
+/// of bytes which were consumed from the buffer.
+pub struct ParseOk<T> {
+    inner: T,
+    consumed: usize,
+}
+
+impl<T> ParseOk<T> {
+    pub fn new(inner: T, consumed: usize) -> Self {
+        Self { inner, consumed }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+}
@@ -0,0 +1,207 @@
+use session::BufMut;
+use std::io::Write;
+use std::sync::Arc;
+
+/// A request to a RESP server. Requests are sent as an array of bulk strings,
+/// the first of which is the command name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    args: Vec<Argument>,
+}
+
+/// A single argument of a request. Keys and values are held without copying
+/// them, numbers are formatted directly into the write buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Argument {
+    Static(&'static [u8]),
+    Shared(Arc<[u8]>),
+    Owned(Vec<u8>),
+    Integer(i64),
+    Double(f64),
+}
+
+impl Request {
+    pub fn new(command: &'static str) -> Self {
+        Self {
+            args: vec![Argument::Static(command.as_bytes())],
+        }
+    }
+
+    /// Appends an argument to the request.
+    pub fn arg<T: Into<Argument>>(mut self, arg: T) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Appends a sequence of arguments to the request.
+    pub fn args<T: Into<Argument>, I: IntoIterator<Item = T>>(mut self, args: I) -> Self {
+        self.args.extend(args.into_iter().map(|a| a.into()));
+        self
+    }
+
+    /// Writes the request into the buffer and returns the number of bytes
+    /// written.
+    pub fn compose<B: BufMut>(&self, dst: &mut B) -> usize {
+        let mut len = header(dst, b'*', self.args.len());
+
+        for arg in &self.args {
+            len += arg.compose(dst);
+        }
+
+        len
+    }
+}
+
+impl Argument {
+    fn compose<B: BufMut>(&self, dst: &mut B) -> usize {
+        // numbers are formatted on the stack, the longest of which is an f64
+        // in exponent notation
+        let mut number = [0; 32];
+
+        let data: &[u8] = match self {
+            Self::Static(v) => v,
+            Self::Shared(v) => &v[..],
+            Self::Owned(v) => &v[..],
+            Self::Integer(v) => format(&mut number, format_args!("{v}")),
+            Self::Double(v) => {
+                if v.is_infinite() {
+                    if v.is_sign_positive() {
+                        &b"+inf"[..]
+                    } else {
+                        &b"-inf"[..]
+                    }
+                } else {
+                    format(&mut number, format_args!("{v:e}"))
+                }
+            }
+        };
+
+        let len = header(dst, b'$', data.len());
+        dst.put_slice(data);
+        dst.put_slice(b"\r\n");
+
+        len + data.len() + 2
+    }
+}
+
+// Writes the type byte and length line for an aggregate or a bulk string.
+fn header<B: BufMut>(dst: &mut B, kind: u8, len: usize) -> usize {
+    let mut number = [0; 32];
+    let len = format(&mut number, format_args!("{len}"));
+
+    dst.put_slice(&[kind]);
+    dst.put_slice(len);
+    dst.put_slice(b"\r\n");
+
+    len.len() + 3
+}
+
+fn format<'a>(buffer: &'a mut [u8; 32], args: std::fmt::Arguments) -> &'a [u8] {
+    let mut cursor: &mut [u8] = &mut buffer[..];
+    cursor
+        .write_fmt(args)
+        .expect("number is too long to format");
+    let len = 32 - cursor.len();
+
+    &buffer[..len]
+}
+
+impl From<&'static str> for Argument {
+    fn from(other: &'static str) -> Self {
+        Self::Static(other.as_bytes())
+    }
+}
+
+impl From<Arc<[u8]>> for Argument {
+    fn from(other: Arc<[u8]>) -> Self {
+        Self::Shared(other)
+    }
+}
+
+impl From<Vec<u8>> for Argument {
+    fn from(other: Vec<u8>) -> Self {
+        Self::Owned(other)
+    }
+}
+
+impl From<i64> for Argument {
+    fn from(other: i64) -> Self {
+        Self::Integer(other)
+    }
+}
+
+impl From<f64> for Argument {
+    fn from(other: f64) -> Self {
+        Self::Double(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Response, ResponseParser};
+    use super::*;
+    use session::Buffer;
+    use std::borrow::Borrow;
+
+    fn compose(request: &Request) -> Vec<u8> {
+        let mut buffer = Buffer::new(1024);
+        let len = request.compose(&mut buffer);
+
+        let bytes: &[u8] = buffer.borrow();
+        assert_eq!(len, bytes.len());
+
+        bytes.to_vec()
+    }
+
+    #[test]
+    fn compose_arguments() {
+        let request = Request::new("SET")
+            .arg(Arc::<[u8]>::from(&b"key"[..]))
+            .arg(b"value".to_vec())
+            .arg("EX")
+            .arg(60_i64);
+
+        assert_eq!(
+            compose(&request),
+            b"*5\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n$2\r\nEX\r\n$2\r\n60\r\n"
+        );
+    }
+
+    #[test]
+    fn compose_doubles() {
+        let request = Request::new("ZRANGEBYSCORE")
+            .arg(f64::NEG_INFINITY)
+            .arg(f64::INFINITY)
+            .arg(1.5);
+
+        assert_eq!(
+            compose(&request),
+            b"*4\r\n$13\r\nZRANGEBYSCORE\r\n$4\r\n-inf\r\n$4\r\n+inf\r\n$5\r\n1.5e0\r\n"
+        );
+    }
+
+    // a request is an array of bulk strings, so it can be read back with the
+    // response parser
+    #[test]
+    fn round_trip() {
+        let request = Request::new("HSET")
+            .arg(b"hash".to_vec())
+            .args(vec![b"field\r\n".to_vec(), Vec::new()])
+            .arg(-1_i64);
+
+        let bytes = compose(&request);
+        let parsed = ResponseParser::new().parse(&bytes).unwrap();
+
+        assert_eq!(parsed.consumed(), bytes.len());
+        assert_eq!(
+            parsed.into_inner(),
+            Response::Array(vec![
+                Response::BulkString(b"HSET".to_vec().into()),
+                Response::BulkString(b"hash".to_vec().into()),
+                Response::BulkString(b"field\r\n".to_vec().into()),
+                Response::BulkString(Vec::new().into()),
+                Response::BulkString(b"-1".to_vec().into()),
+            ])
+        );
+    }
+}
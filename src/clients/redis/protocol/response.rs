@@ -0,0 +1,602 @@
+use super::ParseOk;
+use std::borrow::Cow;
+use std::io::{Error, ErrorKind};
+
+// limit the nesting depth of aggregate types so that a malformed response
+// can't cause unbounded recursion
+const MAX_DEPTH: usize = 32;
+
+// the largest length of a blob or an aggregate which is accepted, this is the
+// default limit for the size of a bulk string in Redis
+const MAX_LEN: usize = 512 * 1024 * 1024;
+
+/// A response from a RESP server. Includes all RESP2 types as well as the
+/// additional types introduced with RESP3.
+///
+/// The data within a response is borrowed from the buffer it was parsed from,
+/// use `into_owned()` to keep the response after the buffer is advanced.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response<'a> {
+    /// `+OK\r\n`
+    SimpleString(Cow<'a, [u8]>),
+    /// `-ERR message\r\n` or the RESP3 blob error `!<len>\r\n<error>\r\n`
+    Error(Cow<'a, [u8]>),
+    /// `:<value>\r\n`
+    Integer(i64),
+    /// `$<len>\r\n<data>\r\n`
+    BulkString(Cow<'a, [u8]>),
+    /// `*<len>\r\n<elements>`
+    Array(Vec<Response<'a>>),
+    /// The RESP2 null bulk string (`$-1\r\n`) or array (`*-1\r\n`) as well as
+    /// the RESP3 null type (`_\r\n`)
+    Null,
+    /// RESP3 `,<floating-point-number>\r\n`
+    Double(f64),
+    /// RESP3 `#t\r\n` or `#f\r\n`
+    Boolean(bool),
+    /// RESP3 `(<big number>\r\n`
+    BigNumber(Cow<'a, [u8]>),
+    /// RESP3 `=<len>\r\n<encoding>:<data>\r\n`, includes the encoding prefix
+    VerbatimString(Cow<'a, [u8]>),
+    /// RESP3 `%<len>\r\n<key><value>...`
+    Map(Vec<(Response<'a>, Response<'a>)>),
+    /// RESP3 `~<len>\r\n<elements>`
+    Set(Vec<Response<'a>>),
+    /// RESP3 `><len>\r\n<elements>`, out-of-band data sent by the server
+    Push(Vec<Response<'a>>),
+}
+
+impl Response<'_> {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+
+    /// Copies any data which is borrowed from the buffer.
+    pub fn into_owned(self) -> Response<'static> {
+        fn owned(data: Cow<'_, [u8]>) -> Cow<'static, [u8]> {
+            Cow::Owned(data.into_owned())
+        }
+
+        fn all(values: Vec<Response<'_>>) -> Vec<Response<'static>> {
+            values.into_iter().map(Response::into_owned).collect()
+        }
+
+        match self {
+            Response::SimpleString(data) => Response::SimpleString(owned(data)),
+            Response::Error(data) => Response::Error(owned(data)),
+            Response::Integer(value) => Response::Integer(value),
+            Response::BulkString(data) => Response::BulkString(owned(data)),
+            Response::Array(values) => Response::Array(all(values)),
+            Response::Null => Response::Null,
+            Response::Double(value) => Response::Double(value),
+            Response::Boolean(value) => Response::Boolean(value),
+            Response::BigNumber(data) => Response::BigNumber(owned(data)),
+            Response::VerbatimString(data) => Response::VerbatimString(owned(data)),
+            Response::Map(entries) => Response::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect(),
+            ),
+            Response::Set(values) => Response::Set(all(values)),
+            Response::Push(values) => Response::Push(all(values)),
+        }
+    }
+}
+
+/// An incremental parser for RESP responses. Returns an error with the kind
+/// `WouldBlock` if the buffer does not yet contain a complete response.
+///
+/// The parser keeps track of how much of an incomplete response it has already
+/// scanned, so that a large response which arrives over many reads is only
+/// scanned once. Until a response is returned, each call must be passed a
+/// buffer which starts with the same response.
+#[derive(Clone, Default)]
+pub struct ResponseParser {
+    // the number of bytes of the incomplete response which have been scanned
+    scanned: usize,
+    // the number of values which remain to be scanned for the response and
+    // for each of the aggregates within it which are not yet complete
+    remaining: Vec<usize>,
+}
+
+impl ResponseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse<'a>(&mut self, buffer: &'a [u8]) -> Result<ParseOk<Response<'a>>, Error> {
+        let len = match self.scan(buffer) {
+            Ok(len) => len,
+            Err(e) => {
+                if e.kind() != ErrorKind::WouldBlock {
+                    self.reset();
+                }
+                return Err(e);
+            }
+        };
+
+        self.reset();
+
+        let (response, consumed) = parse_value(&buffer[..len], 0)?;
+
+        Ok(ParseOk::new(response, consumed))
+    }
+
+    fn reset(&mut self) {
+        self.scanned = 0;
+        self.remaining.clear();
+    }
+
+    // Finds the end of the first response in the buffer without building the
+    // response, resuming from where the previous call stopped. Returns the
+    // length of the response once it is complete.
+    fn scan(&mut self, buffer: &[u8]) -> Result<usize, Error> {
+        if self.remaining.is_empty() || self.scanned > buffer.len() {
+            self.reset();
+            self.remaining.push(1);
+        }
+
+        loop {
+            let buffer = &buffer[self.scanned..];
+
+            if buffer.is_empty() {
+                return Err(incomplete());
+            }
+
+            let (header, header_len) = line(&buffer[1..])?;
+
+            // account for the type byte
+            let mut len = header_len + 1;
+
+            // the number of values which are nested within this one
+            let mut values = 0;
+
+            match buffer[0] {
+                b'+' | b'-' | b':' | b'_' | b',' | b'#' | b'(' => {}
+                b'$' | b'!' | b'=' => {
+                    if let Some(n) = length(header)? {
+                        len = len
+                            .checked_add(n)
+                            .and_then(|len| len.checked_add(2))
+                            .ok_or_else(|| invalid("blob length is too large"))?;
+
+                        if buffer.len() < len {
+                            return Err(incomplete());
+                        }
+
+                        if &buffer[(len - 2)..len] != b"\r\n" {
+                            return Err(invalid("missing terminator for blob"));
+                        }
+                    }
+                }
+                b'*' | b'~' | b'>' => {
+                    values = length(header)?.unwrap_or(0);
+                }
+                b'%' | b'|' => {
+                    values = length(header)?
+                        .and_then(|n| n.checked_mul(2))
+                        .ok_or_else(|| invalid("invalid map length"))?;
+                }
+                _ => {
+                    return Err(invalid("unknown response type"));
+                }
+            }
+
+            self.scanned += len;
+
+            // attributes are followed by the value they describe, which is
+            // the value that is counted
+            if buffer[0] != b'|' {
+                if let Some(remaining) = self.remaining.last_mut() {
+                    *remaining -= 1;
+                }
+            }
+
+            if values > 0 {
+                if self.remaining.len() > MAX_DEPTH {
+                    return Err(invalid("response nesting is too deep"));
+                }
+
+                self.remaining.push(values);
+            }
+
+            while self.remaining.last() == Some(&0) {
+                self.remaining.pop();
+            }
+
+            if self.remaining.is_empty() {
+                return Ok(self.scanned);
+            }
+        }
+    }
+}
+
+fn incomplete() -> Error {
+    Error::from(ErrorKind::WouldBlock)
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+// Returns the content of the first line in the buffer without the trailing
+// CRLF along with the number of bytes consumed, including the CRLF.
+fn line(buffer: &[u8]) -> Result<(&[u8], usize), Error> {
+    match buffer.windows(2).position(|w| w == b"\r\n") {
+        Some(end) => Ok((&buffer[..end], end + 2)),
+        None => Err(incomplete()),
+    }
+}
+
+// Returns the length of a blob or an aggregate, or `None` for the RESP2 null
+// length of `-1`.
+fn length(bytes: &[u8]) -> Result<Option<usize>, Error> {
+    match integer(bytes)? {
+        -1 => Ok(None),
+        len if len < 0 => Err(invalid("negative length")),
+        len if len as u64 > MAX_LEN as u64 => Err(invalid("length is too large")),
+        len => Ok(Some(len as usize)),
+    }
+}
+
+fn integer(bytes: &[u8]) -> Result<i64, Error> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid("invalid integer"))
+}
+
+fn double(bytes: &[u8]) -> Result<f64, Error> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid("invalid double"))
+}
+
+fn parse_value(buffer: &[u8], depth: usize) -> Result<(Response<'_>, usize), Error> {
+    if depth > MAX_DEPTH {
+        return Err(invalid("response nesting is too deep"));
+    }
+
+    if buffer.is_empty() {
+        return Err(incomplete());
+    }
+
+    let (header, header_len) = line(&buffer[1..])?;
+
+    // account for the type byte
+    let mut consumed = header_len + 1;
+
+    let response = match buffer[0] {
+        b'+' => Response::SimpleString(header.into()),
+        b'-' => Response::Error(header.into()),
+        b':' => Response::Integer(integer(header)?),
+        b'_' => Response::Null,
+        b',' => Response::Double(double(header)?),
+        b'#' => match header {
+            b"t" => Response::Boolean(true),
+            b"f" => Response::Boolean(false),
+            _ => {
+                return Err(invalid("invalid boolean"));
+            }
+        },
+        b'(' => Response::BigNumber(header.into()),
+        b'$' | b'!' | b'=' => {
+            let len = match length(header)? {
+                Some(len) => len,
+                None => {
+                    return Ok((Response::Null, consumed));
+                }
+            };
+
+            let end = consumed
+                .checked_add(len)
+                .ok_or_else(|| invalid("blob length is too large"))?;
+
+            if buffer.len() < end.saturating_add(2) {
+                return Err(incomplete());
+            }
+
+            if &buffer[end..(end + 2)] != b"\r\n" {
+                return Err(invalid("missing terminator for blob"));
+            }
+
+            let data = Cow::Borrowed(&buffer[consumed..end]);
+            consumed = end + 2;
+
+            match buffer[0] {
+                b'$' => Response::BulkString(data),
+                b'!' => Response::Error(data),
+                _ => Response::VerbatimString(data),
+            }
+        }
+        b'*' | b'~' | b'>' => {
+            let len = match length(header)? {
+                Some(len) => len,
+                None => {
+                    return Ok((Response::Null, consumed));
+                }
+            };
+
+            let mut elements = Vec::with_capacity(std::cmp::min(len, 1024));
+
+            for _ in 0..len {
+                let (element, n) = parse_value(&buffer[consumed..], depth + 1)?;
+                consumed += n;
+                elements.push(element);
+            }
+
+            match buffer[0] {
+                b'*' => Response::Array(elements),
+                b'~' => Response::Set(elements),
+                _ => Response::Push(elements),
+            }
+        }
+        b'%' | b'|' => {
+            let len = length(header)?.ok_or_else(|| invalid("invalid map length"))?;
+
+            let mut entries = Vec::with_capacity(std::cmp::min(len, 1024));
+
+            for _ in 0..len {
+                let (key, n) = parse_value(&buffer[consumed..], depth + 1)?;
+                consumed += n;
+                let (value, n) = parse_value(&buffer[consumed..], depth + 1)?;
+                consumed += n;
+                entries.push((key, value));
+            }
+
+            if buffer[0] == b'%' {
+                Response::Map(entries)
+            } else {
+                // attributes are auxiliary data for the reply which follows
+                // them, we discard the attributes and return the reply
+                let (response, n) = parse_value(&buffer[consumed..], depth + 1)?;
+                consumed += n;
+                response
+            }
+        }
+        _ => {
+            return Err(invalid("unknown response type"));
+        }
+    };
+
+    Ok((response, consumed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(buffer: &[u8]) -> Result<(Response<'_>, usize), Error> {
+        let ok = ResponseParser::new().parse(buffer)?;
+        let consumed = ok.consumed();
+
+        Ok((ok.into_inner(), consumed))
+    }
+
+    // Every prefix of a complete response is incomplete, and the complete
+    // response consumes the whole buffer.
+    fn check(buffer: &[u8], expected: Response) {
+        for len in 0..buffer.len() {
+            let e = parse(&buffer[..len]).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::WouldBlock, "prefix of {len} bytes");
+        }
+
+        assert_eq!(parse(buffer).unwrap(), (expected, buffer.len()));
+    }
+
+    #[test]
+    fn resp2() {
+        check(b"+OK\r\n", Response::SimpleString(b"OK".to_vec().into()));
+        check(
+            b"-ERR unknown\r\n",
+            Response::Error(b"ERR unknown".to_vec().into()),
+        );
+        check(b":-42\r\n", Response::Integer(-42));
+        check(
+            b"$5\r\nhello\r\n",
+            Response::BulkString(b"hello".to_vec().into()),
+        );
+        check(b"$0\r\n\r\n", Response::BulkString(Vec::new().into()));
+        check(b"$-1\r\n", Response::Null);
+        check(b"*-1\r\n", Response::Null);
+        check(b"*0\r\n", Response::Array(Vec::new()));
+    }
+
+    #[test]
+    fn resp3() {
+        check(b"_\r\n", Response::Null);
+        check(b",1.5\r\n", Response::Double(1.5));
+        check(b",inf\r\n", Response::Double(f64::INFINITY));
+        check(b"#t\r\n", Response::Boolean(true));
+        check(b"#f\r\n", Response::Boolean(false));
+        check(
+            b"(3492890328409238509324850943850943825024385\r\n",
+            Response::BigNumber(
+                b"3492890328409238509324850943850943825024385"
+                    .to_vec()
+                    .into(),
+            ),
+        );
+        check(
+            b"!21\r\nSYNTAX invalid syntax\r\n",
+            Response::Error(b"SYNTAX invalid syntax".to_vec().into()),
+        );
+        check(
+            b"=15\r\ntxt:Some string\r\n",
+            Response::VerbatimString(b"txt:Some string".to_vec().into()),
+        );
+        check(
+            b"~2\r\n:1\r\n:2\r\n",
+            Response::Set(vec![Response::Integer(1), Response::Integer(2)]),
+        );
+        check(
+            b"%1\r\n+key\r\n$5\r\nvalue\r\n",
+            Response::Map(vec![(
+                Response::SimpleString(b"key".to_vec().into()),
+                Response::BulkString(b"value".to_vec().into()),
+            )]),
+        );
+    }
+
+    // binary data which contains a CRLF is read using the length
+    #[test]
+    fn binary_blob() {
+        check(
+            b"$4\r\n\r\n\r\n\r\n",
+            Response::BulkString(b"\r\n\r\n".to_vec().into()),
+        );
+    }
+
+    // the data is borrowed from the buffer until the response is made owned
+    #[test]
+    fn borrowed() {
+        let buffer = b"*2\r\n$5\r\nhello\r\n+OK\r\n";
+
+        let (response, _) = parse(buffer).unwrap();
+        match &response {
+            Response::Array(values) => {
+                assert!(matches!(values[0], Response::BulkString(Cow::Borrowed(_))));
+                assert!(matches!(
+                    values[1],
+                    Response::SimpleString(Cow::Borrowed(_))
+                ));
+            }
+            _ => panic!("expected an array"),
+        }
+
+        let owned = response.clone().into_owned();
+        assert_eq!(owned, response);
+        match owned {
+            Response::Array(values) => {
+                assert!(matches!(values[0], Response::BulkString(Cow::Owned(_))));
+            }
+            _ => panic!("expected an array"),
+        }
+    }
+
+    #[test]
+    fn push() {
+        check(
+            b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nfoo\r\n",
+            Response::Push(vec![
+                Response::BulkString(b"invalidate".to_vec().into()),
+                Response::Array(vec![Response::BulkString(b"foo".to_vec().into())]),
+            ]),
+        );
+    }
+
+    // attributes are discarded and the reply which follows is returned
+    #[test]
+    fn attributes() {
+        check(
+            b"|1\r\n+ttl\r\n:3600\r\n$3\r\nbar\r\n",
+            Response::BulkString(b"bar".to_vec().into()),
+        );
+
+        // attributes within an aggregate
+        check(
+            b"*2\r\n|1\r\n+a\r\n:1\r\n:2\r\n:3\r\n",
+            Response::Array(vec![Response::Integer(2), Response::Integer(3)]),
+        );
+    }
+
+    #[test]
+    fn nested() {
+        check(
+            b"*3\r\n:1\r\n*2\r\n$1\r\na\r\n%1\r\n+k\r\n~1\r\n#t\r\n$-1\r\n",
+            Response::Array(vec![
+                Response::Integer(1),
+                Response::Array(vec![
+                    Response::BulkString(b"a".to_vec().into()),
+                    Response::Map(vec![(
+                        Response::SimpleString(b"k".to_vec().into()),
+                        Response::Set(vec![Response::Boolean(true)]),
+                    )]),
+                ]),
+                Response::Null,
+            ]),
+        );
+    }
+
+    #[test]
+    fn too_deep() {
+        let mut buffer = Vec::new();
+        for _ in 0..=(MAX_DEPTH + 1) {
+            buffer.extend_from_slice(b"*1\r\n");
+        }
+        buffer.extend_from_slice(b":1\r\n");
+
+        assert_eq!(parse(&buffer).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    // a buffer which holds more than one response is parsed one at a time,
+    // such as a push which arrives ahead of a pipelined reply
+    #[test]
+    fn pipelined() {
+        let buffer = b">2\r\n$7\r\nmessage\r\n$2\r\nhi\r\n+OK\r\n:1";
+
+        let (first, consumed) = parse(buffer).unwrap();
+        assert!(matches!(first, Response::Push(_)));
+
+        let (second, n) = parse(&buffer[consumed..]).unwrap();
+        assert_eq!(second, Response::SimpleString(b"OK".to_vec().into()));
+
+        // the last reply has not been fully read yet
+        let e = parse(&buffer[(consumed + n)..]).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::WouldBlock);
+    }
+
+    // a response which arrives over several reads is parsed by resuming the
+    // scan, and the parser is then ready for the next response
+    #[test]
+    fn resume() {
+        let buffer = b"*3\r\n$5\r\nhello\r\n*1\r\n:1\r\n$-1\r\n+OK\r\n";
+        let end = buffer.len() - 5;
+
+        let mut parser = ResponseParser::new();
+
+        for len in 0..end {
+            let e = parser.parse(&buffer[..len]).map(|_| ()).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::WouldBlock, "prefix of {len} bytes");
+        }
+
+        let response = parser.parse(&buffer[..end]).unwrap();
+        assert_eq!(response.consumed(), end);
+        assert_eq!(
+            response.into_inner(),
+            Response::Array(vec![
+                Response::BulkString(b"hello".to_vec().into()),
+                Response::Array(vec![Response::Integer(1)]),
+                Response::Null,
+            ])
+        );
+
+        let response = parser.parse(&buffer[end..]).unwrap();
+        assert_eq!(response.consumed(), 5);
+        assert_eq!(
+            response.into_inner(),
+            Response::SimpleString(b"OK".to_vec().into())
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        for buffer in [
+            &b"?\r\n"[..],
+            b":abc\r\n",
+            b"#x\r\n",
+            b"$3\r\nabcde\r\n",
+            b"%-1\r\n",
+            b"$-2\r\n",
+            b"*-5\r\n",
+            b"$9223372036854775807\r\n",
+            b"*9223372036854775807\r\n",
+            b"%4611686018427387904\r\n",
+        ] {
+            let e = parse(buffer).unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::InvalidData, "{buffer:?}");
+        }
+    }
+}
@@ -1,5 +1,29 @@
 use super::*;
 
+fn resp_version() -> u8 {
+    2
+}
+
+fn memcache_meta_flags() -> Vec<MemcacheMetaFlag> {
+    vec![MemcacheMetaFlag::Value]
+}
+
+/// Flags for meta requests which control what is returned by the server.
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MemcacheMetaFlag {
+    /// Return the item value (`v`)
+    Value,
+    /// Return the remaining TTL (`t`)
+    Ttl,
+    /// Return the CAS value (`c`)
+    Cas,
+    /// Return the key (`k`)
+    Key,
+    /// Return the size of the item value (`s`)
+    Size,
+}
+
 #[derive(Clone, Deserialize)]
 pub struct Client {
     /// The number of connections this process will have to each endpoint.
@@ -30,6 +54,60 @@ pub struct Client {
     read_buffer_size: usize,
     #[serde(default = "default_buffer_size")]
     write_buffer_size: usize,
+
+    /// The maximum number of requests which may be outstanding on a single
+    /// connection. Requests are pipelined and the responses are matched to
+    /// them in the order they were sent. Only the memcache, memcache_meta,
+    /// memcache_binary, ping, and resp protocols support pipelining. The
+    /// default of `1` disables pipelining.
+    #[serde(default)]
+    pipeline_depth: usize,
+
+    /// The RESP protocol version to use. Either `2` (the default) or `3`. When
+    /// set to `3`, each connection sends `HELLO 3` after it is established.
+    #[serde(default = "resp_version")]
+    resp_version: u8,
+    /// Enables server-assisted client-side caching with `CLIENT TRACKING`.
+    /// Invalidations are delivered as push messages, so this requires that
+    /// `resp_version` is set to `3`.
+    #[serde(default)]
+    resp_tracking: bool,
+    /// Use broadcasting mode for client tracking. The server will send
+    /// invalidations for all modified keys instead of only the keys which have
+    /// been read by the connection.
+    #[serde(default)]
+    resp_tracking_bcast: bool,
+
+    /// The flags to add to `mg` and `ma` requests when using the memcache meta
+    /// protocol. Defaults to returning only the value.
+    #[serde(default = "memcache_meta_flags")]
+    memcache_meta_flags: Vec<MemcacheMetaFlag>,
+    /// Use quiet mode for meta requests. The server will not send the common
+    /// response for a request (eg: a miss for `mg` or a success for `ms`), so
+    /// each request is followed by a `mn` to mark the end of its response.
+    #[serde(default)]
+    memcache_meta_quiet: bool,
+    /// Enables stale-while-revalidate for the memcache meta protocol when set
+    /// to a non-zero number of seconds. `mg` requests will ask to win the
+    /// right to recache an item once its remaining TTL falls below this
+    /// value, and `md` requests will mark items as stale with this TTL instead
+    /// of removing them.
+    #[serde(default)]
+    memcache_meta_stale_while_revalidate: u64,
+
+    /// Use the quiet variants of requests for the memcache binary protocol
+    /// (eg: `GETQ` and `SETQ`). Each request is followed by a `NOOP` which
+    /// marks the end of its response.
+    #[serde(default)]
+    memcache_binary_quiet: bool,
+    /// The username for SASL PLAIN authentication with the memcache binary
+    /// protocol. Each connection authenticates after it is established.
+    #[serde(default)]
+    memcache_binary_username: Option<String>,
+    /// The password for SASL PLAIN authentication with the memcache binary
+    /// protocol.
+    #[serde(default)]
+    memcache_binary_password: Option<String>,
 }
 
 impl Client {
@@ -68,4 +146,70 @@ impl Client {
         // pagesize
         ((std::cmp::max(1, self.write_buffer_size) + PAGESIZE - 1) / PAGESIZE) * PAGESIZE
     }
+
+    pub fn pipeline_depth(&self) -> usize {
+        std::cmp::max(1, self.pipeline_depth)
+    }
+
+    pub fn resp_version(&self) -> u8 {
+        self.resp_version
+    }
+
+    pub fn resp_tracking(&self) -> bool {
+        self.resp_tracking
+    }
+
+    pub fn resp_tracking_bcast(&self) -> bool {
+        self.resp_tracking_bcast
+    }
+
+    pub fn memcache_meta_flags(&self) -> &[MemcacheMetaFlag] {
+        &self.memcache_meta_flags
+    }
+
+    pub fn memcache_meta_quiet(&self) -> bool {
+        self.memcache_meta_quiet
+    }
+
+    pub fn memcache_meta_stale_while_revalidate(&self) -> Option<u64> {
+        if self.memcache_meta_stale_while_revalidate == 0 {
+            None
+        } else {
+            Some(self.memcache_meta_stale_while_revalidate)
+        }
+    }
+
+    pub fn memcache_binary_quiet(&self) -> bool {
+        self.memcache_binary_quiet
+    }
+
+    pub fn memcache_binary_username(&self) -> Option<&str> {
+        self.memcache_binary_username.as_deref()
+    }
+
+    pub fn memcache_binary_password(&self) -> Option<&str> {
+        self.memcache_binary_password.as_deref()
+    }
+
+    pub fn validate(&self) {
+        if self.resp_version != 2 && self.resp_version != 3 {
+            eprintln!("resp_version must be either 2 or 3");
+            std::process::exit(2);
+        }
+
+        if self.resp_tracking && self.resp_version != 3 {
+            eprintln!("resp_tracking requires that resp_version is set to 3");
+            std::process::exit(2);
+        }
+
+        if self.resp_tracking_bcast && !self.resp_tracking {
+            eprintln!("resp_tracking_bcast requires that resp_tracking is enabled");
+            std::process::exit(2);
+        }
+
+        if self.memcache_binary_username.is_some() != self.memcache_binary_password.is_some() {
+            eprintln!("memcache_binary_username and memcache_binary_password must be set together");
+            std::process::exit(2);
+        }
+    }
 }
@@ -7,23 +7,25 @@ mod client;
 mod debug;
 mod general;
 mod metrics;
+mod momento;
 mod protocol;
 mod pubsub;
 mod target;
 mod tls;
 mod workload;
 
-pub use client::Client;
+pub use client::{Client, MemcacheMetaFlag};
 pub use debug::Debug;
 pub use general::General;
 pub use metrics::{Format as MetricsFormat, Metrics};
+pub use momento::{Momento, MomentoProfile};
 pub use protocol::Protocol;
-pub use pubsub::Pubsub;
+pub use pubsub::{KafkaCommit, KafkaSaslMechanism, Pubsub};
 pub use target::Target;
 pub use tls::Tls;
 pub use workload::{
-    Command, Distribution, Keyspace, RampCompletionAction, RampType, Topics, ValueKind, Verb,
-    Workload,
+    Command, Distribution, Keyspace, MessageLength, RampCompletionAction, RampType, Ratelimit,
+    Topics, ValueKind, Verb, Workload,
 };
 
 pub const PAGESIZE: usize = 4096;
@@ -38,6 +40,8 @@ pub struct Config {
     general: General,
     client: Option<Client>,
     debug: Debug,
+    #[serde(default)]
+    momento: Momento,
     pubsub: Option<Pubsub>,
     target: Target,
     tls: Option<Tls>,
@@ -69,7 +73,15 @@ impl Config {
             })
             .unwrap();
 
-        config.workload.ratelimit().validate();
+        config.workload.validate();
+        config.workload.validate_verbs(config.general.protocol());
+        config.momento.validate();
+        if let Some(client) = config.client.as_ref() {
+            client.validate();
+        }
+        if let Some(pubsub) = config.pubsub.as_ref() {
+            pubsub.validate();
+        }
         if config.metrics().is_none() {
             config.metrics = Metrics::from_general(&config.general);
         }
@@ -87,6 +99,10 @@ impl Config {
         self.client.as_ref()
     }
 
+    pub fn momento(&self) -> &Momento {
+        &self.momento
+    }
+
     pub fn pubsub(&self) -> Option<&Pubsub> {
         self.pubsub.as_ref()
     }
@@ -0,0 +1,82 @@
+use super::*;
+
+/// The Momento configuration profile, which sets the gRPC deadline and
+/// connection settings for the client.
+#[derive(Clone, Copy, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MomentoProfile {
+    #[default]
+    LowLatency,
+    InRegion,
+    Laptop,
+}
+
+/// Settings for the Momento cache and topics clients.
+#[derive(Clone, Default, Deserialize)]
+pub struct Momento {
+    /// The default TTL for items which are written without one, as a
+    /// humantime duration. Defaults to 900s.
+    #[serde(default)]
+    default_ttl: Option<String>,
+    /// The configuration profile. Defaults to low latency.
+    #[serde(default)]
+    profile: Option<MomentoProfile>,
+    /// Optionally, the deadline for each request in milliseconds. This uses
+    /// the default connection settings and may not be set with a profile.
+    #[serde(default)]
+    deadline: Option<u64>,
+    /// Optionally, the endpoint to connect to instead of the one encoded in
+    /// the API key, for example a local stand-in for testing.
+    #[serde(default)]
+    endpoint: Option<String>,
+    /// Optionally, a file which holds the API key. Otherwise the key is read
+    /// from the `MOMENTO_API_KEY` environment variable.
+    #[serde(default)]
+    credential_file: Option<String>,
+}
+
+impl Momento {
+    pub fn default_ttl(&self) -> Duration {
+        self.default_ttl
+            .as_ref()
+            .map(|ttl| ttl.parse::<humantime::Duration>().unwrap().into())
+            .unwrap_or(Duration::from_secs(900))
+    }
+
+    pub fn profile(&self) -> MomentoProfile {
+        self.profile.unwrap_or_default()
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline.map(Duration::from_millis)
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    pub fn credential_file(&self) -> Option<&str> {
+        self.credential_file.as_deref()
+    }
+
+    pub fn validate(&self) {
+        if let Some(ttl) = &self.default_ttl {
+            if let Err(e) = ttl.parse::<humantime::Duration>() {
+                eprintln!("momento default_ttl `{ttl}` is not a valid duration: {e}");
+                std::process::exit(2);
+            }
+        }
+
+        if self.deadline == Some(0) {
+            eprintln!("momento deadline must be greater than zero");
+            std::process::exit(2);
+        }
+
+        // the profiles each have their own deadline, so a deadline can not be
+        // applied to a profile
+        if self.deadline.is_some() && self.profile.is_some() {
+            eprintln!("momento deadline and profile can not both be set");
+            std::process::exit(2);
+        }
+    }
+}
@@ -1,14 +1,18 @@
 use super::*;
 
-#[derive(Clone, Copy, Deserialize, Debug)]
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Protocol {
     Blabber,
     Http1,
     Http2,
     Memcache,
+    MemcacheMeta,
+    MemcacheBinary,
     Momento,
     Ping,
     Resp,
     Kafka,
+    Nats,
+    Mqtt,
 }
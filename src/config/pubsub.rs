@@ -1,4 +1,93 @@
 use super::*;
+use std::collections::BTreeMap;
+
+/// How Kafka subscribers commit the offsets of the messages they consume.
+#[derive(Clone, Copy, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KafkaCommit {
+    /// Offsets are never committed, so a consumer group does not make progress
+    /// across restarts.
+    #[default]
+    None,
+    /// Offsets are committed periodically by the Kafka client.
+    Auto,
+    /// Offsets are committed and the subscriber waits for the commit to
+    /// complete.
+    Sync,
+    /// Offsets are committed without waiting for the commit to complete.
+    Async,
+}
+
+/// The SASL mechanism for authenticating with Kafka.
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+pub enum KafkaSaslMechanism {
+    #[serde(rename = "plain")]
+    Plain,
+    #[serde(rename = "scram-sha-256")]
+    ScramSha256,
+    #[serde(rename = "scram-sha-512")]
+    ScramSha512,
+    /// Authenticates with a static bearer token.
+    #[serde(rename = "oauthbearer")]
+    OAuthBearer,
+}
+
+impl KafkaSaslMechanism {
+    /// The name of the mechanism for `sasl.mechanism`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plain => "PLAIN",
+            Self::ScramSha256 => "SCRAM-SHA-256",
+            Self::ScramSha512 => "SCRAM-SHA-512",
+            Self::OAuthBearer => "OAUTHBEARER",
+        }
+    }
+}
+
+/// Kafka client properties which are passed to librdkafka as-is. Properties
+/// set here override those set by the other `kafka_*` options.
+#[derive(Clone, Default, Deserialize)]
+pub struct Kafka {
+    #[serde(default)]
+    producer: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    consumer: BTreeMap<String, toml::Value>,
+}
+
+impl Kafka {
+    pub fn producer(&self) -> impl Iterator<Item = (&str, String)> {
+        properties(&self.producer)
+    }
+
+    pub fn consumer(&self) -> impl Iterator<Item = (&str, String)> {
+        properties(&self.consumer)
+    }
+
+    fn validate(&self) {
+        for (name, value) in self.producer.iter().chain(self.consumer.iter()) {
+            if property(value).is_none() {
+                eprintln!("kafka property `{name}` must be a string, number, or boolean");
+                std::process::exit(2);
+            }
+        }
+    }
+}
+
+fn property(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(v) => Some(v.clone()),
+        toml::Value::Integer(v) => Some(v.to_string()),
+        toml::Value::Float(v) => Some(v.to_string()),
+        toml::Value::Boolean(v) => Some(v.to_string()),
+        _ => None,
+    }
+}
+
+fn properties(properties: &BTreeMap<String, toml::Value>) -> impl Iterator<Item = (&str, String)> {
+    properties
+        .iter()
+        .filter_map(|(name, value)| property(value).map(|v| (name.as_str(), v)))
+}
 
 #[derive(Clone, Deserialize)]
 pub struct Pubsub {
@@ -14,6 +103,21 @@ pub struct Pubsub {
     publisher_poolsize: usize,
     publisher_concurrency: usize,
 
+    /// Specifies the rate at which subscribers should randomly leave and
+    /// rejoin. For Kafka, the consumer leaves and rejoins its group, which
+    /// causes a rebalance. Other subscribers close and recreate their
+    /// subscription. This is useful to model rebalance storms.
+    #[serde(default)]
+    subscriber_churn_rate: u64,
+
+    /// When set, publish and receive counters and latency histograms are
+    /// also recorded for each of the first this many topics in each topics
+    /// component. With a zipf topic distribution, these are the hottest
+    /// topics. For Kafka, the per-partition metrics are limited to the same
+    /// topics.
+    #[serde(default)]
+    topic_metrics: usize,
+
     /// Specify the default sizes for the read and write buffers (in bytes).
     /// It is useful to increase the sizes if you expect to send and/or receive
     /// large requests/responses as part of the workload.
@@ -40,6 +144,118 @@ pub struct Pubsub {
     kafka_compression_type: Option<String>,
     kafka_auto_offset_reset: Option<String>,
     kafka_fetch_message_max_bytes: Option<String>,
+    kafka_isolation_level: Option<String>,
+    /// When set, publishers use transactions. Each producer client uses this
+    /// as the prefix of its `transactional.id`.
+    kafka_transactional_id: Option<String>,
+    /// The number of messages sent by each producer client in a transaction.
+    #[serde(default = "kafka_transaction_messages")]
+    kafka_transaction_messages: u64,
+    /// Optionally, transactions are also committed after this many
+    /// milliseconds.
+    kafka_transaction_interval: Option<u64>,
+    /// The strategy for committing consumed offsets.
+    #[serde(default)]
+    kafka_commit: KafkaCommit,
+    /// For `sync` and `async` commits, the offsets are committed after every
+    /// this many messages are received by each subscriber task.
+    #[serde(default = "kafka_commit_interval")]
+    kafka_commit_interval: u64,
+    /// The interval in milliseconds between queries of the committed and
+    /// high-watermark offsets which are used to report consumer lag.
+    #[serde(default = "kafka_lag_interval")]
+    kafka_lag_interval: u64,
+    /// When set, the end-to-end latency is also recorded for each partition.
+    #[serde(default)]
+    kafka_partition_latency: bool,
+    /// Optionally, authenticate with SASL using this mechanism. SASL is used
+    /// over TLS when the `tls` section is present.
+    kafka_sasl_mechanism: Option<KafkaSaslMechanism>,
+    /// The SASL username. For `oauthbearer`, this is the principal name.
+    kafka_sasl_username: Option<String>,
+    /// The SASL password for `plain` and `scram-*` mechanisms.
+    kafka_sasl_password: Option<String>,
+    /// The static bearer token for the `oauthbearer` mechanism.
+    kafka_sasl_oauthbearer_token: Option<String>,
+    /// Kafka client properties passed to librdkafka, from the
+    /// `[pubsub.kafka.producer]` and `[pubsub.kafka.consumer]` tables.
+    #[serde(default)]
+    kafka: Kafka,
+
+    // blabber configs
+    /// Optionally, run a built-in blabber server which listens on this
+    /// address. Messages from the workload are sent to every subscriber
+    /// which connects.
+    blabber_listen: Option<String>,
+
+    // redis pubsub configs
+    /// When set, subscribers use `PSUBSCRIBE` with each topic name as the
+    /// pattern instead of `SUBSCRIBE`.
+    #[serde(default)]
+    redis_psubscribe: bool,
+    /// When set, messages are published to Redis Streams with `XADD` and are
+    /// received by consumer groups with `XREADGROUP`.
+    #[serde(default)]
+    redis_streams: bool,
+    /// Optionally, trims each stream to approximately this many entries as
+    /// messages are published.
+    redis_streams_maxlen: Option<u64>,
+
+    // nats client configs
+    /// When set, topics are JetStream streams. Publishes wait for the stream
+    /// to acknowledge the message and subscribers are durable consumers which
+    /// acknowledge each message.
+    #[serde(default)]
+    nats_jetstream: bool,
+
+    // mqtt client configs
+    /// The MQTT protocol version, either 4 for MQTT 3.1.1 or 5 for MQTT 5.
+    #[serde(default = "mqtt_protocol_version")]
+    mqtt_protocol_version: u8,
+    /// The quality of service level (0, 1, or 2) for publishes and
+    /// subscriptions.
+    #[serde(default)]
+    mqtt_qos: u8,
+    /// When set, messages are published as retained messages.
+    #[serde(default)]
+    mqtt_retain: bool,
+    /// When set, each connection starts a clean session. Otherwise sessions
+    /// persist across reconnects and queued messages are delivered.
+    #[serde(default = "mqtt_clean_session")]
+    mqtt_clean_session: bool,
+    /// The keep alive interval in seconds.
+    #[serde(default = "mqtt_keep_alive")]
+    mqtt_keep_alive: u64,
+    /// Optionally, a prefix which is added to each topic name, for example
+    /// `rpcperf/`, so that subscribers may use a wildcard filter.
+    mqtt_topic_prefix: Option<String>,
+    /// Optionally, a topic filter such as `rpcperf/+` or `#` which subscribers
+    /// use instead of subscribing to each topic.
+    mqtt_topic_filter: Option<String>,
+}
+
+fn kafka_commit_interval() -> u64 {
+    1
+}
+
+fn kafka_transaction_messages() -> u64 {
+    1000
+}
+
+fn kafka_lag_interval() -> u64 {
+    1000
+}
+
+fn mqtt_protocol_version() -> u8 {
+    4
+}
+
+fn mqtt_clean_session() -> bool {
+    true
+}
+
+fn mqtt_keep_alive() -> u64 {
+    30
 }
 
 impl Pubsub {
@@ -67,13 +283,20 @@ impl Pubsub {
         self.publisher_concurrency
     }
 
+    pub fn subscriber_churn_rate(&self) -> Option<NonZeroU64> {
+        NonZeroU64::new(self.subscriber_churn_rate)
+    }
+
+    pub fn topic_metrics(&self) -> usize {
+        self.topic_metrics
+    }
+
     pub fn read_buffer_size(&self) -> usize {
         // rounds the read buffer size up to the next nearest multiple of the
         // pagesize
         ((std::cmp::max(1, self.read_buffer_size) + PAGESIZE - 1) / PAGESIZE) * PAGESIZE
     }
 
-    #[allow(dead_code)]
     pub fn write_buffer_size(&self) -> usize {
         // rounds the write buffer size up to the next nearest multiple of the
         // pagesize
@@ -127,4 +350,157 @@ impl Pubsub {
     pub fn kafka_fetch_message_max_bytes(&self) -> &Option<String> {
         &self.kafka_fetch_message_max_bytes
     }
+
+    pub fn kafka_isolation_level(&self) -> &Option<String> {
+        &self.kafka_isolation_level
+    }
+
+    pub fn kafka_transactional_id(&self) -> Option<&str> {
+        self.kafka_transactional_id.as_deref()
+    }
+
+    pub fn kafka_transaction_messages(&self) -> u64 {
+        self.kafka_transaction_messages
+    }
+
+    pub fn kafka_transaction_interval(&self) -> Option<Duration> {
+        self.kafka_transaction_interval.map(Duration::from_millis)
+    }
+
+    pub fn kafka_commit(&self) -> KafkaCommit {
+        self.kafka_commit
+    }
+
+    pub fn kafka_commit_interval(&self) -> u64 {
+        self.kafka_commit_interval
+    }
+
+    pub fn kafka_lag_interval(&self) -> Duration {
+        Duration::from_millis(self.kafka_lag_interval)
+    }
+
+    pub fn kafka_partition_latency(&self) -> bool {
+        self.kafka_partition_latency
+    }
+
+    pub fn kafka_sasl_mechanism(&self) -> Option<KafkaSaslMechanism> {
+        self.kafka_sasl_mechanism
+    }
+
+    pub fn kafka_sasl_username(&self) -> Option<&str> {
+        self.kafka_sasl_username.as_deref()
+    }
+
+    pub fn kafka_sasl_password(&self) -> Option<&str> {
+        self.kafka_sasl_password.as_deref()
+    }
+
+    pub fn kafka_sasl_oauthbearer_token(&self) -> Option<&str> {
+        self.kafka_sasl_oauthbearer_token.as_deref()
+    }
+
+    pub fn kafka(&self) -> &Kafka {
+        &self.kafka
+    }
+
+    pub fn blabber_listen(&self) -> Option<&str> {
+        self.blabber_listen.as_deref()
+    }
+
+    pub fn redis_psubscribe(&self) -> bool {
+        self.redis_psubscribe
+    }
+
+    pub fn redis_streams(&self) -> bool {
+        self.redis_streams
+    }
+
+    pub fn redis_streams_maxlen(&self) -> Option<u64> {
+        self.redis_streams_maxlen
+    }
+
+    pub fn nats_jetstream(&self) -> bool {
+        self.nats_jetstream
+    }
+
+    pub fn mqtt_protocol_version(&self) -> u8 {
+        self.mqtt_protocol_version
+    }
+
+    pub fn mqtt_qos(&self) -> u8 {
+        self.mqtt_qos
+    }
+
+    pub fn mqtt_retain(&self) -> bool {
+        self.mqtt_retain
+    }
+
+    pub fn mqtt_clean_session(&self) -> bool {
+        self.mqtt_clean_session
+    }
+
+    pub fn mqtt_keep_alive(&self) -> Duration {
+        Duration::from_secs(self.mqtt_keep_alive)
+    }
+
+    pub fn mqtt_topic_prefix(&self) -> Option<&str> {
+        self.mqtt_topic_prefix.as_deref()
+    }
+
+    pub fn mqtt_topic_filter(&self) -> Option<&str> {
+        self.mqtt_topic_filter.as_deref()
+    }
+
+    pub fn validate(&self) {
+        if self.kafka_commit_interval == 0 {
+            eprintln!("kafka_commit_interval must be at least 1");
+            std::process::exit(2);
+        }
+
+        if self.kafka_transaction_messages == 0 {
+            eprintln!("kafka_transaction_messages must be at least 1");
+            std::process::exit(2);
+        }
+
+        if self.kafka_transaction_interval == Some(0) {
+            eprintln!("kafka_transaction_interval must be at least 1");
+            std::process::exit(2);
+        }
+
+        if self.kafka_lag_interval == 0 {
+            eprintln!("kafka_lag_interval must be at least 1");
+            std::process::exit(2);
+        }
+
+        match self.kafka_sasl_mechanism {
+            Some(KafkaSaslMechanism::OAuthBearer) => {
+                if self.kafka_sasl_oauthbearer_token.is_none() {
+                    eprintln!("kafka_sasl_oauthbearer_token is required for oauthbearer");
+                    std::process::exit(2);
+                }
+            }
+            Some(mechanism) => {
+                if self.kafka_sasl_username.is_none() || self.kafka_sasl_password.is_none() {
+                    eprintln!(
+                        "kafka_sasl_username and kafka_sasl_password are required for {}",
+                        mechanism.as_str()
+                    );
+                    std::process::exit(2);
+                }
+            }
+            None => {}
+        }
+
+        self.kafka.validate();
+
+        if self.mqtt_protocol_version != 4 && self.mqtt_protocol_version != 5 {
+            eprintln!("mqtt_protocol_version must be either 4 (MQTT 3.1.1) or 5");
+            std::process::exit(2);
+        }
+
+        if self.mqtt_qos > 2 {
+            eprintln!("mqtt_qos must be 0, 1, or 2");
+            std::process::exit(2);
+        }
+    }
 }
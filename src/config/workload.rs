@@ -37,6 +37,59 @@ impl Workload {
     pub fn ratelimit(&self) -> &Ratelimit {
         &self.ratelimit
     }
+
+    /// Rejects every keyspace command which the client for the protocol can
+    /// not serve, as those requests would be dropped at runtime.
+    pub fn validate_verbs(&self, protocol: Protocol) {
+        if self.keyspace.is_empty() {
+            return;
+        }
+
+        let name = crate::clients::protocol_name(protocol);
+
+        let capabilities = match crate::clients::capabilities(protocol) {
+            Some(capabilities) => capabilities,
+            None => {
+                eprintln!("keyspaces are not supported for the `{name}` protocol");
+                std::process::exit(2);
+            }
+        };
+
+        let mut unsupported = 0;
+
+        for (i, keyspace) in self.keyspace.iter().enumerate() {
+            let keyspace_name = keyspace
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| i.to_string());
+
+            for command in keyspace.commands() {
+                for reason in crate::clients::unsupported(capabilities, command) {
+                    eprintln!("keyspace {keyspace_name}: {reason} by the `{name}` client");
+                    unsupported += 1;
+                }
+            }
+        }
+
+        if unsupported > 0 {
+            eprintln!("run with `--list-verbs` to see the verbs each client supports");
+            std::process::exit(2);
+        }
+    }
+
+    pub fn validate(&self) {
+        self.ratelimit.validate();
+
+        let ratelimits = self
+            .keyspace
+            .iter()
+            .filter_map(|k| k.ratelimit())
+            .chain(self.topics.iter().filter_map(|t| t.ratelimit()));
+
+        for ratelimit in ratelimits {
+            ratelimit.validate();
+        }
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -50,10 +103,28 @@ pub struct Topics {
     #[serde(default)]
     topic_names: Vec<String>,
     message_len: usize,
+    /// Optionally, message lengths are chosen from this distribution instead
+    /// of being fixed. The `message_len` is still used as the typical length
+    /// when estimating how much of each message is random.
+    #[serde(default)]
+    message_len_distribution: Option<MessageLength>,
+    /// Optionally, a directory of sample messages. Each message body is one
+    /// of the files, chosen at random, with the message header prepended. The
+    /// message lengths are then determined by the files.
+    #[serde(default)]
+    message_corpus: Option<String>,
     #[serde(default)]
     compression_ratio: Option<f64>,
     #[serde(default = "one")]
     key_len: usize,
+    /// Optionally, the number of distinct keys. When set, the key for each
+    /// message is chosen from this many keys using the `key_distribution`,
+    /// which can be used to model partition skew. Otherwise each message has
+    /// a random key.
+    #[serde(default)]
+    nkeys: Option<usize>,
+    #[serde(default)]
+    key_distribution: Distribution,
     weight: usize,
     subscriber_poolsize: usize,
     #[serde(default = "one")]
@@ -62,6 +133,19 @@ pub struct Topics {
     topic_distribution: Distribution,
     #[serde(default)]
     kafka_single_subscriber_group: bool,
+    /// Optionally, a name for this component, which is used to address its
+    /// ratelimit in the admin endpoint.
+    #[serde(default)]
+    name: Option<String>,
+    /// Optionally, a ratelimit for this component alone. The component is
+    /// then driven by its own generator threads instead of sharing the
+    /// workload ratelimit with the other components.
+    #[serde(default)]
+    ratelimit: Option<Ratelimit>,
+    /// The number of generator threads for this component when it has its
+    /// own ratelimit.
+    #[serde(default = "one")]
+    threads: usize,
 }
 
 impl Topics {
@@ -69,6 +153,18 @@ impl Topics {
         self.weight
     }
 
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn ratelimit(&self) -> Option<&Ratelimit> {
+        self.ratelimit.as_ref()
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
     pub fn partitions(&self) -> usize {
         self.partitions
     }
@@ -93,10 +189,26 @@ impl Topics {
         self.key_len
     }
 
+    pub fn nkeys(&self) -> Option<usize> {
+        self.nkeys
+    }
+
+    pub fn key_distribution(&self) -> Distribution {
+        self.key_distribution
+    }
+
     pub fn message_len(&self) -> usize {
         self.message_len
     }
 
+    pub fn message_len_distribution(&self) -> Option<&MessageLength> {
+        self.message_len_distribution.as_ref()
+    }
+
+    pub fn message_corpus(&self) -> Option<&str> {
+        self.message_corpus.as_deref()
+    }
+
     pub fn compression_ratio(&self) -> f64 {
         self.compression_ratio.unwrap_or(1.0)
     }
@@ -118,6 +230,23 @@ impl Topics {
     }
 }
 
+/// A distribution of message lengths in bytes.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageLength {
+    /// Lengths are uniformly distributed between `min` and `max`, inclusive.
+    Range { min: usize, max: usize },
+    /// Lengths follow a lognormal distribution with the given median and
+    /// shape parameter, and are limited to `max`.
+    Lognormal {
+        median: usize,
+        sigma: f64,
+        max: usize,
+    },
+    /// Lengths are chosen from buckets, each a `[length, weight]` pair.
+    Buckets { buckets: Vec<(usize, usize)> },
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Distribution {
@@ -155,6 +284,19 @@ pub struct Keyspace {
     #[serde(default)]
     // no ttl is treated as no-expires or max ttl for the protocol
     ttl: Option<String>,
+    /// Optionally, a name for this component, which is used to address its
+    /// ratelimit in the admin endpoint.
+    #[serde(default)]
+    name: Option<String>,
+    /// Optionally, a ratelimit for this component alone. The component is
+    /// then driven by its own generator threads instead of sharing the
+    /// workload ratelimit with the other components.
+    #[serde(default)]
+    ratelimit: Option<Ratelimit>,
+    /// The number of generator threads for this component when it has its
+    /// own ratelimit.
+    #[serde(default = "one")]
+    threads: usize,
 }
 
 impl Keyspace {
@@ -174,6 +316,18 @@ impl Keyspace {
         self.weight
     }
 
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn ratelimit(&self) -> Option<&Ratelimit> {
+        self.ratelimit.as_ref()
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
     pub fn inner_keys_nkeys(&self) -> Option<usize> {
         self.inner_keys_nkeys
     }
@@ -205,7 +359,7 @@ impl Keyspace {
     }
 }
 
-#[derive(Clone, Copy, Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct Command {
     verb: Verb,
     #[serde(default = "one")]
@@ -220,6 +374,18 @@ pub struct Command {
     end: Option<i32>,
     #[serde(default)]
     by_score: bool,
+    // the commands which are sent together as a transaction
+    #[serde(default)]
+    commands: Vec<Command>,
+    // path to a script which is loaded when each connection is established
+    #[serde(default)]
+    script: Option<String>,
+    // the number of generated values which are passed to a script
+    #[serde(default)]
+    values: usize,
+    // additional arguments which are passed to a script after the values
+    #[serde(default)]
+    args: Vec<String>,
 }
 
 impl Command {
@@ -250,6 +416,22 @@ impl Command {
     pub fn by_score(&self) -> bool {
         self.by_score
     }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    pub fn values(&self) -> usize {
+        self.values
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
 }
 
 // #[derive(Deserialize, Clone, Copy, Eq, PartialEq)]
@@ -268,12 +450,12 @@ pub enum Verb {
      */
     /// Set the value for a key if it does not already exist.
     /// * Memcache: `add`
-    /// * Momento: unsupported
+    /// * Momento: `set_if_absent`
     /// * RESP: `SET` with `NX` option
     Add,
     /// Read the value for one or more keys.
     /// * Memcache: `get`
-    /// * Momento: `get` (NOTE: cardinality > 1 is not supported)
+    /// * Momento: `get`, a `get` for each key sent concurrently, not a batch
     /// * RESP: `GET` or `MGET`
     Get,
     /// Set the value for a key.
@@ -289,9 +471,63 @@ pub enum Verb {
     Delete,
     /// Set the value for a key only if it already exists.
     /// * Memcache: `replace`
-    /// * Momento: unsupported
+    /// * Momento: `set_if_present`
     /// * RESP: `SET` with `XX` option
     Replace,
+    /// Increment the numeric value stored at a key.
+    /// * Memcache: `incr`
+    /// * Memcache Meta: `ma` with `MI` mode
+    /// * RESP: `INCRBY`
+    #[serde(alias = "incr")]
+    Increment,
+    /// Decrement the numeric value stored at a key.
+    /// * Memcache: `decr`
+    /// * Memcache Meta: `ma` with `MD` mode
+    /// * RESP: `DECRBY`
+    #[serde(alias = "decr")]
+    Decrement,
+    /// Read the value and CAS token for a key.
+    /// * Memcache: `gets`
+    Gets,
+    /// Read the CAS token for a key and then set the value for the key using
+    /// that token. The set is only sent if the key exists.
+    /// * Memcache: `gets` followed by `cas`
+    Cas,
+    /// Update the TTL for a key.
+    /// * Memcache: `touch`
+    /// * Memcache Meta: `mg` with the `T` flag
+    /// * RESP: `EXPIRE` or `PERSIST`
+    Touch,
+    /// Read the value for a key and update its TTL.
+    /// * Memcache: `gat`
+    /// * Memcache Meta: `mg` with the `v` and `T` flags
+    /// * RESP: `GETEX`
+    #[serde(alias = "gat")]
+    GetAndTouch,
+    /// Append data to the value for a key.
+    /// * Memcache: `append`
+    /// * RESP: `APPEND`
+    Append,
+    /// Prepend data to the value for a key.
+    /// * Memcache: `prepend`
+    Prepend,
+
+    /*
+     * TRANSACTIONS AND SCRIPTING
+     */
+    /// Sends the generated `commands` together as a transaction. Each of the
+    /// commands is validated using the reply for it from the `EXEC`. Commands
+    /// which send a further request based on their reply, `eval` and
+    /// `stream_read_group`, can not be used within a transaction.
+    /// * RESP: `MULTI`, the commands, and then `EXEC`
+    #[serde(alias = "multi")]
+    Transaction,
+    /// Runs the `script` with `cardinality` generated keys. The arguments are
+    /// `values` generated values followed by the `args`. The script is loaded
+    /// on each connection as it is established.
+    /// * RESP: `EVALSHA`, or `EVAL` if the server replies with `NOSCRIPT`
+    #[serde(alias = "evalsha")]
+    Eval,
 
     /*
      * HASHES (DICTIONARIES)
@@ -303,6 +539,7 @@ pub enum Verb {
     #[serde(alias = "hdel")]
     HashDelete,
     /// Check if a field exists in a hash.
+    /// * Momento: `dictionary_get_field`
     /// * RESP: `HEXISTS`
     #[serde(alias = "hexists")]
     HashExists,
@@ -410,7 +647,7 @@ pub enum Verb {
     #[serde(alias = "zrange")]
     SortedSetRange,
     /// Increment the score for a member of a sorted set.
-    /// * Moemento: `sorted_set_increment`
+    /// * Momento: unsupported
     /// * RESP: `ZINCRBY`
     #[serde(alias = "sorted_set_increment")]
     #[serde(alias = "zincrby")]
@@ -434,6 +671,29 @@ pub enum Verb {
     #[serde(alias = "zmscore")]
     #[serde(alias = "zscore")]
     SortedSetScore,
+
+    /*
+     * STREAMS
+     */
+    /// Appends an entry with one or more fields to a stream. When `truncate`
+    /// is set, the stream is trimmed to approximately that length.
+    /// * RESP: `XADD` with `MAXLEN ~`
+    #[serde(alias = "xadd")]
+    StreamAdd,
+    /// Reads up to `cardinality` entries from the start of a stream.
+    /// * RESP: `XREAD`
+    #[serde(alias = "xread")]
+    StreamRead,
+    /// Reads up to `cardinality` new entries from a stream as a member of a
+    /// consumer group and then acknowledges them. The group is created if it
+    /// does not exist.
+    /// * RESP: `XREADGROUP` followed by `XACK`
+    #[serde(alias = "xreadgroup")]
+    StreamReadGroup,
+    /// Trims a stream to approximately the length set by `truncate`.
+    /// * RESP: `XTRIM` with `MAXLEN ~`
+    #[serde(alias = "xtrim")]
+    StreamTrim,
 }
 
 impl Verb {
@@ -441,7 +701,9 @@ impl Verb {
     pub fn supports_cardinality(&self) -> bool {
         matches!(
             self,
-            Self::HashDelete
+            Self::Eval
+                | Self::Get
+                | Self::HashDelete
                 | Self::HashGet
                 | Self::HashSet
                 | Self::ListPushBack
@@ -451,6 +713,9 @@ impl Verb {
                 | Self::SortedSetAdd
                 | Self::SortedSetScore
                 | Self::SortedSetRemove
+                | Self::StreamAdd
+                | Self::StreamRead
+                | Self::StreamReadGroup
         )
     }
 
@@ -483,11 +748,15 @@ impl Verb {
                 | Self::SortedSetRank
                 | Self::SortedSetRemove
                 | Self::SortedSetScore
+                | Self::StreamAdd
         )
     }
 
     pub fn supports_truncate(&self) -> bool {
-        matches!(self, Self::ListPushBack | Self::ListPushFront)
+        matches!(
+            self,
+            Self::ListPushBack | Self::ListPushFront | Self::StreamAdd | Self::StreamTrim
+        )
     }
 }
 
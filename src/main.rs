@@ -1,6 +1,6 @@
 use crate::clients::launch_clients;
 use crate::pubsub::launch_pubsub;
-use crate::workload::{launch_workload, Generator, Ratelimit};
+use crate::workload::{launch_workload, Ratelimit};
 use async_channel::{bounded, Sender};
 use backtrace::Backtrace;
 use clap::{Arg, Command};
@@ -53,8 +53,19 @@ fn main() {
                 .action(clap::ArgAction::Set)
                 .index(1),
         )
+        .arg(
+            Arg::new("LIST_VERBS")
+                .long("list-verbs")
+                .help("List the verbs supported by each client protocol")
+                .action(clap::ArgAction::SetTrue),
+        )
         .get_matches();
 
+    if matches.get_flag("LIST_VERBS") {
+        clients::list_verbs();
+        std::process::exit(0);
+    }
+
     // load config from file
     let config = if let Some(file) = matches.get_one::<String>("CONFIG") {
         Config::new(file)
@@ -139,14 +150,42 @@ fn main() {
     output!("Protocol: {:?}", config.general().protocol());
 
     debug!("Initializing workload generator");
-    let workload_generator = Generator::new(&config);
-
-    let workload_ratelimit = workload_generator.ratelimiter();
-
-    let workload_components = workload_generator.components().to_owned();
+    let workload_generators = workload::generators(&config);
+
+    // the ratelimit of the generator which is shared by the components that
+    // do not have their own ratelimit, if there are any
+    let workload_ratelimit = workload_generators
+        .iter()
+        .find(|g| g.name().is_none())
+        .and_then(|g| g.ratelimiter());
+
+    let component_ratelimits: admin::ComponentRatelimits = Arc::new(
+        workload_generators
+            .iter()
+            .filter_map(|g| Some((g.name()?.to_string(), g.ratelimiter()?)))
+            .collect(),
+    );
+
+    let workload_components: Vec<_> = workload_generators
+        .iter()
+        .flat_map(|g| g.components().iter().cloned())
+        .collect();
+
+    // the ratelimit controllers for generators with a dynamic ratelimit
+    let ratelimit_controllers: Vec<_> = workload_generators
+        .iter()
+        .filter_map(|g| {
+            let name = g.name().map(|n| n.to_string());
+            Ratelimit::new(g.ratelimit()).map(|c| (name, c))
+        })
+        .collect();
 
     // spawn the admin thread
-    control_runtime.spawn(admin::http(config.clone(), workload_ratelimit.clone()));
+    control_runtime.spawn(admin::http(
+        config.clone(),
+        workload_ratelimit.clone(),
+        component_ratelimits.clone(),
+    ));
 
     // launch metrics file output
     control_runtime.spawn(output::metrics(config.clone()));
@@ -156,29 +195,39 @@ fn main() {
 
     // start the workload generator(s)
     let workload_runtime =
-        launch_workload(workload_generator, &config, client_sender, pubsub_sender);
+        launch_workload(workload_generators, &config, client_sender, pubsub_sender);
 
     // start client(s)
-    let client_runtime = launch_clients(&config, client_receiver);
+    let client_runtime = launch_clients(&config, client_receiver, &workload_components);
 
     // start publisher(s) and subscriber(s)
     let mut pubsub_runtimes = launch_pubsub(&config, pubsub_receiver, &workload_components);
 
-    // start ratelimit controller thread if a dynamic ratelimit is configured
-    {
-        if let Some(mut ratelimit_controller) = Ratelimit::new(&config) {
-            control_runtime.spawn(async move {
-                while RUNNING.load(Ordering::Relaxed) {
-                    let _ = admin::handlers::update_ratelimit(
-                        ratelimit_controller.next_ratelimit(),
-                        workload_ratelimit.clone(),
+    // start ratelimit controller threads for each dynamic ratelimit
+    for (name, mut ratelimit_controller) in ratelimit_controllers {
+        let workload_ratelimit = workload_ratelimit.clone();
+        let component_ratelimits = component_ratelimits.clone();
+
+        control_runtime.spawn(async move {
+            while RUNNING.load(Ordering::Relaxed) {
+                let rate = ratelimit_controller.next_ratelimit();
+
+                if let Some(name) = &name {
+                    let _ = admin::handlers::update_component_ratelimit(
+                        name.clone(),
+                        rate,
+                        component_ratelimits.clone(),
                     )
                     .await;
-                    // delay until next step function
-                    sleep(ratelimit_controller.interval()).await;
+                } else {
+                    let _ =
+                        admin::handlers::update_ratelimit(rate, workload_ratelimit.clone()).await;
                 }
-            });
-        }
+
+                // delay until next step function
+                sleep(ratelimit_controller.interval()).await;
+            }
+        });
     }
 
     while RUNNING.load(Ordering::Relaxed) {
@@ -193,6 +242,11 @@ fn main() {
 
     pubsub_runtimes.shutdown_timeout(std::time::Duration::from_millis(100));
 
+    // count the pubsub messages which were never received
+    if config.pubsub().is_some() {
+        pubsub::reconcile();
+    }
+
     workload_runtime.shutdown_timeout(std::time::Duration::from_millis(100));
 
     // delay before exiting
@@ -392,6 +392,30 @@ histogram!(PUBSUB_LATENCY, "pubsub_latency");
 
 histogram!(PUBSUB_PUBLISH_LATENCY, "pubsub_publish_latency");
 
+histogram!(
+    PUBSUB_REBALANCE_DURATION,
+    "pubsub_rebalance_duration",
+    "distribution of rebalance durations in nanoseconds."
+);
+
+histogram!(
+    PUBSUB_REBALANCE_LATENCY,
+    "pubsub_rebalance_latency",
+    "distribution of end-to-end latencies in nanoseconds for messages received during a rebalance."
+);
+
+histogram!(
+    PUBSUB_TRANSACTION_LATENCY,
+    "pubsub_transaction_latency",
+    "distribution of the time from the start of a transaction until it is committed in nanoseconds."
+);
+
+histogram!(
+    PUBSUB_TRANSACTION_COMMIT_LATENCY,
+    "pubsub_transaction_commit_latency",
+    "distribution of transaction commit latencies in nanoseconds."
+);
+
 gauge!(RATELIMIT_CURR, "ratelimit/current");
 counter!(RATELIMIT_DROPPED, "ratelimit/dropped");
 
@@ -484,6 +508,71 @@ counter!(
 counter!(RESPONSE_HIT, "client/response/hit");
 counter!(RESPONSE_MISS, "client/response/miss");
 
+// Push messages are out-of-band data sent by RESP3 servers. When client
+// tracking is enabled, invalidations are pushed for keys which may be held in
+// a client-side cache. We simulate such a near-cache to report the hit rate it
+// would have achieved for the workload.
+counter!(
+    RESP_PUSH,
+    "client/resp/push",
+    "push messages received from the server"
+);
+counter!(
+    RESP_INVALIDATE,
+    "client/resp/invalidate/total",
+    "invalidation push messages received"
+);
+counter!(
+    RESP_INVALIDATE_KEYS,
+    "client/resp/invalidate/keys",
+    "keys invalidated by invalidation push messages"
+);
+counter!(
+    RESP_INVALIDATE_FLUSH,
+    "client/resp/invalidate/flush",
+    "invalidation push messages which invalidated all keys"
+);
+counter!(
+    NEAR_CACHE_HIT,
+    "client/near_cache/hit",
+    "reads which would have been served from a client-side cache"
+);
+counter!(
+    NEAR_CACHE_MISS,
+    "client/near_cache/miss",
+    "reads which would have missed a client-side cache"
+);
+
+// stale-while-revalidate outcomes for the memcache meta protocol, these are
+// indicated by flags on the response to a `mg`
+counter!(
+    MEMCACHE_META_WIN,
+    "client/memcache_meta/win",
+    "responses which granted the client the right to recache an item"
+);
+counter!(
+    MEMCACHE_META_STALE,
+    "client/memcache_meta/stale",
+    "responses for items which have been marked as stale"
+);
+counter!(
+    MEMCACHE_META_WIN_SENT,
+    "client/memcache_meta/win_sent",
+    "responses for items where another client has already won the right to recache"
+);
+
+// authentication outcomes for the memcache binary protocol
+counter!(
+    MEMCACHE_BINARY_AUTH_OK,
+    "client/memcache_binary/auth/ok",
+    "connections which successfully authenticated"
+);
+counter!(
+    MEMCACHE_BINARY_AUTH_EX,
+    "client/memcache_binary/auth/exception",
+    "connections which failed to authenticate"
+);
+
 // augment the add stats
 counter!(
     ADD_TIMEOUT,
@@ -524,6 +613,11 @@ counter!(
     "delete/timeout",
     "delete requests that resulted in timeout"
 );
+counter!(
+    DELETE_QUIET,
+    "delete/quiet",
+    "quiet delete requests where the key was either deleted or not found"
+);
 
 request!(HASH_GET, "hash_get");
 counter!(HASH_GET_FIELD_HIT, "hash_get/field_hit");
@@ -540,6 +634,80 @@ counter!(SESSION, "client/session/total");
 counter!(SESSION_CLOSED_CLIENT, "client/session/client_closed");
 counter!(SESSION_CLOSED_SERVER, "client/session/server_closed");
 
+/*
+ * KEY-VALUE
+ */
+request!(INCR, "incr");
+counter!(INCR_NOT_FOUND, "incr/not_found");
+
+request!(DECR, "decr");
+counter!(DECR_NOT_FOUND, "decr/not_found");
+
+request!(GETS, "gets");
+counter!(GETS_KEY_HIT, "gets/key_hit");
+counter!(GETS_KEY_MISS, "gets/key_miss");
+
+request!(CAS, "cas");
+counter!(CAS_STORED, "cas/stored");
+counter!(
+    CAS_EXISTS,
+    "cas/exists",
+    "cas requests which conflicted with another update to the key"
+);
+counter!(CAS_NOT_FOUND, "cas/not_found");
+
+request!(TOUCH, "touch");
+counter!(TOUCH_HIT, "touch/hit");
+counter!(TOUCH_MISS, "touch/miss");
+
+request!(GAT, "gat");
+counter!(GAT_HIT, "gat/hit");
+counter!(GAT_MISS, "gat/miss");
+
+request!(APPEND, "append");
+counter!(APPEND_STORED, "append/stored");
+counter!(APPEND_NOT_STORED, "append/not_stored");
+
+request!(PREPEND, "prepend");
+counter!(PREPEND_STORED, "prepend/stored");
+counter!(PREPEND_NOT_STORED, "prepend/not_stored");
+
+/*
+ * TRANSACTIONS AND SCRIPTING
+ */
+request!(TRANSACTION, "transaction");
+counter!(
+    TRANSACTION_EXECABORT,
+    "transaction/execabort",
+    "transactions which were discarded due to an error while queueing a command"
+);
+
+request!(EVAL, "eval");
+counter!(
+    EVAL_NOSCRIPT,
+    "eval/noscript",
+    "script calls which failed because the script was not loaded"
+);
+
+/*
+ * STREAMS
+ */
+request!(STREAM_ADD, "stream_add");
+
+request!(STREAM_READ, "stream_read");
+counter!(STREAM_READ_HIT, "stream_read/hit");
+counter!(STREAM_READ_MISS, "stream_read/miss");
+
+request!(STREAM_READ_GROUP, "stream_read_group");
+counter!(STREAM_READ_GROUP_HIT, "stream_read_group/hit");
+counter!(STREAM_READ_GROUP_MISS, "stream_read_group/miss");
+
+request!(STREAM_ACK, "stream_ack");
+
+request!(STREAM_GROUP_CREATE, "stream_group_create");
+
+request!(STREAM_TRIM, "stream_trim");
+
 /*
  * PING
  */
@@ -617,6 +785,38 @@ request!(PUBSUB_SUBSCRIBE, "subscriber/subscribe");
 counter!(PUBSUB_PUBLISHER_CONNECT, "publisher/connect");
 gauge!(PUBSUB_PUBLISHER_CURR, "publisher/current");
 
+gauge!(
+    BLABBER_SUBSCRIBERS,
+    "publisher/blabber/subscribers",
+    "subscribers connected to the built-in blabber server"
+);
+counter!(
+    BLABBER_DROPPED,
+    "publisher/blabber/dropped",
+    "messages dropped by the built-in blabber server for subscribers which are not keeping up"
+);
+
+counter!(
+    PUBSUB_TRANSACTION,
+    "publisher/transaction/total",
+    "transactions which the publishers attempted to commit"
+);
+counter!(
+    PUBSUB_TRANSACTION_OK,
+    "publisher/transaction/ok",
+    "transactions which were committed"
+);
+counter!(
+    PUBSUB_TRANSACTION_ABORT,
+    "publisher/transaction/abort",
+    "transactions which failed to commit and were aborted"
+);
+counter!(
+    PUBSUB_TRANSACTION_EX,
+    "publisher/transaction/exception",
+    "transactions which failed to commit and could not be aborted"
+);
+
 gauge!(PUBSUB_SUBSCRIBER_CURR, "subscriber/current");
 
 counter!(PUBSUB_RECEIVE, "subscriber/receive/total");
@@ -625,3 +825,69 @@ counter!(PUBSUB_RECEIVE_CLOSED, "subscriber/receive/closed");
 counter!(PUBSUB_RECEIVE_CORRUPT, "subscriber/receive/corrupt");
 counter!(PUBSUB_RECEIVE_INVALID, "subscriber/receive/invalid");
 counter!(PUBSUB_RECEIVE_OK, "subscriber/receive/ok");
+counter!(
+    PUBSUB_RECEIVE_DUPLICATE,
+    "subscriber/receive/duplicate",
+    "received messages with a sequence number which was already received"
+);
+counter!(
+    PUBSUB_RECEIVE_REORDERED,
+    "subscriber/receive/reordered",
+    "received messages which arrived after a later message in the sequence"
+);
+counter!(
+    PUBSUB_RECEIVE_LOST,
+    "subscriber/receive/lost",
+    "published messages which were never received, counted at shutdown"
+);
+gauge!(
+    PUBSUB_RECEIVE_MISSING,
+    "subscriber/receive/missing",
+    "messages skipped in the sequence which have not yet been received"
+);
+
+counter!(
+    PUBSUB_ACK_EX,
+    "subscriber/ack/exception",
+    "acknowledgements of received messages which failed"
+);
+counter!(
+    PUBSUB_CHURN,
+    "subscriber/churn",
+    "subscribers which left and rejoined to model churn"
+);
+counter!(
+    PUBSUB_REBALANCE,
+    "subscriber/rebalance/total",
+    "rebalances, from when a subscriber stops receiving until it receives again"
+);
+gauge!(
+    PUBSUB_REBALANCE_CURR,
+    "subscriber/rebalance/current",
+    "rebalances which are in progress"
+);
+counter!(
+    PUBSUB_RECEIVE_REBALANCE,
+    "subscriber/receive/rebalance",
+    "messages received during a rebalance or within a second after one"
+);
+counter!(
+    PUBSUB_COMMIT,
+    "subscriber/commit/total",
+    "offset commits by the subscribers"
+);
+counter!(
+    PUBSUB_COMMIT_EX,
+    "subscriber/commit/exception",
+    "offset commits by the subscribers which failed"
+);
+gauge!(
+    PUBSUB_PENDING,
+    "subscriber/pending",
+    "messages delivered to the subscriber groups which are not yet acknowledged"
+);
+gauge!(
+    PUBSUB_LAG,
+    "subscriber/lag",
+    "messages which are not yet delivered to the subscriber groups"
+);
@@ -140,6 +140,23 @@ fn client_stats(snapshot: &mut MetricsSnapshot) {
         response_timeout,
     );
 
+    // near-cache stats are only produced when client tracking is enabled
+    let near_cache_hit = snapshot.counter_rate(NEAR_CACHE_HIT_COUNTER);
+    let near_cache_miss = snapshot.counter_rate(NEAR_CACHE_MISS_COUNTER);
+
+    if near_cache_hit + near_cache_miss > 0.0 {
+        let near_cache_hr = 100.0 * near_cache_hit / (near_cache_hit + near_cache_miss);
+        let invalidate = snapshot.counter_rate(RESP_INVALIDATE_COUNTER);
+        let invalidate_keys = snapshot.counter_rate(RESP_INVALIDATE_KEYS_COUNTER);
+
+        output!(
+            "Client Near-Cache: Hit: {:.2} % Invalidations (/s): {:.2} Keys Invalidated (/s): {:.2}",
+            near_cache_hr,
+            invalidate,
+            invalidate_keys,
+        );
+    }
+
     let mut latencies = "Client Response Latency (us):".to_owned();
 
     for (label, _percentile, nanoseconds) in response_latency {
@@ -166,6 +183,8 @@ fn pubsub_stats(snapshot: &mut MetricsSnapshot) {
     let pubsub_rx_corrupt = snapshot.counter_rate(PUBSUB_RECEIVE_CORRUPT_COUNTER);
     let pubsub_rx_invalid = snapshot.counter_rate(PUBSUB_RECEIVE_INVALID_COUNTER);
     let pubsub_rx_total = snapshot.counter_rate(PUBSUB_RECEIVE_COUNTER);
+    let pubsub_rx_duplicate = snapshot.counter_rate(PUBSUB_RECEIVE_DUPLICATE_COUNTER);
+    let pubsub_rx_reordered = snapshot.counter_rate(PUBSUB_RECEIVE_REORDERED_COUNTER);
 
     // end-to-end stats
     let pubsub_latency = snapshot.percentiles(PUBSUB_LATENCY_HISTOGRAM);
@@ -187,6 +206,15 @@ fn pubsub_stats(snapshot: &mut MetricsSnapshot) {
         pubsub_tx_timeout,
     );
 
+    if PUBSUB_TRANSACTION.value() > 0 {
+        output!(
+            "Publisher Transaction Rate (/s): Ok: {:.2} Abort: {:.2} Error: {:.2}",
+            snapshot.counter_rate(PUBSUB_TRANSACTION_OK_COUNTER),
+            snapshot.counter_rate(PUBSUB_TRANSACTION_ABORT_COUNTER),
+            snapshot.counter_rate(PUBSUB_TRANSACTION_EX_COUNTER),
+        );
+    }
+
     output!("Subscribers: Current: {}", PUBSUB_SUBSCRIBER_CURR.value(),);
 
     let pubsub_rx_sr = 100.0 * pubsub_rx_ok / pubsub_rx_total;
@@ -205,6 +233,13 @@ fn pubsub_stats(snapshot: &mut MetricsSnapshot) {
         pubsub_rx_invalid,
     );
 
+    output!(
+        "Subscriber Sequence: Missing: {} Duplicate (/s): {:.2} Reordered (/s): {:.2}",
+        PUBSUB_RECEIVE_MISSING.value(),
+        pubsub_rx_duplicate,
+        pubsub_rx_reordered,
+    );
+
     let mut latencies = "Pubsub Publish Latency (us):".to_owned();
 
     for (label, _percentile, nanoseconds) in pubsub_publish_latency {
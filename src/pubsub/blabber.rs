@@ -5,7 +5,9 @@ use bytes::BufMut;
 use session::Buffer;
 use std::borrow::Borrow;
 use std::borrow::BorrowMut;
-use tokio::io::AsyncReadExt;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 use tokio::time::timeout;
 
@@ -24,6 +26,7 @@ pub fn launch_subscribers(
     runtime: &mut Runtime,
     config: Config,
     workload_components: &[Component],
+    churn: Churn,
 ) {
     debug!("launching blabber subscriber tasks");
 
@@ -35,7 +38,12 @@ pub fn launch_subscribers(
             // note: these may be channels instead of connections for multiplexed protocols
             for _ in 0..connections {
                 for endpoint in config.target().endpoints() {
-                    runtime.spawn(subscriber_task(endpoint.clone(), config.clone()));
+                    runtime.spawn(subscriber_task(
+                        endpoint.clone(),
+                        config.clone(),
+                        topics.topics().to_vec(),
+                        churn.clone(),
+                    ));
                 }
             }
         }
@@ -44,8 +52,13 @@ pub fn launch_subscribers(
 
 // a task for blabber servers (eg: Pelikan Blabber)
 #[allow(clippy::slow_vector_initialization)]
-async fn subscriber_task(endpoint: String, config: Config) -> Result<()> {
-    let validator = MessageValidator::new();
+async fn subscriber_task(
+    endpoint: String,
+    config: Config,
+    topics: Vec<Arc<String>>,
+    churn: Churn,
+) -> Result<()> {
+    let validator = MessageValidator::subscriber(None, &topics);
 
     let connector = Connector::new(&config)?;
 
@@ -56,6 +69,9 @@ async fn subscriber_task(endpoint: String, config: Config) -> Result<()> {
     let mut stream = None;
     let mut read_buffer = Buffer::new(pubsub_config.read_buffer_size());
 
+    // set while the connection is being recreated to model churn
+    let mut rebalance: Option<Rebalance> = None;
+
     while RUNNING.load(Ordering::Relaxed) {
         if stream.is_none() {
             CONNECT.increment();
@@ -73,6 +89,10 @@ async fn subscriber_task(endpoint: String, config: Config) -> Result<()> {
                     PUBSUB_SUBSCRIBER_CURR.add(1);
                     PUBSUB_SUBSCRIBE_OK.increment();
 
+                    if let Some(rebalance) = rebalance.take() {
+                        rebalance.complete();
+                    }
+
                     Some(s)
                 }
                 Ok(Err(_)) => {
@@ -96,7 +116,19 @@ async fn subscriber_task(endpoint: String, config: Config) -> Result<()> {
 
         // read until response or timeout
         loop {
-            match s.read(read_buffer.borrow_mut()).await {
+            let result = tokio::select! {
+                result = s.read(read_buffer.borrow_mut()) => result,
+                _ = churn.wait() => {
+                    // drop the connection and any partial message
+                    CONNECT_CURR.decrement();
+                    PUBSUB_SUBSCRIBER_CURR.sub(1);
+                    read_buffer = Buffer::new(pubsub_config.read_buffer_size());
+                    rebalance = Some(Rebalance::start());
+                    break;
+                }
+            };
+
+            match result {
                 Ok(n) => {
                     unsafe {
                         read_buffer.advance_mut(n);
@@ -142,13 +174,147 @@ async fn subscriber_task(endpoint: String, config: Config) -> Result<()> {
     Ok(())
 }
 
-/// Launch tasks with one channel per task as gRPC is mux-enabled.
-pub fn launch_publishers(
-    _runtime: &mut Runtime,
-    _config: Config,
-    _work_receiver: Receiver<WorkItem>,
-) {
-    // note: there are no publish tasks for blabber, instead the server is
-    // expected to publish compatible messages to the subscribers
-    debug!("skipping blabber publisher tasks");
+/// The queues of framed messages for each subscriber of the built-in server.
+type Subscribers = Arc<Mutex<Vec<async_channel::Sender<Arc<Vec<u8>>>>>>;
+
+// the number of messages which may be queued for each subscriber of the
+// built-in server before messages are dropped
+const SUBSCRIBER_QUEUE_DEPTH: usize = 1024;
+
+/// Launch a built-in blabber server when `pubsub.blabber_listen` is set. The
+/// server accepts subscribers and each message from the workload is sent to
+/// all of them, so that rpc-perf can exercise both sides of a blabber server.
+///
+/// Without the built-in server, an external server (eg: Pelikan Blabber) is
+/// expected to publish compatible messages to the subscribers.
+pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    let pubsub_config = config.pubsub().unwrap();
+
+    let listen = match pubsub_config.blabber_listen() {
+        Some(listen) => listen,
+        None => {
+            debug!("skipping blabber publisher tasks");
+            return;
+        }
+    };
+
+    debug!("launching blabber server on {listen}");
+
+    let listener = match runtime.block_on(TcpListener::bind(listen)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("blabber: could not listen on {listen}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let subscribers: Subscribers = Arc::new(Mutex::new(Vec::new()));
+
+    runtime.spawn(accept_task(listener, subscribers.clone()));
+
+    let tasks = pubsub_config.publisher_poolsize() * pubsub_config.publisher_concurrency();
+
+    for _ in 0..tasks {
+        runtime.spawn(publisher_task(subscribers.clone(), work_receiver.clone()));
+    }
+}
+
+/// Accepts subscribers and spawns a task for each which writes its queued
+/// messages to the connection.
+async fn accept_task(listener: TcpListener, subscribers: Subscribers) {
+    while RUNNING.load(Ordering::Relaxed) {
+        // use a timeout here so we always check RUNNING at least once a second
+        let mut stream = match timeout(Duration::from_secs(1), listener.accept()).await {
+            Ok(Ok((stream, _))) => stream,
+            Ok(Err(e)) => {
+                debug!("blabber: failed to accept a subscriber: {e}");
+                continue;
+            }
+            Err(_) => continue,
+        };
+
+        let _ = stream.set_nodelay(true);
+
+        let (sender, receiver) = async_channel::bounded::<Arc<Vec<u8>>>(SUBSCRIBER_QUEUE_DEPTH);
+
+        subscribers.lock().unwrap().push(sender);
+        BLABBER_SUBSCRIBERS.increment();
+
+        tokio::spawn(async move {
+            while let Ok(frame) = receiver.recv().await {
+                if stream.write_all(&frame).await.is_err() {
+                    break;
+                }
+            }
+
+            BLABBER_SUBSCRIBERS.decrement();
+        });
+    }
+}
+
+async fn publisher_task(subscribers: Subscribers, work_receiver: Receiver<WorkItem>) -> Result<()> {
+    PUBSUB_PUBLISHER_CURR.add(1);
+
+    let validator = MessageValidator::publisher();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        let work_item = work_receiver
+            .recv()
+            .await
+            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
+        REQUEST.increment();
+        let start = Instant::now();
+
+        match work_item {
+            WorkItem::Publish {
+                topic,
+                mut message,
+                key: _,
+            } => {
+                validator.stamp(&mut message, &topic, 0);
+
+                PUBSUB_PUBLISH.increment();
+
+                // add the blabber header
+                let len = (HEADER_LEN as usize + message.len()) as u32;
+                let mut frame = Vec::with_capacity(len as usize);
+                frame.extend_from_slice(&len.to_be_bytes());
+                frame.extend_from_slice(&[0; 4]);
+                frame.extend_from_slice(&message);
+                let frame = Arc::new(frame);
+
+                // send the message to all of the subscribers, dropping it for
+                // any which are not keeping up, and forgetting any which have
+                // disconnected
+                subscribers.lock().unwrap().retain(|subscriber| {
+                    match subscriber.try_send(frame.clone()) {
+                        Ok(_) => true,
+                        Err(async_channel::TrySendError::Full(_)) => {
+                            BLABBER_DROPPED.increment();
+                            true
+                        }
+                        Err(async_channel::TrySendError::Closed(_)) => false,
+                    }
+                });
+            }
+        }
+
+        let latency = start.elapsed().as_nanos() as u64;
+
+        PUBSUB_PUBLISH_OK.increment();
+        let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+        if let Some(metrics) = topic_metrics {
+            metrics.published(latency);
+        }
+    }
+
+    PUBSUB_PUBLISHER_CURR.sub(1);
+
+    Ok(())
 }
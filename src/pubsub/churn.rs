@@ -0,0 +1,129 @@
+use super::*;
+use ratelimit::Ratelimiter;
+use std::sync::atomic::AtomicU64;
+
+// messages received within this long after a rebalance completes are counted
+// as received during the rebalance, so that the latency spike is captured
+const REBALANCE_WINDOW: Duration = Duration::from_secs(1);
+
+// the unix time in nanoseconds when the most recent rebalance completed
+static REBALANCE_COMPLETED: AtomicU64 = AtomicU64::new(0);
+
+/// Requests for subscribers to leave and rejoin, sent at the
+/// `subscriber_churn_rate`. Each request is received by a single subscriber.
+#[derive(Clone)]
+pub struct Churn {
+    receiver: Option<Receiver<()>>,
+}
+
+impl Churn {
+    /// Spawns a task which sends the churn requests, if churn is enabled.
+    pub fn new(runtime: &mut Runtime, config: &Config) -> Self {
+        let rate = match config.pubsub().unwrap().subscriber_churn_rate() {
+            Some(rate) => rate.get(),
+            None => return Self { receiver: None },
+        };
+
+        let amount = (rate as f64 / 1_000_000.0).ceil() as u64;
+
+        // even though we might not have nanosecond level clock resolution,
+        // by using a nanosecond level duration, we achieve more accurate
+        // ratelimits.
+        let interval = Duration::from_nanos(1_000_000_000 / (rate / amount));
+
+        let ratelimiter = Ratelimiter::builder(amount, interval)
+            .max_tokens(amount)
+            .build()
+            .expect("failed to initialize ratelimiter");
+
+        let (sender, receiver) = async_channel::bounded(1);
+
+        runtime.spawn(async move {
+            while RUNNING.load(Ordering::Relaxed) {
+                match ratelimiter.try_wait() {
+                    Ok(_) => {
+                        if sender.send(()).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(d) => {
+                        sleep(d).await;
+                    }
+                }
+            }
+        });
+
+        Self {
+            receiver: Some(receiver),
+        }
+    }
+
+    /// Whether subscribers are asked to churn.
+    pub fn enabled(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Waits until this subscriber should churn. Never completes if churn is
+    /// not enabled.
+    pub async fn wait(&self) {
+        match &self.receiver {
+            Some(receiver) => {
+                if receiver.recv().await.is_ok() {
+                    PUBSUB_CHURN.increment();
+                } else {
+                    std::future::pending::<()>().await;
+                }
+            }
+            None => std::future::pending::<()>().await,
+        }
+    }
+}
+
+/// A rebalance in progress, from when a subscriber stops receiving until it,
+/// or the group it belongs to, is receiving again.
+pub struct Rebalance {
+    start: Instant,
+}
+
+impl Rebalance {
+    pub fn start() -> Self {
+        PUBSUB_REBALANCE.increment();
+        PUBSUB_REBALANCE_CURR.increment();
+
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the duration of the rebalance.
+    pub fn complete(self) {
+        let duration = self.start.elapsed().as_nanos() as u64;
+        let _ = PUBSUB_REBALANCE_DURATION.increment(duration);
+    }
+}
+
+impl Drop for Rebalance {
+    fn drop(&mut self) {
+        PUBSUB_REBALANCE_CURR.decrement();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        REBALANCE_COMPLETED.store(now, Ordering::Relaxed);
+    }
+}
+
+/// Records a message which was received during a rebalance, or shortly after
+/// one completed. The time the message was received is the unix time in
+/// nanoseconds which was already taken to calculate its latency.
+pub fn received(now: u64, latency: u64) {
+    let rebalancing = PUBSUB_REBALANCE_CURR.value() > 0
+        || now.saturating_sub(REBALANCE_COMPLETED.load(Ordering::Relaxed))
+            < REBALANCE_WINDOW.as_nanos() as u64;
+
+    if rebalancing {
+        PUBSUB_RECEIVE_REBALANCE.increment();
+        let _ = PUBSUB_REBALANCE_LATENCY.increment(latency);
+    }
+}
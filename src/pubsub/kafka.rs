@@ -1,11 +1,91 @@
 use super::*;
+use metriken::{AtomicHistogram, Counter, DynBoxedMetric, Gauge, MetricBuilder};
 use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
-use rdkafka::client::DefaultClientContext;
+use rdkafka::client::{ClientContext, OAuthToken};
 use rdkafka::config::ClientConfig;
-use rdkafka::consumer::{Consumer, StreamConsumer};
-use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::consumer::{
+    BaseConsumer, CommitMode, Consumer, ConsumerContext, Rebalance as RebalanceEvent,
+    StreamConsumer,
+};
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
 use rdkafka::types::RDKafkaErrorCode::TopicAlreadyExists;
-use rdkafka::Message;
+use rdkafka::{Message, Offset};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::Mutex;
+
+type KafkaProducer = FutureProducer<KafkaContext>;
+type KafkaConsumer = StreamConsumer<KafkaContext>;
+
+/// The client context, which provides the static token for OAUTHBEARER
+/// authentication and measures consumer group rebalances.
+struct KafkaContext {
+    principal: String,
+    token: Option<String>,
+    // set from when partitions are revoked until partitions are assigned
+    rebalance: Mutex<Option<Rebalance>>,
+}
+
+impl KafkaContext {
+    fn new(config: &Config) -> Self {
+        let pubsub_config = config.pubsub().unwrap();
+
+        Self {
+            principal: pubsub_config
+                .kafka_sasl_username()
+                .unwrap_or("rpcperf")
+                .to_string(),
+            token: pubsub_config
+                .kafka_sasl_oauthbearer_token()
+                .map(|token| token.to_string()),
+            rebalance: Mutex::new(None),
+        }
+    }
+}
+
+impl ClientContext for KafkaContext {
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = true;
+
+    fn generate_oauth_token(
+        &self,
+        _oauthbearer_config: Option<&str>,
+    ) -> std::result::Result<OAuthToken, Box<dyn std::error::Error>> {
+        let token = self
+            .token
+            .clone()
+            .ok_or("no oauthbearer token is configured")?;
+
+        // the token is static, so it is provided again when this expires
+        let lifetime = SystemTime::now() + Duration::from_secs(3600);
+
+        Ok(OAuthToken {
+            token,
+            principal_name: self.principal.clone(),
+            lifetime_ms: lifetime.duration_since(UNIX_EPOCH).unwrap().as_millis() as i64,
+        })
+    }
+}
+
+impl ConsumerContext for KafkaContext {
+    fn pre_rebalance(&self, _consumer: &BaseConsumer<Self>, rebalance: &RebalanceEvent<'_>) {
+        if let RebalanceEvent::Revoke(_) = rebalance {
+            let mut current = self.rebalance.lock().unwrap();
+            if current.is_none() {
+                *current = Some(Rebalance::start());
+            }
+        }
+    }
+
+    fn post_rebalance(&self, _consumer: &BaseConsumer<Self>, rebalance: &RebalanceEvent<'_>) {
+        if let RebalanceEvent::Assign(_) = rebalance {
+            if let Some(rebalance) = self.rebalance.lock().unwrap().take() {
+                rebalance.complete();
+            }
+        }
+    }
+}
 
 fn get_client_config(config: &Config) -> ClientConfig {
     let bootstrap_servers = config.target().endpoints().join(",");
@@ -16,10 +96,40 @@ fn get_client_config(config: &Config) -> ClientConfig {
         .set("bootstrap.servers", &bootstrap_servers)
         .set("socket.timeout.ms", connect_timeout)
         .set("socket.nagle.disable", "true");
+    let security_protocol = match (config.tls(), pubsub_config.kafka_sasl_mechanism()) {
+        (None, None) => "plaintext",
+        (Some(_), None) => "ssl",
+        (None, Some(_)) => "sasl_plaintext",
+        (Some(_), Some(_)) => "sasl_ssl",
+    };
+    client_config.set("security.protocol", security_protocol);
+    if let Some(mechanism) = pubsub_config.kafka_sasl_mechanism() {
+        client_config.set("sasl.mechanism", mechanism.as_str());
+        if mechanism != KafkaSaslMechanism::OAuthBearer {
+            // these are checked when the config is loaded
+            client_config
+                .set(
+                    "sasl.username",
+                    pubsub_config.kafka_sasl_username().unwrap(),
+                )
+                .set(
+                    "sasl.password",
+                    pubsub_config.kafka_sasl_password().unwrap(),
+                );
+        }
+    }
     if let Some(tls) = config.tls() {
-        client_config
-            .set("security.protocol", "ssl")
-            .set("enable.ssl.certificate.verification", "false");
+        // the broker certificate and hostname are verified unless hostname
+        // verification is disabled
+        if tls.verify_hostname() {
+            client_config
+                .set("enable.ssl.certificate.verification", "true")
+                .set("ssl.endpoint.identification.algorithm", "https");
+        } else {
+            client_config
+                .set("enable.ssl.certificate.verification", "false")
+                .set("ssl.endpoint.identification.algorithm", "none");
+        }
         if let Some(ca_file) = tls.ca_file() {
             client_config.set("ssl.ca.location", ca_file);
         }
@@ -36,11 +146,14 @@ fn get_client_config(config: &Config) -> ClientConfig {
     client_config
 }
 
-fn get_kafka_producer(config: &Config) -> FutureProducer {
+fn get_kafka_producer(config: &Config, transactional_id: Option<&str>) -> KafkaProducer {
     let pubsub_config = config.pubsub().unwrap();
     let publish_timeout = format!("{}", pubsub_config.publish_timeout().as_millis());
     let mut client_config = get_client_config(config);
     client_config.set("message.timeout.ms", publish_timeout);
+    if let Some(transactional_id) = transactional_id {
+        client_config.set("transactional.id", transactional_id);
+    }
     if let Some(acks) = pubsub_config.kafka_acks() {
         client_config.set("acks", acks);
     }
@@ -71,29 +184,47 @@ fn get_kafka_producer(config: &Config) -> FutureProducer {
     if let Some(compression_type) = pubsub_config.kafka_compression_type() {
         client_config.set("compression.type", compression_type);
     }
+    for (name, value) in pubsub_config.kafka().producer() {
+        client_config.set(name, value);
+    }
     debug!("Kafka producer config: {:?}", client_config);
-    client_config.create().unwrap()
+    client_config
+        .create_with_context(KafkaContext::new(config))
+        .unwrap()
 }
 
-fn get_kafka_consumer(config: &Config, group_id: &str) -> StreamConsumer {
+fn get_kafka_consumer(config: &Config, group_id: &str) -> KafkaConsumer {
     let pubsub_config: &Pubsub = config.pubsub().unwrap();
     let mut client_config = get_client_config(config);
     client_config
         .set("group.id", group_id)
         .set("client.id", "rpcperf_subscriber")
-        .set("enable.auto.commit", "false");
+        .set(
+            "enable.auto.commit",
+            (pubsub_config.kafka_commit() == KafkaCommit::Auto).to_string(),
+        );
     if let Some(auto_offset_reset) = pubsub_config.kafka_auto_offset_reset() {
         client_config.set("auto.offset.reset", auto_offset_reset);
     }
     if let Some(fetch_message_max_bytes) = pubsub_config.kafka_fetch_message_max_bytes() {
         client_config.set("fetch.message.max.bytes", fetch_message_max_bytes);
     }
+    if let Some(isolation_level) = pubsub_config.kafka_isolation_level() {
+        client_config.set("isolation.level", isolation_level);
+    }
+    for (name, value) in pubsub_config.kafka().consumer() {
+        client_config.set(name, value);
+    }
     debug!("Kafka consumer config: {:?}", client_config);
-    client_config.create().unwrap()
+    client_config
+        .create_with_context(KafkaContext::new(config))
+        .unwrap()
 }
 
-fn get_kafka_admin(config: &Config) -> AdminClient<DefaultClientContext> {
-    get_client_config(config).create().unwrap()
+fn get_kafka_admin(config: &Config) -> AdminClient<KafkaContext> {
+    get_client_config(config)
+        .create_with_context(KafkaContext::new(config))
+        .unwrap()
 }
 
 fn validate_topic(
@@ -184,72 +315,208 @@ pub fn create_topics(runtime: &mut Runtime, config: Config, workload_components:
     }
 }
 
+/// Receive stats for a single partition.
+struct PartitionStats {
+    receive: DynBoxedMetric<Counter>,
+    latency: Option<DynBoxedMetric<AtomicHistogram>>,
+}
+
+/// The receive stats for each partition of each topic, indexed by partition.
+type Partitions = Arc<HashMap<String, Vec<PartitionStats>>>;
+
+fn partition_stats(config: &Config, workload_components: &[Component]) -> Partitions {
+    let latency = config.pubsub().unwrap().kafka_partition_latency();
+
+    // when per-topic metrics are enabled, the partition stats are limited to
+    // the same topics
+    let limited = config.pubsub().unwrap().topic_metrics() > 0;
+
+    let mut partitions = HashMap::new();
+
+    for component in workload_components {
+        if let Component::Topics(topics) = component {
+            for topic in topics.topics() {
+                if limited && !topic_metrics::is_tracked(config, topics, topic) {
+                    continue;
+                }
+
+                let stats = (0..topics.partitions())
+                    .map(|partition| {
+                        let prefix = format!("subscriber/topic/{topic}/partition/{partition}");
+
+                        let builder = |name: &str, description: &'static str| {
+                            MetricBuilder::new(format!("{prefix}/{name}"))
+                                .description(description)
+                                .metadata("metric", format!("subscriber/partition/{name}"))
+                                .metadata("topic", topic.as_str())
+                                .metadata("partition", partition.to_string())
+                        };
+
+                        PartitionStats {
+                            receive: builder("receive", "messages received from the partition")
+                                .build(Counter::new()),
+                            latency: latency.then(|| {
+                                builder("latency", "end-to-end latency for the partition")
+                                    .build(AtomicHistogram::new(7, 64))
+                            }),
+                        }
+                    })
+                    .collect();
+
+                partitions.insert(topic.to_string(), stats);
+            }
+        }
+    }
+
+    Arc::new(partitions)
+}
+
 /// Launch tasks with one channel per task as Kafka connection is mux-enabled.
+///
+/// A single task reports the consumer lag for the partitions assigned to all
+/// of the consumers.
+///
+/// When churn is enabled, each task has its own consumer which leaves and
+/// rejoins its group, causing the group to rebalance.
 pub fn launch_subscribers(
     runtime: &mut Runtime,
     config: Config,
     workload_components: &[Component],
+    churn: Churn,
 ) {
+    let partitions = partition_stats(&config, workload_components);
+
+    let mut consumers = Vec::new();
+
     for component in workload_components {
         if let Component::Topics(topics) = component {
             let poolsize = topics.subscriber_poolsize();
             let concurrency = topics.subscriber_concurrency();
 
             for id in 0..poolsize {
-                let client = {
+                // set the group_id to 0 for all subscribers if using the single subscriber group
+                let group_id = if topics.kafka_single_subscriber_group() {
+                    0
+                } else {
+                    id
+                };
+                let group = format!("rpcperf_subscriber_{group_id}");
+                let new_client = || {
                     let _guard = runtime.enter();
-                    // set the group_id to 0 for all subscribers if using the single subscriber group
-                    let group_id = if topics.kafka_single_subscriber_group() {
-                        0
+                    Arc::new(get_kafka_consumer(&config, &group))
+                };
+                let client = new_client();
+                // the tasks share the partitions assigned to the consumer, and
+                // the consumers in a group share the sequence tracking
+                let validator = MessageValidator::subscriber(Some(&group), topics.topics());
+                for task in 0..concurrency {
+                    // a consumer which churns leaves and rejoins the group, so
+                    // each task has its own consumer in the group to avoid
+                    // interrupting the other tasks
+                    let client = if churn.enabled() && task > 0 {
+                        let client = new_client();
+                        consumers.push((group.clone(), client.clone()));
+                        client
                     } else {
-                        id
+                        client.clone()
                     };
-                    Arc::new(get_kafka_consumer(
-                        &config,
-                        &format!("rpcperf_subscriber_{group_id}"),
-                    ))
-                };
-                for _ in 0..concurrency {
+
                     let mut sub_topics: Vec<String> = Vec::new();
                     for t in topics.topics() {
                         sub_topics.push(t.to_string().clone())
                     }
 
-                    runtime.spawn(subscriber_task(client.clone(), sub_topics));
+                    runtime.spawn(subscriber_task(
+                        config.clone(),
+                        client,
+                        sub_topics,
+                        validator.clone(),
+                        partitions.clone(),
+                        churn.clone(),
+                    ));
                 }
+                consumers.push((group, client));
             }
         }
     }
+
+    runtime.spawn(lag_task(config, consumers));
 }
 
-async fn subscriber_task(client: Arc<StreamConsumer>, topics: Vec<String>) {
+async fn subscriber_task(
+    config: Config,
+    client: Arc<KafkaConsumer>,
+    topics: Vec<String>,
+    validator: MessageValidator,
+    partitions: Partitions,
+    churn: Churn,
+) {
     PUBSUB_SUBSCRIBE.increment();
 
+    let pubsub_config = config.pubsub().unwrap();
+    let commit = pubsub_config.kafka_commit();
+    let commit_interval = pubsub_config.kafka_commit_interval();
+
     let sub_topics: Vec<&str> = topics.iter().map(AsRef::as_ref).collect();
 
     if client.subscribe(&sub_topics).is_ok() {
         PUBSUB_SUBSCRIBER_CURR.add(1);
         PUBSUB_SUBSCRIBE_OK.increment();
 
-        let validator = MessageValidator::new();
+        let mut received: u64 = 0;
 
         while RUNNING.load(Ordering::Relaxed) {
-            match client.recv().await {
-                Ok(message) => match message.payload_view::<[u8]>() {
-                    Some(Ok(message)) => {
-                        let _ = validator.validate(&mut message.to_owned());
+            let result = tokio::select! {
+                result = client.recv() => result,
+                _ = churn.wait() => {
+                    // leave and rejoin the group
+                    client.unsubscribe();
+                    PUBSUB_SUBSCRIBE.increment();
+                    if client.subscribe(&sub_topics).is_ok() {
+                        PUBSUB_SUBSCRIBE_OK.increment();
+                    } else {
+                        PUBSUB_SUBSCRIBE_EX.increment();
+                    }
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(message) => {
+                    let stats = partitions
+                        .get(message.topic())
+                        .and_then(|p| p.get(message.partition() as usize));
+
+                    if let Some(stats) = stats {
+                        stats.receive.increment();
                     }
-                    Some(Err(e)) => {
-                        error!("Error in deserializing the message:{:?}", e);
-                        PUBSUB_RECEIVE.increment();
-                        PUBSUB_RECEIVE_EX.increment();
+
+                    match message.payload_view::<[u8]>() {
+                        Some(Ok(message)) => {
+                            if let Ok(latency) = validator.validate(&mut message.to_owned()) {
+                                if let Some(histogram) = stats.and_then(|s| s.latency.as_ref()) {
+                                    let _ = histogram.increment(latency);
+                                }
+                            }
+                        }
+                        Some(Err(e)) => {
+                            error!("Error in deserializing the message:{:?}", e);
+                            PUBSUB_RECEIVE.increment();
+                            PUBSUB_RECEIVE_EX.increment();
+                        }
+                        None => {
+                            error!("Empty Message");
+                            PUBSUB_RECEIVE.increment();
+                            PUBSUB_RECEIVE_EX.increment();
+                        }
                     }
-                    None => {
-                        error!("Empty Message");
-                        PUBSUB_RECEIVE.increment();
-                        PUBSUB_RECEIVE_EX.increment();
+
+                    received += 1;
+
+                    if received % commit_interval == 0 {
+                        commit_offsets(&client, commit);
                     }
-                },
+                }
                 Err(e) => {
                     debug!("Kafka Message Error {}", e);
                     PUBSUB_RECEIVE.increment();
@@ -263,38 +530,369 @@ async fn subscriber_task(client: Arc<StreamConsumer>, topics: Vec<String>) {
     }
 }
 
+/// Commits the offsets of the messages received by the consumer. Does nothing
+/// unless the commit strategy is `sync` or `async`.
+fn commit_offsets(client: &KafkaConsumer, commit: KafkaCommit) {
+    let result = match commit {
+        KafkaCommit::Sync => {
+            // a synchronous commit blocks until the broker responds
+            tokio::task::block_in_place(|| client.commit_consumer_state(CommitMode::Sync))
+        }
+        KafkaCommit::Async => client.commit_consumer_state(CommitMode::Async),
+        KafkaCommit::None | KafkaCommit::Auto => return,
+    };
+
+    PUBSUB_COMMIT.increment();
+
+    if let Err(e) = result {
+        debug!("Kafka commit error: {e}");
+        PUBSUB_COMMIT_EX.increment();
+    }
+}
+
+/// Returns the lag for each partition assigned to the consumers as tuples of
+/// the group, topic, partition, and lag. The lag is the difference between the
+/// high watermark and the committed offset, or the position of the consumer if
+/// there is no committed offset.
+///
+/// This blocks while the offsets are queried.
+fn consumer_lag(
+    consumers: &[(String, Arc<KafkaConsumer>)],
+    timeout: Duration,
+) -> Vec<(String, String, i32, i64)> {
+    let mut lags = Vec::new();
+
+    for (group, consumer) in consumers {
+        let assignment = match consumer.assignment() {
+            Ok(assignment) if assignment.count() > 0 => assignment,
+            _ => continue,
+        };
+
+        let committed = match consumer.committed_offsets(assignment, timeout) {
+            Ok(committed) => committed,
+            Err(e) => {
+                debug!("Kafka: failed to get the committed offsets: {e}");
+                continue;
+            }
+        };
+
+        let position = consumer.position().ok();
+
+        for element in committed.elements() {
+            let offset = match element.offset() {
+                Offset::Offset(offset) => offset,
+                _ => match position
+                    .as_ref()
+                    .and_then(|p| p.find_partition(element.topic(), element.partition()))
+                    .map(|e| e.offset())
+                {
+                    Some(Offset::Offset(offset)) => offset,
+                    _ => continue,
+                },
+            };
+
+            match consumer.fetch_watermarks(element.topic(), element.partition(), timeout) {
+                Ok((_low, high)) => {
+                    lags.push((
+                        group.clone(),
+                        element.topic().to_string(),
+                        element.partition(),
+                        std::cmp::max(0, high - offset),
+                    ));
+                }
+                Err(e) => {
+                    debug!("Kafka: failed to get the watermarks: {e}");
+                }
+            }
+        }
+    }
+
+    lags
+}
+
+/// Periodically reports the consumer lag for each partition and each group.
+/// The total lag is reported as `subscriber/lag`.
+async fn lag_task(config: Config, consumers: Vec<(String, Arc<KafkaConsumer>)>) {
+    let pubsub_config = config.pubsub().unwrap();
+    let interval = pubsub_config.kafka_lag_interval();
+    let timeout = pubsub_config.connect_timeout();
+
+    let consumers = Arc::new(consumers);
+
+    let mut partition_gauges: HashMap<(String, String, i32), DynBoxedMetric<Gauge>> =
+        HashMap::new();
+    let mut group_gauges: HashMap<String, DynBoxedMetric<Gauge>> = HashMap::new();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        sleep(interval).await;
+
+        let c = consumers.clone();
+        let lags = match tokio::task::spawn_blocking(move || consumer_lag(&c, timeout)).await {
+            Ok(lags) => lags,
+            Err(_) => continue,
+        };
+
+        // partitions which are no longer assigned have no lag to report
+        for gauge in partition_gauges.values() {
+            gauge.set(0);
+        }
+
+        let mut groups: HashMap<String, i64> = HashMap::new();
+        let mut total = 0;
+
+        for (group, topic, partition, lag) in lags {
+            *groups.entry(group.clone()).or_default() += lag;
+            total += lag;
+
+            partition_gauges
+                .entry((group, topic, partition))
+                .or_insert_with_key(|(group, topic, partition)| {
+                    MetricBuilder::new(format!(
+                        "subscriber/group/{group}/topic/{topic}/partition/{partition}/lag"
+                    ))
+                    .description("messages in the partition not yet consumed by the group")
+                    .build(Gauge::new())
+                })
+                .set(lag);
+        }
+
+        for (group, lag) in groups {
+            group_gauges
+                .entry(group)
+                .or_insert_with_key(|group| {
+                    MetricBuilder::new(format!("subscriber/group/{group}/lag"))
+                        .description("messages not yet consumed by the group")
+                        .build(Gauge::new())
+                })
+                .set(lag);
+        }
+
+        PUBSUB_LAG.set(total);
+    }
+}
+
 /// Launch tasks with one channel per task as Kafka connection is mux-enabled.
-pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
-    for _ in 0..config.pubsub().unwrap().publisher_poolsize() {
+///
+/// The partition for each message is chosen by the publisher so that it can
+/// be recorded in the message for sequence tracking.
+///
+/// In transactional mode, each producer client has its own transactional ID
+/// and the messages sent by its tasks are grouped into transactions.
+pub fn launch_publishers(
+    runtime: &mut Runtime,
+    config: Config,
+    work_receiver: Receiver<WorkItem>,
+    workload_components: &[Component],
+) {
+    let pubsub_config = config.pubsub().unwrap();
+
+    let partitioner = Partitioner::new(workload_components);
+
+    for id in 0..pubsub_config.publisher_poolsize() {
+        let transactional_id = pubsub_config
+            .kafka_transactional_id()
+            .map(|prefix| format!("{prefix}-{id}"));
+
         let client = {
             let _guard = runtime.enter();
-            Arc::new(get_kafka_producer(&config))
+            Arc::new(get_kafka_producer(&config, transactional_id.as_deref()))
         };
 
+        let transactions = transactional_id.map(|transactional_id| {
+            let transactions = Arc::new(Transactions::new(&config, client.clone()));
+
+            if let Err(e) = transactions.init() {
+                eprintln!("Kafka: failed to initialize transactions for {transactional_id}: {e}");
+                std::process::exit(1);
+            }
+
+            if let Some(interval) = pubsub_config.kafka_transaction_interval() {
+                runtime.spawn(transaction_task(transactions.clone(), interval));
+            }
+
+            transactions
+        });
+
         PUBSUB_PUBLISHER_CONNECT.increment();
 
-        for _ in 0..config.pubsub().unwrap().publisher_concurrency() {
-            runtime.spawn(publisher_task(client.clone(), work_receiver.clone()));
+        for _ in 0..pubsub_config.publisher_concurrency() {
+            runtime.spawn(publisher_task(
+                client.clone(),
+                work_receiver.clone(),
+                partitioner.clone(),
+                transactions.clone(),
+            ));
         }
     }
 }
 
+/// Groups the messages sent by a transactional producer into transactions.
+/// The publisher tasks hold the read lock while sending and counting each
+/// message, so a transaction is only committed when none of the sends are in
+/// progress.
+struct Transactions {
+    client: Arc<KafkaProducer>,
+    lock: tokio::sync::RwLock<Instant>,
+    // the number of messages sent in the current transaction
+    messages: AtomicU64,
+    // set once the producer has a fatal error and can not be used
+    failed: AtomicBool,
+    // the number of messages in each transaction
+    limit: u64,
+    timeout: Duration,
+}
+
+impl Transactions {
+    fn new(config: &Config, client: Arc<KafkaProducer>) -> Self {
+        let pubsub_config = config.pubsub().unwrap();
+
+        Self {
+            client,
+            lock: tokio::sync::RwLock::new(Instant::now()),
+            messages: AtomicU64::new(0),
+            failed: AtomicBool::new(false),
+            limit: pubsub_config.kafka_transaction_messages(),
+            timeout: pubsub_config.publish_timeout(),
+        }
+    }
+
+    /// Initializes transactions for the producer and begins the first one.
+    fn init(&self) -> std::result::Result<(), KafkaError> {
+        self.client.init_transactions(self.timeout)?;
+        self.client.begin_transaction()
+    }
+
+    /// Counts a message sent in the current transaction, which must be done
+    /// while holding the read lock. Returns true once the transaction has
+    /// enough messages to be committed.
+    fn sent(&self) -> bool {
+        self.messages.fetch_add(1, Ordering::Relaxed) + 1 >= self.limit
+    }
+
+    /// Returns true if the producer has a fatal error.
+    fn failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Commits the current transaction and begins the next one. A transaction
+    /// which fails to commit is aborted if possible. A commit which may be
+    /// retried is left to the next commit, and after a fatal error no further
+    /// transactions are started.
+    async fn commit(&self) {
+        let mut started = self.lock.write().await;
+
+        // another task may have committed the transaction already
+        if self.failed() || self.messages.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+
+        PUBSUB_TRANSACTION.increment();
+
+        let start = Instant::now();
+
+        // committing flushes the outstanding messages and blocks until the
+        // transaction is complete
+        let result = tokio::task::block_in_place(|| self.client.commit_transaction(self.timeout));
+
+        match result {
+            Ok(_) => {
+                let stop = Instant::now();
+
+                PUBSUB_TRANSACTION_OK.increment();
+                let _ = PUBSUB_TRANSACTION_COMMIT_LATENCY
+                    .increment(stop.duration_since(start).as_nanos() as u64);
+                let _ = PUBSUB_TRANSACTION_LATENCY
+                    .increment(stop.duration_since(*started).as_nanos() as u64);
+            }
+            Err(e) => {
+                debug!("Kafka transaction commit error: {e}");
+
+                let result = match &e {
+                    KafkaError::Transaction(t) if t.txn_requires_abort() => {
+                        tokio::task::block_in_place(|| self.client.abort_transaction(self.timeout))
+                    }
+                    KafkaError::Transaction(t) if t.is_retriable() => {
+                        PUBSUB_TRANSACTION_EX.increment();
+                        return;
+                    }
+                    _ => Err(e),
+                };
+
+                match result {
+                    Ok(_) => {
+                        PUBSUB_TRANSACTION_ABORT.increment();
+                    }
+                    Err(e) => {
+                        PUBSUB_TRANSACTION_EX.increment();
+
+                        if matches!(&e, KafkaError::Transaction(t) if t.is_fatal()) {
+                            error!("Kafka: transactional producer failed: {e}");
+                            self.failed.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.messages.store(0, Ordering::Relaxed);
+        *started = Instant::now();
+
+        if let Err(e) = self.client.begin_transaction() {
+            error!("Kafka: failed to begin a transaction: {e}");
+        }
+    }
+}
+
+/// Commits the current transaction at a fixed interval, in addition to the
+/// commits once a transaction has enough messages.
+async fn transaction_task(transactions: Arc<Transactions>, interval: Duration) {
+    while RUNNING.load(Ordering::Relaxed) && !transactions.failed() {
+        let elapsed = transactions.lock.read().await.elapsed();
+
+        if elapsed < interval {
+            sleep(interval - elapsed).await;
+            continue;
+        }
+
+        transactions.commit().await;
+    }
+}
+
 async fn publisher_task(
-    client: Arc<FutureProducer>,
+    client: Arc<KafkaProducer>,
     work_receiver: Receiver<WorkItem>,
+    mut partitioner: Partitioner,
+    transactions: Option<Arc<Transactions>>,
 ) -> Result<()> {
     PUBSUB_PUBLISHER_CURR.add(1);
 
-    let validator = MessageValidator::new();
+    let validator = MessageValidator::publisher();
 
     while RUNNING.load(Ordering::Relaxed) {
+        // a producer with a fatal error can not publish any more messages
+        if transactions.as_ref().is_some_and(|t| t.failed()) {
+            break;
+        }
+
         let work_item = work_receiver
             .recv()
             .await
             .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
 
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
         REQUEST.increment();
 
+        // sends may not be in progress while a transaction is committed
+        let guard = match &transactions {
+            Some(transactions) => Some(transactions.lock.read().await),
+            None => None,
+        };
+
         let start = Instant::now();
 
         let result = match work_item {
@@ -303,7 +901,8 @@ async fn publisher_task(
                 key,
                 mut message,
             } => {
-                validator.stamp(&mut message);
+                let partition = partitioner.partition(&topic, key.as_deref());
+                validator.stamp(&mut message, &topic, partition);
                 PUBSUB_PUBLISH.increment();
                 client
                     .send(
@@ -311,7 +910,7 @@ async fn publisher_task(
                             topic: &topic,
                             payload: Some(&message),
                             key: key.as_ref(),
-                            partition: None,
+                            partition: Some(partition as i32),
                             timestamp: None,
                             headers: None,
                         },
@@ -323,17 +922,32 @@ async fn publisher_task(
 
         let stop = Instant::now();
 
+        // the message is counted in the transaction before the lock is
+        // released, so that it can not be counted in the next one
+        let commit = transactions.as_ref().is_some_and(|t| t.sent());
+
+        drop(guard);
+
         match result {
             Ok(_) => {
                 let latency = stop.duration_since(start).as_nanos() as u64;
                 PUBSUB_PUBLISH_OK.increment();
                 let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
             }
             Err(e) => {
                 debug!("Error in producing: {:?}", e);
                 PUBSUB_PUBLISH_EX.increment();
             }
         }
+
+        if commit {
+            if let Some(transactions) = &transactions {
+                transactions.commit().await;
+            }
+        }
     }
 
     PUBSUB_PUBLISHER_CURR.sub(1);
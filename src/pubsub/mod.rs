@@ -1,18 +1,31 @@
 use crate::clients::*;
-use crate::workload::Component;
 use crate::workload::PublisherWorkItem as WorkItem;
+use crate::workload::{Component, MESSAGE_HEADER_LEN};
 use crate::*;
 
 use ahash::RandomState;
 use async_channel::Receiver;
 use tokio::runtime::Runtime;
 
+use std::collections::HashMap;
 use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 mod blabber;
+mod churn;
 mod kafka;
 mod momento;
+mod mqtt;
+mod nats;
+mod redis;
+mod redis_streams;
+mod sequence;
+mod topic_metrics;
+
+use churn::{Churn, Rebalance};
+pub use sequence::reconcile;
+use topic_metrics::TopicMetrics;
 
 pub fn hasher() -> RandomState {
     RandomState::with_seeds(
@@ -23,8 +36,26 @@ pub fn hasher() -> RandomState {
     )
 }
 
+/// Stamps and validates the header of each message. The header is:
+///
+/// | bytes  | field        |
+/// |--------|--------------|
+/// | 0..8   | magic        |
+/// | 8..16  | checksum     |
+/// | 16..24 | timestamp    |
+/// | 24..32 | publisher ID |
+/// | 32..40 | sequence     |
+/// | 40..48 | stream ID    |
+///
+/// Each publisher numbers the messages for each stream, which is a partition
+/// of a topic, so that subscribers can detect lost, duplicate, and reordered
+/// messages. Clones share the sequence tracking, so tasks which consume from
+/// the same subscription should use clones of one validator.
+#[derive(Clone)]
 struct MessageValidator {
     hash_builder: RandomState,
+    publisher: Option<Arc<sequence::Publisher>>,
+    subscription: Option<Arc<sequence::Subscription>>,
 }
 
 pub enum ValidationError {
@@ -33,16 +64,31 @@ pub enum ValidationError {
 }
 
 impl MessageValidator {
+    /// Returns a validator which stamps the messages for a publisher.
     /// Deterministic seeds are used so that multiple validators can stamp and
     /// validate messages produced by other instances.
-    pub fn new() -> Self {
+    pub fn publisher() -> Self {
+        MessageValidator {
+            hash_builder: hasher(),
+            publisher: Some(sequence::Publisher::new()),
+            subscription: None,
+        }
+    }
+
+    /// Returns a validator for a subscription to the topics. The subscribers
+    /// in a consumer group should provide the group, so that the messages they
+    /// receive are tracked together.
+    pub fn subscriber(group: Option<&str>, topics: &[Arc<String>]) -> Self {
         MessageValidator {
             hash_builder: hasher(),
+            publisher: None,
+            subscription: Some(sequence::Subscription::new(group, topics)),
         }
     }
 
-    /// Sets the checksum and timestamp in the message. Returns the timestamp.
-    pub fn stamp(&self, message: &mut [u8]) -> u64 {
+    /// Sets the checksum, timestamp, and sequence number in the message for the
+    /// partition of the topic it is published to. Returns the timestamp.
+    pub fn stamp(&self, message: &mut [u8], topic: &str, partition: usize) -> u64 {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -52,7 +98,15 @@ impl MessageValidator {
         // write the current unix time into the message
         message[16..24].copy_from_slice(&ts[0..8]);
 
-        // todo, write a sequence number into the message
+        // write the publisher, sequence number, and stream into the message
+        let stream = sequence::stream_id(topic, partition);
+        let (publisher, sequence) = match &self.publisher {
+            Some(publisher) => (publisher.id(), publisher.next(stream)),
+            None => (0, 0),
+        };
+        message[24..32].copy_from_slice(&publisher.to_be_bytes());
+        message[32..40].copy_from_slice(&sequence.to_be_bytes());
+        message[40..48].copy_from_slice(&stream.to_be_bytes());
 
         // checksum the message and put the checksum into the message
         let checksum = self.hash_builder.hash_one(&message).to_be_bytes();
@@ -84,16 +138,84 @@ impl MessageValidator {
 
         // calculate and return the end to end latency
         let ts = u64::from_be_bytes(v[16..24].try_into().unwrap());
-        let latency = now_unix.duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64 - ts;
+        let now = now_unix.duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64;
+        let latency = now - ts;
 
         let _ = PUBSUB_LATENCY.increment(latency);
         PUBSUB_RECEIVE.increment();
         PUBSUB_RECEIVE_OK.increment();
 
+        churn::received(now, latency);
+
+        // track the sequence, messages without a publisher are not numbered
+        if v.len() < MESSAGE_HEADER_LEN {
+            return Ok(latency);
+        }
+
+        let publisher = u64::from_be_bytes(v[24..32].try_into().unwrap());
+        let sequence = u64::from_be_bytes(v[32..40].try_into().unwrap());
+        let stream = u64::from_be_bytes(v[40..48].try_into().unwrap());
+
+        topic_metrics::received(stream, latency);
+
+        if let Some(subscription) = &self.subscription {
+            if publisher != 0 && sequence != 0 {
+                subscription.receive(publisher, stream, sequence);
+            }
+        }
+
         Ok(latency)
     }
 }
 
+/// Chooses the partition for each message. Messages with the same key are
+/// published to the same partition and other messages are spread across the
+/// partitions in turn.
+#[derive(Clone)]
+struct Partitioner {
+    hash_builder: RandomState,
+    partitions: Arc<HashMap<Arc<String>, usize>>,
+    next: usize,
+}
+
+impl Partitioner {
+    pub fn new(workload_components: &[Component]) -> Self {
+        let mut partitions = HashMap::new();
+
+        for component in workload_components {
+            if let Component::Topics(topics) = component {
+                for topic in topics.topics() {
+                    partitions.insert(topic.clone(), topics.partitions());
+                }
+            }
+        }
+
+        Self {
+            hash_builder: hasher(),
+            partitions: Arc::new(partitions),
+            next: 0,
+        }
+    }
+
+    /// Returns the number of partitions for the topic.
+    pub fn partitions(&self, topic: &Arc<String>) -> usize {
+        self.partitions.get(topic).copied().unwrap_or(1)
+    }
+
+    /// Returns the partition to publish the message to.
+    pub fn partition(&mut self, topic: &Arc<String>, key: Option<&[u8]>) -> usize {
+        let count = self.partitions(topic);
+
+        match key {
+            Some(key) => self.hash_builder.hash_one(key) as usize % count,
+            None => {
+                self.next = self.next.wrapping_add(1);
+                self.next % count
+            }
+        }
+    }
+}
+
 pub struct PubsubRuntimes {
     publisher_rt: Option<Runtime>,
     subscriber_rt: Option<Runtime>,
@@ -116,6 +238,9 @@ pub fn launch_pubsub(
     work_receiver: Receiver<WorkItem>,
     workload_components: &[Component],
 ) -> PubsubRuntimes {
+    sequence::register_topics(config, workload_components);
+    topic_metrics::register(config, workload_components);
+
     PubsubRuntimes {
         publisher_rt: launch_publishers(config, work_receiver, workload_components),
         subscriber_rt: launch_subscribers(config, workload_components),
@@ -150,7 +275,31 @@ fn launch_publishers(
         }
         Protocol::Kafka => {
             kafka::create_topics(&mut publisher_rt, config.clone(), workload_components);
-            kafka::launch_publishers(&mut publisher_rt, config.clone(), work_receiver);
+            kafka::launch_publishers(
+                &mut publisher_rt,
+                config.clone(),
+                work_receiver,
+                workload_components,
+            );
+        }
+        Protocol::Mqtt => {
+            mqtt::launch_publishers(&mut publisher_rt, config.clone(), work_receiver);
+        }
+        Protocol::Nats => {
+            nats::create_streams(&mut publisher_rt, config.clone(), workload_components);
+            nats::launch_publishers(&mut publisher_rt, config.clone(), work_receiver);
+        }
+        Protocol::Resp => {
+            if config.pubsub().unwrap().redis_streams() {
+                redis_streams::launch_publishers(
+                    &mut publisher_rt,
+                    config.clone(),
+                    work_receiver,
+                    workload_components,
+                );
+            } else {
+                redis::launch_publishers(&mut publisher_rt, config.clone(), work_receiver);
+            }
         }
         _ => {
             error!("pubsub is not supported for the selected protocol");
@@ -176,15 +325,59 @@ fn launch_subscribers(config: &Config, workload_components: &[Component]) -> Opt
         .build()
         .expect("failed to initialize tokio runtime");
 
+    if config.pubsub().unwrap().subscriber_churn_rate().is_some()
+        && !matches!(
+            config.general().protocol(),
+            Protocol::Blabber | Protocol::Kafka | Protocol::Momento
+        )
+    {
+        eprintln!("subscriber_churn_rate is not supported for the selected protocol");
+        std::process::exit(2);
+    }
+
+    let churn = Churn::new(&mut subscriber_rt, config);
+
     match config.general().protocol() {
         Protocol::Blabber => {
-            blabber::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+            blabber::launch_subscribers(
+                &mut subscriber_rt,
+                config.clone(),
+                workload_components,
+                churn,
+            );
         }
         Protocol::Momento => {
-            momento::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+            momento::launch_subscribers(
+                &mut subscriber_rt,
+                config.clone(),
+                workload_components,
+                churn,
+            );
         }
         Protocol::Kafka => {
-            kafka::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+            kafka::launch_subscribers(
+                &mut subscriber_rt,
+                config.clone(),
+                workload_components,
+                churn,
+            );
+        }
+        Protocol::Mqtt => {
+            mqtt::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+        }
+        Protocol::Nats => {
+            nats::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+        }
+        Protocol::Resp => {
+            if config.pubsub().unwrap().redis_streams() {
+                redis_streams::launch_subscribers(
+                    &mut subscriber_rt,
+                    config.clone(),
+                    workload_components,
+                );
+            } else {
+                redis::launch_subscribers(&mut subscriber_rt, config.clone(), workload_components);
+            }
         }
         _ => {
             error!("pubsub is not supported for the selected protocol");
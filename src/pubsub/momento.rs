@@ -1,8 +1,8 @@
 use super::*;
 
-use ::momento::topics::configurations::LowLatency;
-use ::momento::topics::{TopicClient, ValueKind};
-use ::momento::CredentialProvider;
+use crate::clients::momento::{configuration, credential_provider};
+
+use ::momento::topics::{Configuration, TopicClient, ValueKind};
 use futures::stream::StreamExt;
 use tokio::time::timeout;
 
@@ -14,6 +14,7 @@ pub fn launch_subscribers(
     runtime: &mut Runtime,
     config: Config,
     workload_components: &[Component],
+    churn: Churn,
 ) {
     debug!("launching momento subscriber tasks");
 
@@ -36,23 +37,9 @@ pub fn launch_subscribers(
                     let _guard = runtime.enter();
 
                     // initialize the Momento topic client
-                    if std::env::var("MOMENTO_API_KEY").is_err() {
-                        eprintln!("environment variable `MOMENTO_API_KEY` is not set");
-                        std::process::exit(1);
-                    }
-
-                    let credential_provider =
-                        match CredentialProvider::from_env_var("MOMENTO_API_KEY".to_string()) {
-                            Ok(v) => v,
-                            Err(e) => {
-                                eprintln!("MOMENTO_API_KEY key should be valid: {e}");
-                                std::process::exit(1);
-                            }
-                        };
-
                     match TopicClient::builder()
-                        .configuration(LowLatency::v1())
-                        .credential_provider(credential_provider)
+                        .configuration(configuration::<Configuration>(&config))
+                        .credential_provider(credential_provider(&config))
                         .build()
                     {
                         Ok(c) => Arc::new(c),
@@ -69,6 +56,7 @@ pub fn launch_subscribers(
                             client.clone(),
                             cache_name.clone(),
                             topic.to_string(),
+                            churn.clone(),
                         ));
                     }
                 }
@@ -77,39 +65,69 @@ pub fn launch_subscribers(
     }
 }
 
-async fn subscriber_task(client: Arc<TopicClient>, cache_name: String, topic: String) {
-    PUBSUB_SUBSCRIBE.increment();
-    if let Ok(mut subscription) = client
-        .subscribe(cache_name.clone(), topic.to_string())
-        .await
-    {
+/// Subscribes to the topic until the subscription is closed. When churn is
+/// enabled, the subscription is periodically dropped and recreated.
+async fn subscriber_task(
+    client: Arc<TopicClient>,
+    cache_name: String,
+    topic: String,
+    churn: Churn,
+) {
+    let validator = MessageValidator::subscriber(None, &[Arc::new(topic.clone())]);
+
+    // set while the subscription is being recreated
+    let mut rebalance: Option<Rebalance> = None;
+
+    'subscribe: while RUNNING.load(Ordering::Relaxed) {
+        PUBSUB_SUBSCRIBE.increment();
+
+        let mut subscription = match client
+            .subscribe(cache_name.clone(), topic.to_string())
+            .await
+        {
+            Ok(subscription) => subscription,
+            Err(_) => {
+                PUBSUB_SUBSCRIBE_EX.increment();
+                return;
+            }
+        };
+
         PUBSUB_SUBSCRIBER_CURR.add(1);
         PUBSUB_SUBSCRIBE_OK.increment();
 
-        let validator = MessageValidator::new();
+        if let Some(rebalance) = rebalance.take() {
+            rebalance.complete();
+        }
 
         while RUNNING.load(Ordering::Relaxed) {
-            match subscription.next().await {
-                Some(v) => {
-                    if let ValueKind::Binary(mut v) = v.kind {
-                        let _ = validator.validate(&mut v);
-                    } else {
-                        error!("there was a string in the topic");
-                        // unexpected message
+            tokio::select! {
+                v = subscription.next() => match v {
+                    Some(v) => {
+                        if let ValueKind::Binary(mut v) = v.kind {
+                            let _ = validator.validate(&mut v);
+                        } else {
+                            error!("there was a string in the topic");
+                            // unexpected message
+                            PUBSUB_RECEIVE.increment();
+                            PUBSUB_RECEIVE_EX.increment();
+                        }
+                    }
+                    None => {
                         PUBSUB_RECEIVE.increment();
-                        PUBSUB_RECEIVE_EX.increment();
+                        PUBSUB_RECEIVE_CLOSED.increment();
+                        PUBSUB_SUBSCRIBER_CURR.sub(1);
+                        return;
                     }
-                }
-                None => {
-                    PUBSUB_RECEIVE.increment();
-                    PUBSUB_RECEIVE_CLOSED.increment();
+                },
+                _ = churn.wait() => {
                     PUBSUB_SUBSCRIBER_CURR.sub(1);
-                    break;
+                    rebalance = Some(Rebalance::start());
+                    continue 'subscribe;
                 }
             }
         }
-    } else {
-        PUBSUB_SUBSCRIBE_EX.increment();
+
+        PUBSUB_SUBSCRIBER_CURR.sub(1);
     }
 }
 
@@ -122,23 +140,9 @@ pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: R
             let _guard = runtime.enter();
 
             // initialize the Momento topic client
-            if std::env::var("MOMENTO_API_KEY").is_err() {
-                eprintln!("environment variable `MOMENTO_API_KEY` is not set");
-                std::process::exit(1);
-            }
-
-            let credential_provider =
-                match CredentialProvider::from_env_var("MOMENTO_API_KEY".to_string()) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        eprintln!("MOMENTO_API_KEY key should be valid: {e}");
-                        std::process::exit(1);
-                    }
-                };
-
             match TopicClient::builder()
-                .configuration(LowLatency::v1())
-                .credential_provider(credential_provider)
+                .configuration(configuration::<Configuration>(&config))
+                .credential_provider(credential_provider(&config))
                 .build()
             {
                 Ok(c) => Arc::new(c),
@@ -179,7 +183,7 @@ async fn publisher_task(
         })
         .to_string();
 
-    let validator = MessageValidator::new();
+    let validator = MessageValidator::publisher();
 
     while RUNNING.load(Ordering::Relaxed) {
         let work_item = work_receiver
@@ -187,6 +191,11 @@ async fn publisher_task(
             .await
             .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
 
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
         REQUEST.increment();
         let start = Instant::now();
         let result = match work_item {
@@ -195,7 +204,7 @@ async fn publisher_task(
                 mut message,
                 key: _,
             } => {
-                validator.stamp(&mut message);
+                validator.stamp(&mut message, &topic, 0);
 
                 PUBSUB_PUBLISH.increment();
 
@@ -223,6 +232,9 @@ async fn publisher_task(
 
                 PUBSUB_PUBLISH_OK.increment();
                 let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
             }
             Err(ResponseError::Exception) => {
                 PUBSUB_PUBLISH_EX.increment();
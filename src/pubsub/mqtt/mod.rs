@@ -0,0 +1,272 @@
+use super::*;
+use rumqttc::Transport;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+mod v4;
+mod v5;
+
+/// Publishes which are waiting to complete. A QoS 0 publish completes once it
+/// is written to the network, a QoS 1 publish once the PUBACK is received, and
+/// a QoS 2 publish once the PUBCOMP is received.
+#[derive(Default)]
+struct Inflight {
+    // publishes which are queued but do not have a packet identifier yet
+    queued: VecDeque<oneshot::Sender<()>>,
+    // publishes which are waiting for an acknowledgement
+    acks: HashMap<u16, oneshot::Sender<()>>,
+}
+
+impl Inflight {
+    /// Called as the event loop writes the next queued publish. QoS 0
+    /// publishes have no packet identifier and complete immediately.
+    fn sent(&mut self, pkid: u16) {
+        if let Some(tx) = self.queued.pop_front() {
+            if pkid == 0 {
+                let _ = tx.send(());
+            } else {
+                self.acks.insert(pkid, tx);
+            }
+        }
+    }
+
+    /// Called when the final acknowledgement for a publish is received.
+    fn acked(&mut self, pkid: u16) {
+        if let Some(tx) = self.acks.remove(&pkid) {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Fails all of the publishes, used when the connection is lost.
+    fn clear(&mut self) {
+        self.queued.clear();
+        self.acks.clear();
+    }
+}
+
+/// Splits the endpoint into the host and port. The port defaults to 1883.
+fn host_port(endpoint: &str) -> (String, u16) {
+    match endpoint.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host.to_string(), port),
+            Err(_) => {
+                eprintln!("MQTT: invalid port for the endpoint: {endpoint}");
+                std::process::exit(1);
+            }
+        },
+        None => (endpoint.to_string(), 1883),
+    }
+}
+
+fn get_transport(config: &Config) -> Transport {
+    let tls = match config.tls() {
+        Some(tls) => tls,
+        None => return Transport::Tcp,
+    };
+
+    let read = |path: &str| {
+        std::fs::read(path).unwrap_or_else(|e| {
+            eprintln!("MQTT: could not read {path}: {e}");
+            std::process::exit(1);
+        })
+    };
+
+    let ca = match tls.ca_file() {
+        Some(ca_file) => read(ca_file),
+        None => {
+            eprintln!("MQTT: TLS requires that `ca_file` is set in the `tls` section");
+            std::process::exit(1);
+        }
+    };
+
+    let client_auth = match (tls.certificate(), tls.private_key()) {
+        (Some(cert), Some(private_key)) => Some((read(cert), read(private_key))),
+        _ => None,
+    };
+
+    Transport::tls(ca, client_auth, None)
+}
+
+/// Returns the name of the MQTT topic for a topic in the workload.
+fn topic_name(config: &Config, topic: &str) -> String {
+    let prefix = config.pubsub().unwrap().mqtt_topic_prefix().unwrap_or("");
+    format!("{prefix}{topic}")
+}
+
+/// Returns the topic filters for a subscriber. This is either the configured
+/// wildcard filter or each of the topics.
+fn topic_filters(config: &Config, topics: &[Arc<String>]) -> Vec<String> {
+    match config.pubsub().unwrap().mqtt_topic_filter() {
+        Some(filter) => vec![filter.to_string()],
+        None => topics
+            .iter()
+            .map(|topic| topic_name(config, topic))
+            .collect(),
+    }
+}
+
+/// Launch tasks with one connection per subscriber. Each connection
+/// subscribes to all of the topics.
+///
+/// Client identifiers are stable across runs so that persistent sessions are
+/// resumed when clean sessions are disabled.
+pub fn launch_subscribers(
+    runtime: &mut Runtime,
+    config: Config,
+    workload_components: &[Component],
+) {
+    debug!("launching mqtt subscriber tasks");
+
+    for (component, topics) in workload_components.iter().enumerate() {
+        if let Component::Topics(topics) = topics {
+            let connections = topics.subscriber_poolsize() * topics.subscriber_concurrency();
+            let filters = topic_filters(&config, topics.topics());
+
+            for id in 0..connections {
+                for (index, endpoint) in config.target().endpoints().iter().enumerate() {
+                    let client_id = format!("rpcperf_subscriber_{component}_{id}_{index}");
+
+                    if config.pubsub().unwrap().mqtt_protocol_version() == 5 {
+                        runtime.spawn(v5::subscriber_task(
+                            config.clone(),
+                            client_id,
+                            endpoint.clone(),
+                            filters.clone(),
+                            MessageValidator::subscriber(None, topics.topics()),
+                        ));
+                    } else {
+                        runtime.spawn(v4::subscriber_task(
+                            config.clone(),
+                            client_id,
+                            endpoint.clone(),
+                            filters.clone(),
+                            MessageValidator::subscriber(None, topics.topics()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Launch tasks with one connection per member of the publisher pool. Each
+/// connection is shared by the publisher tasks, and a separate task drives
+/// the connection and completes the publishes.
+pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    debug!("launching mqtt publisher tasks");
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    for id in 0..pubsub_config.publisher_poolsize() {
+        for (index, endpoint) in config.target().endpoints().iter().enumerate() {
+            let client_id = format!("rpcperf_publisher_{id}_{index}");
+
+            if pubsub_config.mqtt_protocol_version() == 5 {
+                v5::launch_publisher(runtime, &config, client_id, endpoint, &work_receiver);
+            } else {
+                v4::launch_publisher(runtime, &config, client_id, endpoint, &work_receiver);
+            }
+
+            PUBSUB_PUBLISHER_CONNECT.increment();
+        }
+    }
+}
+
+/// Publishes messages using the provided function, which queues the publish
+/// on the connection and returns false if it could not be queued. The latency
+/// is the time until the publish completes.
+async fn publisher_task(
+    config: Config,
+    inflight: Arc<Mutex<Inflight>>,
+    publish: impl Fn(String, Vec<u8>) -> bool + Send,
+    work_receiver: Receiver<WorkItem>,
+) -> Result<()> {
+    PUBSUB_PUBLISHER_CURR.add(1);
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    let validator = MessageValidator::publisher();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        let work_item = work_receiver
+            .recv()
+            .await
+            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
+        REQUEST.increment();
+        let start = Instant::now();
+        let result = match work_item {
+            WorkItem::Publish {
+                topic,
+                mut message,
+                key: _,
+            } => {
+                validator.stamp(&mut message, &topic, 0);
+
+                PUBSUB_PUBLISH.increment();
+
+                let (tx, rx) = oneshot::channel();
+
+                // the publish is added to the queue while holding the lock so
+                // that the queue order matches the order the event loop
+                // assigns packet identifiers
+                let queued = {
+                    let mut inflight = inflight.lock().unwrap();
+                    inflight.queued.push_back(tx);
+
+                    if publish(topic_name(&config, &topic), message) {
+                        true
+                    } else {
+                        inflight.queued.pop_back();
+                        false
+                    }
+                };
+
+                if queued {
+                    match timeout(pubsub_config.publish_timeout(), rx).await {
+                        Ok(Ok(_)) => Ok(()),
+                        Ok(Err(_)) => Err(ResponseError::Exception),
+                        Err(_) => Err(ResponseError::Timeout),
+                    }
+                } else {
+                    Err(ResponseError::Exception)
+                }
+            }
+        };
+
+        let stop = Instant::now();
+
+        match result {
+            Ok(_) => {
+                let latency = stop.duration_since(start).as_nanos() as u64;
+
+                PUBSUB_PUBLISH_OK.increment();
+                let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
+            }
+            Err(ResponseError::Exception) => {
+                PUBSUB_PUBLISH_EX.increment();
+            }
+            Err(ResponseError::Timeout) | Err(ResponseError::BackendTimeout) => {
+                PUBSUB_PUBLISH_TIMEOUT.increment();
+            }
+            Err(ResponseError::Ratelimited) => {
+                PUBSUB_PUBLISH_RATELIMITED.increment();
+            }
+        }
+    }
+
+    PUBSUB_PUBLISHER_CURR.sub(1);
+
+    Ok(())
+}
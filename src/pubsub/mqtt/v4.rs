@@ -0,0 +1,218 @@
+// MQTT 3.1.1 (protocol level 4)
+
+use super::*;
+use rumqttc::{
+    AsyncClient, Event, EventLoop, MqttOptions, Outgoing, Packet, QoS, SubscribeFilter,
+    SubscribeReasonCode,
+};
+
+fn get_qos(config: &Config) -> QoS {
+    match config.pubsub().unwrap().mqtt_qos() {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::ExactlyOnce,
+    }
+}
+
+fn get_client(
+    config: &Config,
+    client_id: String,
+    endpoint: &str,
+    cap: usize,
+) -> (AsyncClient, EventLoop) {
+    let pubsub_config = config.pubsub().unwrap();
+    let (host, port) = host_port(endpoint);
+
+    let mut options = MqttOptions::new(client_id, host, port);
+    options
+        .set_keep_alive(pubsub_config.mqtt_keep_alive())
+        .set_clean_session(pubsub_config.mqtt_clean_session())
+        .set_max_packet_size(
+            pubsub_config.read_buffer_size(),
+            pubsub_config.write_buffer_size(),
+        )
+        .set_transport(get_transport(config));
+
+    let (client, mut eventloop) = AsyncClient::new(options, cap);
+    eventloop
+        .network_options
+        .set_connection_timeout(std::cmp::max(1, pubsub_config.connect_timeout().as_secs()));
+
+    (client, eventloop)
+}
+
+pub fn launch_publisher(
+    runtime: &mut Runtime,
+    config: &Config,
+    client_id: String,
+    endpoint: &str,
+    work_receiver: &Receiver<WorkItem>,
+) {
+    let pubsub_config = config.pubsub().unwrap();
+    let concurrency = pubsub_config.publisher_concurrency();
+
+    // each publisher task queues at most one publish at a time
+    let (client, eventloop) =
+        get_client(config, client_id, endpoint, std::cmp::max(1, concurrency));
+
+    let inflight = Arc::new(Mutex::new(Inflight::default()));
+
+    runtime.spawn(publisher_connection(
+        config.clone(),
+        eventloop,
+        inflight.clone(),
+    ));
+
+    let qos = get_qos(config);
+    let retain = pubsub_config.mqtt_retain();
+
+    for _ in 0..concurrency {
+        let client = client.clone();
+
+        runtime.spawn(publisher_task(
+            config.clone(),
+            inflight.clone(),
+            move |topic, message| client.try_publish(topic, qos, retain, message).is_ok(),
+            work_receiver.clone(),
+        ));
+    }
+}
+
+/// Drives the connection for the publishers, completing each publish when it
+/// is written (QoS 0), acknowledged (QoS 1), or completed (QoS 2).
+async fn publisher_connection(
+    config: Config,
+    mut eventloop: EventLoop,
+    inflight: Arc<Mutex<Inflight>>,
+) {
+    let qos = config.pubsub().unwrap().mqtt_qos();
+
+    let mut connected = false;
+
+    CONNECT.increment();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                CONNECT_OK.increment();
+                CONNECT_CURR.increment();
+                connected = true;
+            }
+            Ok(Event::Outgoing(Outgoing::Publish(pkid))) => {
+                inflight.lock().unwrap().sent(pkid);
+            }
+            Ok(Event::Incoming(Packet::PubAck(ack))) if qos == 1 => {
+                inflight.lock().unwrap().acked(ack.pkid);
+            }
+            Ok(Event::Incoming(Packet::PubComp(comp))) if qos == 2 => {
+                inflight.lock().unwrap().acked(comp.pkid);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                debug!("MQTT connection error: {e}");
+
+                if connected {
+                    CONNECT_CURR.decrement();
+                    connected = false;
+                } else {
+                    CONNECT_EX.increment();
+                }
+
+                inflight.lock().unwrap().clear();
+
+                // the event loop reconnects when it is next polled
+                sleep(Duration::from_millis(100)).await;
+                CONNECT.increment();
+            }
+        }
+    }
+}
+
+pub async fn subscriber_task(
+    config: Config,
+    client_id: String,
+    endpoint: String,
+    filters: Vec<String>,
+    validator: MessageValidator,
+) {
+    let (client, mut eventloop) = get_client(&config, client_id, &endpoint, 10);
+
+    let qos = get_qos(&config);
+
+    let mut connected = false;
+    let mut subscribed = false;
+
+    CONNECT.increment();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(ack))) => {
+                CONNECT_OK.increment();
+                CONNECT_CURR.increment();
+                connected = true;
+
+                // a resumed session keeps its subscriptions
+                if ack.session_present {
+                    PUBSUB_SUBSCRIBER_CURR.add(1);
+                    subscribed = true;
+                    continue;
+                }
+
+                PUBSUB_SUBSCRIBE.increment();
+
+                let filters = filters
+                    .iter()
+                    .map(|filter| SubscribeFilter::new(filter.clone(), qos));
+
+                if client.try_subscribe_many(filters).is_err() {
+                    PUBSUB_SUBSCRIBE_EX.increment();
+                }
+            }
+            Ok(Event::Incoming(Packet::SubAck(ack))) => {
+                if ack
+                    .return_codes
+                    .iter()
+                    .all(|code| matches!(code, SubscribeReasonCode::Success(_)))
+                {
+                    PUBSUB_SUBSCRIBE_OK.increment();
+
+                    if !subscribed {
+                        PUBSUB_SUBSCRIBER_CURR.add(1);
+                        subscribed = true;
+                    }
+                } else {
+                    PUBSUB_SUBSCRIBE_EX.increment();
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let _ = validator.validate(&mut publish.payload.to_vec());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                debug!("MQTT connection error: {e}");
+
+                if connected {
+                    CONNECT_CURR.decrement();
+                    connected = false;
+                } else {
+                    CONNECT_EX.increment();
+                }
+
+                if subscribed {
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_CLOSED.increment();
+                    PUBSUB_SUBSCRIBER_CURR.sub(1);
+                    subscribed = false;
+                }
+
+                // the event loop reconnects when it is next polled
+                sleep(Duration::from_millis(100)).await;
+                CONNECT.increment();
+            }
+        }
+    }
+
+    if subscribed {
+        PUBSUB_SUBSCRIBER_CURR.sub(1);
+    }
+}
@@ -0,0 +1,323 @@
+use super::*;
+use async_nats::jetstream::{self, consumer, stream};
+use async_nats::{Client, ConnectOptions};
+use futures::stream::StreamExt;
+use tokio::time::timeout;
+
+use std::sync::Arc;
+
+fn get_connect_options(config: &Config) -> ConnectOptions {
+    let pubsub_config = config.pubsub().unwrap();
+    let mut options = ConnectOptions::new()
+        .connection_timeout(pubsub_config.connect_timeout())
+        .no_echo();
+    if let Some(tls) = config.tls() {
+        options = options.require_tls(true);
+        if let Some(ca_file) = tls.ca_file() {
+            options = options.add_root_certificates(ca_file.into());
+        }
+        if let (Some(cert), Some(private_key)) = (tls.certificate(), tls.private_key()) {
+            options = options.add_client_certificate(cert.into(), private_key.into());
+        }
+    }
+    options
+}
+
+fn get_nats_client(runtime: &mut Runtime, config: &Config) -> Client {
+    let servers = config.target().endpoints().join(",");
+    match runtime.block_on(get_connect_options(config).connect(servers.as_str())) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("NATS: could not connect to the servers: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Creates a JetStream stream for each topic. The stream captures the subject
+/// with the same name as the topic. Does nothing for core NATS.
+pub fn create_streams(runtime: &mut Runtime, config: Config, workload_components: &[Component]) {
+    if !config.pubsub().unwrap().nats_jetstream() {
+        return;
+    }
+
+    let client = get_nats_client(runtime, &config);
+    let context = jetstream::new(client);
+
+    for component in workload_components {
+        if let Component::Topics(topics) = component {
+            for topic in topics.topics() {
+                if let Err(e) = runtime.block_on(context.get_or_create_stream(stream::Config {
+                    name: topic.to_string(),
+                    subjects: vec![topic.to_string()],
+                    num_replicas: topics.replications(),
+                    ..Default::default()
+                })) {
+                    eprintln!("NATS: failed to create the stream {topic}: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Launch tasks with one subscription per task as NATS connections are
+/// mux-enabled.
+///
+/// For JetStream, each member of the subscriber pool is a durable consumer on
+/// each stream, shared by the tasks for that member.
+pub fn launch_subscribers(
+    runtime: &mut Runtime,
+    config: Config,
+    workload_components: &[Component],
+) {
+    debug!("launching nats subscriber tasks");
+
+    let jetstream = config.pubsub().unwrap().nats_jetstream();
+
+    for component in workload_components {
+        if let Component::Topics(topics) = component {
+            let poolsize = topics.subscriber_poolsize();
+            let concurrency = topics.subscriber_concurrency();
+
+            for id in 0..poolsize {
+                let client = get_nats_client(runtime, &config);
+
+                // the tasks for a durable consumer share its messages
+                let validator = MessageValidator::subscriber(None, topics.topics());
+
+                for _ in 0..concurrency {
+                    for topic in topics.topics() {
+                        if jetstream {
+                            runtime.spawn(jetstream_subscriber_task(
+                                jetstream::new(client.clone()),
+                                topic.clone(),
+                                format!("rpcperf_subscriber_{id}"),
+                                validator.clone(),
+                            ));
+                        } else {
+                            runtime.spawn(subscriber_task(client.clone(), topic.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn subscriber_task(client: Client, topic: Arc<String>) {
+    PUBSUB_SUBSCRIBE.increment();
+
+    if let Ok(mut subscription) = client.subscribe(topic.to_string()).await {
+        PUBSUB_SUBSCRIBER_CURR.add(1);
+        PUBSUB_SUBSCRIBE_OK.increment();
+
+        let validator = MessageValidator::subscriber(None, std::slice::from_ref(&topic));
+
+        while RUNNING.load(Ordering::Relaxed) {
+            match subscription.next().await {
+                Some(message) => {
+                    let _ = validator.validate(&mut message.payload.to_vec());
+                }
+                None => {
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_CLOSED.increment();
+                    break;
+                }
+            }
+        }
+
+        PUBSUB_SUBSCRIBER_CURR.sub(1);
+    } else {
+        PUBSUB_SUBSCRIBE_EX.increment();
+    }
+}
+
+async fn jetstream_subscriber_task(
+    context: jetstream::Context,
+    topic: Arc<String>,
+    name: String,
+    validator: MessageValidator,
+) {
+    PUBSUB_SUBSCRIBE.increment();
+
+    let consumer = match context.get_stream(topic.as_str()).await {
+        Ok(stream) => {
+            stream
+                .get_or_create_consumer(
+                    &name,
+                    consumer::pull::Config {
+                        durable_name: Some(name.clone()),
+                        ack_policy: consumer::AckPolicy::Explicit,
+                        ..Default::default()
+                    },
+                )
+                .await
+        }
+        Err(e) => {
+            error!("NATS: failed to get the stream {topic}: {e}");
+            PUBSUB_SUBSCRIBE_EX.increment();
+            return;
+        }
+    };
+
+    let mut messages = match consumer {
+        Ok(consumer) => match consumer.messages().await {
+            Ok(messages) => messages,
+            Err(e) => {
+                error!("NATS: failed to receive from the consumer {name}: {e}");
+                PUBSUB_SUBSCRIBE_EX.increment();
+                return;
+            }
+        },
+        Err(e) => {
+            error!("NATS: failed to create the consumer {name}: {e}");
+            PUBSUB_SUBSCRIBE_EX.increment();
+            return;
+        }
+    };
+
+    PUBSUB_SUBSCRIBER_CURR.add(1);
+    PUBSUB_SUBSCRIBE_OK.increment();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        match messages.next().await {
+            Some(Ok(message)) => {
+                let _ = validator.validate(&mut message.payload.to_vec());
+
+                if message.ack().await.is_err() {
+                    PUBSUB_ACK_EX.increment();
+                }
+            }
+            Some(Err(e)) => {
+                debug!("NATS message error: {e}");
+                PUBSUB_RECEIVE.increment();
+                PUBSUB_RECEIVE_EX.increment();
+            }
+            None => {
+                PUBSUB_RECEIVE.increment();
+                PUBSUB_RECEIVE_CLOSED.increment();
+                break;
+            }
+        }
+    }
+
+    PUBSUB_SUBSCRIBER_CURR.sub(1);
+}
+
+/// Launch tasks with one channel per task as NATS connections are mux-enabled.
+pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    debug!("launching nats publisher tasks");
+
+    for _ in 0..config.pubsub().unwrap().publisher_poolsize() {
+        let client = get_nats_client(runtime, &config);
+
+        PUBSUB_PUBLISHER_CONNECT.increment();
+
+        for _ in 0..config.pubsub().unwrap().publisher_concurrency() {
+            runtime.spawn(publisher_task(
+                config.clone(),
+                client.clone(),
+                work_receiver.clone(),
+            ));
+        }
+    }
+}
+
+async fn publisher_task(
+    config: Config,
+    client: Client,
+    work_receiver: Receiver<WorkItem>,
+) -> Result<()> {
+    PUBSUB_PUBLISHER_CURR.add(1);
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    // with JetStream, each publish waits for the stream to acknowledge it
+    let context = if pubsub_config.nats_jetstream() {
+        Some(jetstream::new(client.clone()))
+    } else {
+        None
+    };
+
+    let validator = MessageValidator::publisher();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        let work_item = work_receiver
+            .recv()
+            .await
+            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
+        REQUEST.increment();
+        let start = Instant::now();
+        let result = match work_item {
+            WorkItem::Publish {
+                topic,
+                mut message,
+                key: _,
+            } => {
+                validator.stamp(&mut message, &topic, 0);
+
+                PUBSUB_PUBLISH.increment();
+
+                let publish = async {
+                    if let Some(context) = &context {
+                        context
+                            .publish(topic.to_string(), message.into())
+                            .await
+                            .map_err(|e| e.to_string())?
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    } else {
+                        client
+                            .publish(topic.to_string(), message.into())
+                            .await
+                            .map_err(|e| e.to_string())
+                    }
+                };
+
+                match timeout(pubsub_config.publish_timeout(), publish).await {
+                    Ok(Ok(_)) => Ok(()),
+                    Ok(Err(e)) => {
+                        debug!("NATS publish error: {e}");
+                        Err(ResponseError::Exception)
+                    }
+                    Err(_) => Err(ResponseError::Timeout),
+                }
+            }
+        };
+
+        let stop = Instant::now();
+
+        match result {
+            Ok(_) => {
+                let latency = stop.duration_since(start).as_nanos() as u64;
+
+                PUBSUB_PUBLISH_OK.increment();
+                let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
+            }
+            Err(ResponseError::Exception) => {
+                PUBSUB_PUBLISH_EX.increment();
+            }
+            Err(ResponseError::Timeout) | Err(ResponseError::BackendTimeout) => {
+                PUBSUB_PUBLISH_TIMEOUT.increment();
+            }
+            Err(ResponseError::Ratelimited) => {
+                PUBSUB_PUBLISH_RATELIMITED.increment();
+            }
+        }
+    }
+
+    PUBSUB_PUBLISHER_CURR.sub(1);
+
+    Ok(())
+}
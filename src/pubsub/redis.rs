@@ -0,0 +1,344 @@
+use super::*;
+use crate::clients::redis::protocol::{Request, Response, ResponseParser};
+use crate::net::{Connector, Stream};
+use bytes::{Buf, BufMut};
+use session::Buffer;
+use std::borrow::{Borrow, BorrowMut};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout;
+
+/// A connection to a RESP server which is used either to publish messages or
+/// to receive the messages for its subscriptions.
+pub(super) struct Connection {
+    stream: Stream,
+    read_buffer: Buffer,
+    write_buffer: Buffer,
+    parser: ResponseParser,
+}
+
+impl Connection {
+    fn new(stream: Stream, config: &Pubsub) -> Self {
+        Self {
+            stream,
+            read_buffer: Buffer::new(config.read_buffer_size()),
+            write_buffer: Buffer::new(config.write_buffer_size()),
+            parser: ResponseParser::new(),
+        }
+    }
+
+    pub(super) async fn send(&mut self, request: &Request) -> Result<()> {
+        request.compose(&mut self.write_buffer);
+
+        let result = self.stream.write_all(self.write_buffer.borrow()).await;
+
+        self.write_buffer.clear();
+
+        result
+    }
+
+    pub(super) async fn receive(&mut self) -> Result<Response<'static>> {
+        loop {
+            // there may already be complete responses in the buffer
+            let parsed = match self.parser.parse(self.read_buffer.borrow()) {
+                Ok(response) => Some((response.consumed(), response.into_inner().into_owned())),
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        return Err(e);
+                    }
+
+                    None
+                }
+            };
+
+            if let Some((consumed, response)) = parsed {
+                self.read_buffer.advance(consumed);
+
+                return Ok(response);
+            }
+
+            let n = self.stream.read(self.read_buffer.borrow_mut()).await?;
+
+            if n == 0 {
+                return Err(Error::new(ErrorKind::ConnectionReset, "connection closed"));
+            }
+
+            unsafe {
+                self.read_buffer.advance_mut(n);
+            }
+        }
+    }
+}
+
+/// Connects to the endpoint, recording the connection metrics.
+pub(super) async fn connect(
+    connector: &Connector,
+    endpoint: &str,
+    config: &Pubsub,
+) -> Option<Connection> {
+    CONNECT.increment();
+
+    match timeout(config.connect_timeout(), connector.connect(endpoint)).await {
+        Ok(Ok(stream)) => {
+            CONNECT_OK.increment();
+            CONNECT_CURR.increment();
+
+            Some(Connection::new(stream, config))
+        }
+        Ok(Err(_)) => {
+            CONNECT_EX.increment();
+            None
+        }
+        Err(_) => {
+            CONNECT_TIMEOUT.increment();
+            None
+        }
+    }
+}
+
+/// Returns the payload if the response is a message for a subscription. The
+/// replies which confirm each subscription are ignored.
+fn payload(response: Response) -> Option<Vec<u8>> {
+    let mut data = match response {
+        Response::Array(data) | Response::Push(data) => data,
+        _ => return None,
+    };
+
+    let index = match data.first() {
+        Some(Response::BulkString(kind)) => match (&kind[..], data.len()) {
+            (b"message", 3) => 2,
+            (b"pmessage", 4) => 3,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match data.swap_remove(index) {
+        Response::BulkString(payload) => Some(payload.into_owned()),
+        _ => None,
+    }
+}
+
+/// Launch tasks with one conncetion per task as RESP protocol is not mux-enabled.
+pub fn launch_subscribers(
+    runtime: &mut Runtime,
+    config: Config,
+    workload_components: &[Component],
+) {
+    debug!("launching redis subscriber tasks");
+
+    for component in workload_components {
+        if let Component::Topics(topics) = component {
+            let connections = topics.subscriber_poolsize() * topics.subscriber_concurrency();
+
+            // each connection subscribes to all of the topics
+            for _ in 0..connections {
+                for endpoint in config.target().endpoints() {
+                    runtime.spawn(subscriber_task(
+                        endpoint.clone(),
+                        config.clone(),
+                        topics.topics().to_vec(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+async fn subscriber_task(endpoint: String, config: Config, topics: Vec<Arc<String>>) -> Result<()> {
+    let validator = MessageValidator::subscriber(None, &topics);
+
+    let connector = Connector::new(&config)?;
+
+    // this unwrap will succeed because we wouldn't be creating these tasks if
+    // there wasn't a pubsub config.
+    let pubsub_config = config.pubsub().unwrap();
+
+    let request = if pubsub_config.redis_psubscribe() {
+        Request::new("PSUBSCRIBE")
+    } else {
+        Request::new("SUBSCRIBE")
+    }
+    .args(topics.iter().map(|topic| topic.as_bytes().to_vec()));
+
+    while RUNNING.load(Ordering::Relaxed) {
+        PUBSUB_SUBSCRIBE.increment();
+
+        let mut connection = match connect(&connector, &endpoint, pubsub_config).await {
+            Some(c) => c,
+            None => {
+                PUBSUB_SUBSCRIBE_EX.increment();
+
+                sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        if connection.send(&request).await.is_err() {
+            CONNECT_CURR.decrement();
+            PUBSUB_SUBSCRIBE_EX.increment();
+
+            sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+
+        PUBSUB_SUBSCRIBER_CURR.add(1);
+        PUBSUB_SUBSCRIBE_OK.increment();
+
+        // receive messages until the connection is closed
+        while RUNNING.load(Ordering::Relaxed) {
+            match connection.receive().await {
+                Ok(Response::Error(e)) => {
+                    debug!("subscription error: {}", String::from_utf8_lossy(&e));
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_EX.increment();
+                }
+                Ok(response) => {
+                    if let Some(mut payload) = payload(response) {
+                        let _ = validator.validate(&mut payload);
+                    }
+                }
+                Err(_) => {
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_CLOSED.increment();
+                    break;
+                }
+            }
+        }
+
+        CONNECT_CURR.decrement();
+        PUBSUB_SUBSCRIBER_CURR.sub(1);
+    }
+
+    Ok(())
+}
+
+/// Launch tasks with one conncetion per task as RESP protocol is not mux-enabled.
+pub fn launch_publishers(runtime: &mut Runtime, config: Config, work_receiver: Receiver<WorkItem>) {
+    debug!("launching redis publisher tasks");
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    let connections = pubsub_config.publisher_poolsize() * pubsub_config.publisher_concurrency();
+
+    for _ in 0..connections {
+        for endpoint in config.target().endpoints() {
+            runtime.spawn(publisher_task(
+                endpoint.clone(),
+                config.clone(),
+                work_receiver.clone(),
+            ));
+        }
+    }
+}
+
+async fn publisher_task(
+    endpoint: String,
+    config: Config,
+    work_receiver: Receiver<WorkItem>,
+) -> Result<()> {
+    let validator = MessageValidator::publisher();
+
+    let connector = Connector::new(&config)?;
+
+    // this unwrap will succeed because we wouldn't be creating these tasks if
+    // there wasn't a pubsub config.
+    let pubsub_config = config.pubsub().unwrap();
+
+    let mut connection = None;
+
+    PUBSUB_PUBLISHER_CURR.add(1);
+
+    while RUNNING.load(Ordering::Relaxed) {
+        if connection.is_none() {
+            PUBSUB_PUBLISHER_CONNECT.increment();
+
+            connection = connect(&connector, &endpoint, pubsub_config).await;
+
+            if connection.is_none() {
+                sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        }
+
+        let mut con = connection.take().unwrap();
+
+        let work_item = work_receiver
+            .recv()
+            .await
+            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
+        REQUEST.increment();
+
+        let start = Instant::now();
+
+        let result = match work_item {
+            WorkItem::Publish {
+                topic,
+                mut message,
+                key: _,
+            } => {
+                validator.stamp(&mut message, &topic, 0);
+
+                PUBSUB_PUBLISH.increment();
+
+                let request = Request::new("PUBLISH")
+                    .arg(topic.as_bytes().to_vec())
+                    .arg(message);
+
+                match timeout(pubsub_config.publish_timeout(), async {
+                    con.send(&request).await?;
+                    con.receive().await
+                })
+                .await
+                {
+                    // the reply is the number of subscribers which received
+                    // the message
+                    Ok(Ok(Response::Integer(_))) => Ok(()),
+                    Ok(Ok(_)) => Err(ResponseError::Exception),
+                    Ok(Err(_)) => {
+                        // the connection is no longer usable
+                        CONNECT_CURR.decrement();
+                        PUBSUB_PUBLISH_EX.increment();
+                        continue;
+                    }
+                    Err(_) => {
+                        // a late reply would be read as the reply to the next
+                        // request, so the connection is dropped
+                        CONNECT_CURR.decrement();
+                        PUBSUB_PUBLISH_TIMEOUT.increment();
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let stop = Instant::now();
+
+        connection = Some(con);
+
+        match result {
+            Ok(_) => {
+                let latency = stop.duration_since(start).as_nanos() as u64;
+
+                PUBSUB_PUBLISH_OK.increment();
+                let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
+            }
+            Err(_) => {
+                PUBSUB_PUBLISH_EX.increment();
+            }
+        }
+    }
+
+    PUBSUB_PUBLISHER_CURR.sub(1);
+
+    Ok(())
+}
@@ -0,0 +1,550 @@
+use super::redis::{connect, Connection};
+use super::*;
+use crate::clients::redis::protocol::{Request, Response};
+use crate::net::Connector;
+use crate::workload::Topics;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::time::timeout;
+
+// the field of each stream entry which holds the message
+const MESSAGE_FIELD: &str = "message";
+
+// the maximum number of entries returned by each read
+const READ_COUNT: i64 = 100;
+
+// how long each read blocks waiting for new entries, in milliseconds
+const READ_BLOCK_MS: i64 = 1000;
+
+/// Returns the name of the stream which holds a partition of the topic. Each
+/// partition of a topic is a separate stream, but a topic with only one
+/// partition uses the topic name for the stream.
+fn stream_name(topic: &str, partition: usize, partitions: usize) -> Vec<u8> {
+    if partitions == 1 {
+        topic.as_bytes().to_vec()
+    } else {
+        format!("{topic}-{partition}").into_bytes()
+    }
+}
+
+/// Returns the names of all of the streams for the topics.
+fn stream_names(topics: &Topics) -> Vec<Vec<u8>> {
+    let partitions = topics.partitions();
+
+    topics
+        .topics()
+        .iter()
+        .flat_map(|topic| (0..partitions).map(move |p| stream_name(topic, p, partitions)))
+        .collect()
+}
+
+/// Splits the reply to `XREADGROUP` into the name of each stream and the
+/// entries which were read from it. The reply is an array for RESP2 and a map
+/// for RESP3, and is null if the read timed out.
+fn streams(response: Response) -> Vec<(Vec<u8>, Response)> {
+    match response {
+        Response::Array(streams) => streams
+            .into_iter()
+            .filter_map(|stream| match stream {
+                Response::Array(stream) => {
+                    let mut stream = stream.into_iter();
+                    match (stream.next(), stream.next()) {
+                        (Some(Response::BulkString(name)), Some(entries)) => {
+                            Some((name.into_owned(), entries))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect(),
+        Response::Map(streams) => streams
+            .into_iter()
+            .filter_map(|(name, entries)| match name {
+                Response::BulkString(name) => Some((name.into_owned(), entries)),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the ID of each entry and the message, if the entry contains one.
+fn entries(entries: Response) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+    let entries = match entries {
+        Response::Array(entries) => entries,
+        _ => return Vec::new(),
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let mut entry = match entry {
+                Response::Array(entry) => entry.into_iter(),
+                _ => return None,
+            };
+
+            let id = match entry.next() {
+                Some(Response::BulkString(id)) => id.into_owned(),
+                _ => return None,
+            };
+
+            // the fields are a flat list of names and values
+            let mut message = None;
+
+            if let Some(Response::Array(fields)) = entry.next() {
+                let mut fields = fields.into_iter();
+
+                while let (Some(name), Some(value)) = (fields.next(), fields.next()) {
+                    if let (Response::BulkString(name), Response::BulkString(value)) = (name, value)
+                    {
+                        if name == MESSAGE_FIELD.as_bytes() {
+                            message = Some(value.into_owned());
+                        }
+                    }
+                }
+            }
+
+            Some((id, message))
+        })
+        .collect()
+}
+
+/// Returns the value of a field in the reply to `XINFO GROUPS` for a single
+/// group. Each group is a flat array of names and values for RESP2 and a map
+/// for RESP3.
+fn group_field<'a, 'b>(group: &'a Response<'b>, field: &[u8]) -> Option<&'a Response<'b>> {
+    match group {
+        Response::Array(fields) => fields.chunks_exact(2).find_map(|pair| match &pair[0] {
+            Response::BulkString(name) if *name == field => Some(&pair[1]),
+            _ => None,
+        }),
+        Response::Map(fields) => fields.iter().find_map(|(name, value)| match name {
+            Response::BulkString(name) if *name == field => Some(value),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Launch tasks with one conncetion per task as RESP protocol is not mux-enabled.
+///
+/// Each member of the subscriber pool is a consumer group, and each of the
+/// subscriber tasks within that member is a consumer in the group. A single
+/// task reports the pending entries and lag for all of the groups.
+pub fn launch_subscribers(
+    runtime: &mut Runtime,
+    config: Config,
+    workload_components: &[Component],
+) {
+    debug!("launching redis streams subscriber tasks");
+
+    let mut groups = Vec::new();
+
+    for component in workload_components {
+        if let Component::Topics(topics) = component {
+            let streams = stream_names(topics);
+
+            for id in 0..topics.subscriber_poolsize() {
+                let group = format!("rpcperf_subscriber_{id}");
+
+                // the consumers in a group share the entries of the streams
+                let validator = MessageValidator::subscriber(None, topics.topics());
+
+                for consumer in 0..topics.subscriber_concurrency() {
+                    for endpoint in config.target().endpoints() {
+                        runtime.spawn(subscriber_task(
+                            endpoint.clone(),
+                            config.clone(),
+                            group.clone(),
+                            format!("{group}_{consumer}"),
+                            streams.clone(),
+                            validator.clone(),
+                        ));
+                    }
+                }
+
+                for stream in &streams {
+                    groups.push((stream.clone(), group.clone()));
+                }
+            }
+        }
+    }
+
+    runtime.spawn(lag_task(config, groups));
+}
+
+async fn subscriber_task(
+    endpoint: String,
+    config: Config,
+    group: String,
+    consumer: String,
+    streams: Vec<Vec<u8>>,
+    validator: MessageValidator,
+) -> Result<()> {
+    let connector = Connector::new(&config)?;
+
+    // this unwrap will succeed because we wouldn't be creating these tasks if
+    // there wasn't a pubsub config.
+    let pubsub_config = config.pubsub().unwrap();
+
+    // read the entries which have not been delivered to the group from each
+    // of the streams
+    let read = Request::new("XREADGROUP")
+        .arg("GROUP")
+        .arg(group.as_bytes().to_vec())
+        .arg(consumer.into_bytes())
+        .arg("COUNT")
+        .arg(READ_COUNT)
+        .arg("BLOCK")
+        .arg(READ_BLOCK_MS)
+        .arg("STREAMS")
+        .args(streams.iter().cloned())
+        .args(streams.iter().map(|_| ">"));
+
+    'connection: while RUNNING.load(Ordering::Relaxed) {
+        PUBSUB_SUBSCRIBE.increment();
+
+        let mut connection = match connect(&connector, &endpoint, pubsub_config).await {
+            Some(c) => c,
+            None => {
+                PUBSUB_SUBSCRIBE_EX.increment();
+
+                sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        // create the group for each stream, starting from new entries. The
+        // group may have been created by another subscriber already.
+        for stream in &streams {
+            let request = Request::new("XGROUP")
+                .arg("CREATE")
+                .arg(stream.clone())
+                .arg(group.as_bytes().to_vec())
+                .arg("$")
+                .arg("MKSTREAM");
+
+            let created = match connection.send(&request).await {
+                Ok(_) => match connection.receive().await {
+                    Ok(Response::SimpleString(_)) => true,
+                    Ok(Response::Error(e)) => e.starts_with(b"BUSYGROUP"),
+                    _ => false,
+                },
+                Err(_) => false,
+            };
+
+            if !created {
+                CONNECT_CURR.decrement();
+                PUBSUB_SUBSCRIBE_EX.increment();
+
+                sleep(Duration::from_millis(100)).await;
+                continue 'connection;
+            }
+        }
+
+        PUBSUB_SUBSCRIBER_CURR.add(1);
+        PUBSUB_SUBSCRIBE_OK.increment();
+
+        // read and acknowledge entries until the connection is closed
+        'read: while RUNNING.load(Ordering::Relaxed) {
+            let response = match connection.send(&read).await {
+                Ok(_) => connection.receive().await,
+                Err(e) => Err(e),
+            };
+
+            let response = match response {
+                Ok(Response::Error(e)) => {
+                    debug!("stream read error: {}", String::from_utf8_lossy(&e));
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_EX.increment();
+                    continue;
+                }
+                Ok(response) => response,
+                Err(_) => {
+                    PUBSUB_RECEIVE.increment();
+                    PUBSUB_RECEIVE_CLOSED.increment();
+                    break;
+                }
+            };
+
+            for (stream, entries) in streams(response) {
+                let mut ids = Vec::new();
+
+                for (id, message) in entries(entries) {
+                    if let Some(mut message) = message {
+                        let _ = validator.validate(&mut message);
+                    } else {
+                        PUBSUB_RECEIVE.increment();
+                        PUBSUB_RECEIVE_INVALID.increment();
+                    }
+
+                    ids.push(id);
+                }
+
+                if ids.is_empty() {
+                    continue;
+                }
+
+                let ack = Request::new("XACK")
+                    .arg(stream)
+                    .arg(group.as_bytes().to_vec())
+                    .args(ids);
+
+                match connection.send(&ack).await {
+                    Ok(_) => match connection.receive().await {
+                        Ok(Response::Integer(_)) => {}
+                        Ok(_) => {
+                            PUBSUB_ACK_EX.increment();
+                        }
+                        Err(_) => {
+                            PUBSUB_ACK_EX.increment();
+                            break 'read;
+                        }
+                    },
+                    Err(_) => {
+                        PUBSUB_ACK_EX.increment();
+                        break 'read;
+                    }
+                }
+            }
+        }
+
+        CONNECT_CURR.decrement();
+        PUBSUB_SUBSCRIBER_CURR.sub(1);
+    }
+
+    Ok(())
+}
+
+/// Periodically sums the pending entries and the lag for the subscriber
+/// groups across all of the endpoints. The lag is only reported by servers
+/// which support it (Redis 7.0 and later).
+async fn lag_task(config: Config, groups: Vec<(Vec<u8>, String)>) -> Result<()> {
+    let connector = Connector::new(&config)?;
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    // the groups for each stream
+    let mut streams: HashMap<Vec<u8>, HashSet<Vec<u8>>> = HashMap::new();
+
+    for (stream, group) in groups {
+        streams
+            .entry(stream)
+            .or_default()
+            .insert(group.into_bytes());
+    }
+
+    let endpoints = config.target().endpoints();
+
+    let mut connections: Vec<Option<Connection>> = endpoints.iter().map(|_| None).collect();
+
+    while RUNNING.load(Ordering::Relaxed) {
+        sleep(Duration::from_secs(1)).await;
+
+        let mut pending = 0;
+        let mut lag = 0;
+
+        for (endpoint, connection) in endpoints.iter().zip(connections.iter_mut()) {
+            if connection.is_none() {
+                *connection = connect(&connector, endpoint, pubsub_config).await;
+            }
+
+            let con = match connection {
+                Some(con) => con,
+                None => continue,
+            };
+
+            let mut failed = false;
+
+            for (stream, ours) in &streams {
+                let request = Request::new("XINFO").arg("GROUPS").arg(stream.clone());
+
+                let response = match con.send(&request).await {
+                    Ok(_) => con.receive().await,
+                    Err(e) => Err(e),
+                };
+
+                let groups = match response {
+                    Ok(Response::Array(groups)) => groups,
+                    // the stream does not exist yet
+                    Ok(_) => continue,
+                    Err(_) => {
+                        failed = true;
+                        break;
+                    }
+                };
+
+                for group in &groups {
+                    match group_field(group, b"name") {
+                        Some(Response::BulkString(name)) if ours.contains(&name[..]) => {}
+                        _ => continue,
+                    }
+
+                    if let Some(Response::Integer(n)) = group_field(group, b"pending") {
+                        pending += n;
+                    }
+
+                    if let Some(Response::Integer(n)) = group_field(group, b"lag") {
+                        lag += n;
+                    }
+                }
+            }
+
+            if failed {
+                CONNECT_CURR.decrement();
+                *connection = None;
+            }
+        }
+
+        PUBSUB_PENDING.set(pending);
+        PUBSUB_LAG.set(lag);
+    }
+
+    Ok(())
+}
+
+/// Launch tasks with one conncetion per task as RESP protocol is not mux-enabled.
+pub fn launch_publishers(
+    runtime: &mut Runtime,
+    config: Config,
+    work_receiver: Receiver<WorkItem>,
+    workload_components: &[Component],
+) {
+    debug!("launching redis streams publisher tasks");
+
+    let partitioner = Partitioner::new(workload_components);
+
+    let pubsub_config = config.pubsub().unwrap();
+
+    let connections = pubsub_config.publisher_poolsize() * pubsub_config.publisher_concurrency();
+
+    for _ in 0..connections {
+        for endpoint in config.target().endpoints() {
+            runtime.spawn(publisher_task(
+                endpoint.clone(),
+                config.clone(),
+                work_receiver.clone(),
+                partitioner.clone(),
+            ));
+        }
+    }
+}
+
+async fn publisher_task(
+    endpoint: String,
+    config: Config,
+    work_receiver: Receiver<WorkItem>,
+    mut partitioner: Partitioner,
+) -> Result<()> {
+    let validator = MessageValidator::publisher();
+
+    let connector = Connector::new(&config)?;
+
+    // this unwrap will succeed because we wouldn't be creating these tasks if
+    // there wasn't a pubsub config.
+    let pubsub_config = config.pubsub().unwrap();
+
+    let mut connection = None;
+
+    PUBSUB_PUBLISHER_CURR.add(1);
+
+    while RUNNING.load(Ordering::Relaxed) {
+        if connection.is_none() {
+            PUBSUB_PUBLISHER_CONNECT.increment();
+
+            connection = connect(&connector, &endpoint, pubsub_config).await;
+
+            if connection.is_none() {
+                sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        }
+
+        let mut con = connection.take().unwrap();
+
+        let work_item = work_receiver
+            .recv()
+            .await
+            .map_err(|_| Error::new(ErrorKind::Other, "channel closed"))?;
+
+        // the per-topic metrics, if the topic has its own metrics
+        let topic_metrics = match &work_item {
+            WorkItem::Publish { topic, .. } => TopicMetrics::get(topic),
+        };
+
+        REQUEST.increment();
+
+        let start = Instant::now();
+
+        let result = match work_item {
+            WorkItem::Publish {
+                topic,
+                mut message,
+                key,
+            } => {
+                let count = partitioner.partitions(&topic);
+                let partition = partitioner.partition(&topic, key.as_deref());
+
+                validator.stamp(&mut message, &topic, partition);
+
+                PUBSUB_PUBLISH.increment();
+
+                let mut request = Request::new("XADD").arg(stream_name(&topic, partition, count));
+
+                if let Some(maxlen) = pubsub_config.redis_streams_maxlen() {
+                    request = request.arg("MAXLEN").arg("~").arg(maxlen as i64);
+                }
+
+                let request = request.arg("*").arg(MESSAGE_FIELD).arg(message);
+
+                match timeout(pubsub_config.publish_timeout(), async {
+                    con.send(&request).await?;
+                    con.receive().await
+                })
+                .await
+                {
+                    // the reply is the ID of the new entry
+                    Ok(Ok(Response::BulkString(_))) => Ok(()),
+                    Ok(Ok(_)) => Err(ResponseError::Exception),
+                    Ok(Err(_)) => {
+                        // the connection is no longer usable
+                        CONNECT_CURR.decrement();
+                        PUBSUB_PUBLISH_EX.increment();
+                        continue;
+                    }
+                    Err(_) => {
+                        // a late reply would be read as the reply to the next
+                        // request, so the connection is dropped
+                        CONNECT_CURR.decrement();
+                        PUBSUB_PUBLISH_TIMEOUT.increment();
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let stop = Instant::now();
+
+        connection = Some(con);
+
+        match result {
+            Ok(_) => {
+                let latency = stop.duration_since(start).as_nanos() as u64;
+
+                PUBSUB_PUBLISH_OK.increment();
+                let _ = PUBSUB_PUBLISH_LATENCY.increment(latency);
+                if let Some(metrics) = topic_metrics {
+                    metrics.published(latency);
+                }
+            }
+            Err(_) => {
+                PUBSUB_PUBLISH_EX.increment();
+            }
+        }
+    }
+
+    PUBSUB_PUBLISHER_CURR.sub(1);
+
+    Ok(())
+}
@@ -0,0 +1,587 @@
+use super::*;
+use metriken::{DynBoxedMetric, Gauge, MetricBuilder};
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+// Publisher IDs combine a random instance ID with a counter so that publishers
+// in separate processes have distinct IDs. An ID of zero is never used, so
+// messages without a publisher ID (eg: from a blabber server) are not tracked.
+static INSTANCE_ID: Lazy<u64> = Lazy::new(|| (rand::random::<u32>() as u64) << 32);
+static NEXT_PUBLISHER_ID: AtomicU64 = AtomicU64::new(1);
+
+// all of the publishers, which are reconciled with the subscriptions at
+// shutdown
+static PUBLISHERS: Mutex<Vec<Arc<Publisher>>> = Mutex::new(Vec::new());
+
+// the subscriptions shared by the members of each consumer group
+static GROUPS: Mutex<BTreeMap<String, Arc<Subscription>>> = Mutex::new(BTreeMap::new());
+
+// other subscriptions, which are dropped when they are closed
+static SUBSCRIPTIONS: Mutex<Vec<Weak<Subscription>>> = Mutex::new(Vec::new());
+
+// subscriptions which were dropped at shutdown, these are reconciled the same
+// as open subscriptions
+static CLOSED: Mutex<Vec<Tracking>> = Mutex::new(Vec::new());
+
+// messages lost by subscriptions which were closed before shutdown
+static RETIRED_LOST: AtomicU64 = AtomicU64::new(0);
+
+// the topic for each stream, and its stats if the topic has its own metrics
+static STREAMS: OnceLock<HashMap<u64, Stream>> = OnceLock::new();
+
+/// Returns the ID of a stream, which is a single partition of a topic. The ID
+/// is the same for all instances so that the streams can be identified in
+/// messages from other instances.
+pub fn stream_id(topic: &str, partition: usize) -> u64 {
+    hasher().hash_one((topic, partition as u64))
+}
+
+struct Stream {
+    topic: Arc<String>,
+    stats: Option<Arc<TopicStats>>,
+}
+
+/// Sequence tracking stats for a single topic.
+struct TopicStats {
+    missing: DynBoxedMetric<Gauge>,
+    duplicate: DynBoxedMetric<Gauge>,
+    reordered: DynBoxedMetric<Gauge>,
+    lost: DynBoxedMetric<Gauge>,
+}
+
+impl TopicStats {
+    fn new(topic: &str) -> Self {
+        let gauge = |name: &str, description: &'static str| {
+            MetricBuilder::new(format!("subscriber/topic/{topic}/{name}"))
+                .description(description)
+                .build(Gauge::new())
+        };
+
+        Self {
+            missing: gauge("missing", "messages in sequence gaps for the topic"),
+            duplicate: gauge("duplicate", "duplicate messages received for the topic"),
+            reordered: gauge("reordered", "messages received out of order for the topic"),
+            lost: gauge("lost", "messages lost for the topic, set at shutdown"),
+        }
+    }
+}
+
+/// Registers the streams of each topic so that messages for the workload are
+/// tracked. Sequence tracking is also reported for each topic which has its
+/// own metrics, see `pubsub.topic_metrics`.
+pub fn register_topics(config: &Config, workload_components: &[Component]) {
+    let mut streams = HashMap::new();
+
+    for component in workload_components {
+        if let Component::Topics(t) = component {
+            for topic in t.topics() {
+                let stats = if topic_metrics::is_tracked(config, t, topic) {
+                    Some(Arc::new(TopicStats::new(topic)))
+                } else {
+                    None
+                };
+
+                for partition in 0..t.partitions() {
+                    streams.insert(
+                        stream_id(topic, partition),
+                        Stream {
+                            topic: topic.clone(),
+                            stats: stats.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    let _ = STREAMS.set(streams);
+}
+
+fn stream(stream: u64) -> Option<&'static Stream> {
+    STREAMS.get().and_then(|streams| streams.get(&stream))
+}
+
+fn topic_stats(stream: u64) -> Option<&'static TopicStats> {
+    self::stream(stream).and_then(|s| s.stats.as_deref())
+}
+
+/// The sequence numbers for each stream a publisher has published to.
+pub struct Publisher {
+    id: u64,
+    sequences: Mutex<HashMap<u64, u64>>,
+}
+
+impl Publisher {
+    pub fn new() -> Arc<Self> {
+        let publisher = Arc::new(Self {
+            id: *INSTANCE_ID | NEXT_PUBLISHER_ID.fetch_add(1, Ordering::Relaxed),
+            sequences: Mutex::new(HashMap::new()),
+        });
+
+        PUBLISHERS.lock().unwrap().push(publisher.clone());
+
+        publisher
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the next sequence number for the stream. Sequence numbers start
+    /// at one.
+    pub fn next(&self, stream: u64) -> u64 {
+        let mut sequences = self.sequences.lock().unwrap();
+        let sequence = sequences.entry(stream).or_insert(0);
+        *sequence += 1;
+        *sequence
+    }
+}
+
+/// The sequence numbers received from a single publisher on a single stream.
+struct Received {
+    // the first sequence number received, earlier messages were published
+    // before the subscription started
+    first: u64,
+    highest: u64,
+    // the gaps in the sequence, as inclusive ranges keyed by the start
+    missing: BTreeMap<u64, u64>,
+}
+
+impl Received {
+    /// Removes the sequence number from the gaps, returning true if it was
+    /// missing.
+    fn fill(&mut self, sequence: u64) -> bool {
+        let (start, end) = match self.missing.range(..=sequence).next_back() {
+            Some((&start, &end)) if sequence <= end => (start, end),
+            _ => return false,
+        };
+
+        self.missing.remove(&start);
+
+        if start < sequence {
+            self.missing.insert(start, sequence - 1);
+        }
+
+        if sequence < end {
+            self.missing.insert(sequence + 1, end);
+        }
+
+        true
+    }
+
+    fn missing(&self) -> u64 {
+        self.missing
+            .iter()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+}
+
+/// The sequence numbers received by a subscription, and the topics it is
+/// subscribed to.
+#[derive(Default)]
+struct Tracking {
+    topics: HashSet<Arc<String>>,
+    streams: HashMap<(u64, u64), Received>,
+}
+
+impl Tracking {
+    /// Returns the messages which were lost and the messages of those which
+    /// are still missing from a gap, for each stream. Messages published by
+    /// this instance after the last one received are lost, and so are all of
+    /// the messages for a subscribed topic which were never received.
+    fn loss(&self, published: &HashMap<(u64, u64), u64>) -> Vec<(u64, u64, u64)> {
+        let mut loss = Vec::new();
+
+        for ((publisher, stream), received) in self.streams.iter() {
+            let missing = received.missing();
+            let mut lost = missing;
+
+            if let Some(sequence) = published.get(&(*publisher, *stream)) {
+                lost += sequence.saturating_sub(received.highest);
+            }
+
+            loss.push((*stream, lost, missing));
+        }
+
+        for ((publisher, stream), sequence) in published.iter() {
+            if self.streams.contains_key(&(*publisher, *stream)) {
+                continue;
+            }
+
+            if let Some(s) = self::stream(*stream) {
+                if self.topics.contains(&s.topic) {
+                    loss.push((*stream, *sequence, 0));
+                }
+            }
+        }
+
+        loss
+    }
+}
+
+/// Tracks the sequence numbers received by a subscriber, which may be shared
+/// by multiple tasks when they consume from the same subscription. The members
+/// of a consumer group share one subscription, so that the messages received
+/// by each member are reconciled together, even as the partitions move between
+/// the members.
+#[derive(Default)]
+pub struct Subscription {
+    tracking: Mutex<Tracking>,
+}
+
+impl Subscription {
+    /// Returns the subscription for the topics. When there is a group, this is
+    /// the subscription shared by all the members of the group.
+    pub fn new(group: Option<&str>, topics: &[Arc<String>]) -> Arc<Self> {
+        let subscription = match group {
+            Some(group) => GROUPS
+                .lock()
+                .unwrap()
+                .entry(group.to_string())
+                .or_default()
+                .clone(),
+            None => {
+                let subscription = Arc::new(Self::default());
+
+                let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+                subscriptions.retain(|s| s.strong_count() > 0);
+                subscriptions.push(Arc::downgrade(&subscription));
+
+                subscription
+            }
+        };
+
+        subscription
+            .tracking
+            .lock()
+            .unwrap()
+            .topics
+            .extend(topics.iter().cloned());
+
+        subscription
+    }
+
+    /// Records a received message. A message after a gap in the sequence marks
+    /// the skipped messages as missing until they arrive out of order. Any
+    /// other message at or below the highest sequence number is a duplicate.
+    ///
+    /// Messages for unknown streams are ignored, as they were not stamped by a
+    /// publisher for this workload.
+    pub fn receive(&self, publisher: u64, stream: u64, sequence: u64) {
+        if self::stream(stream).is_none() {
+            return;
+        }
+
+        let topic = topic_stats(stream);
+
+        let mut tracking = self.tracking.lock().unwrap();
+
+        let received = tracking
+            .streams
+            .entry((publisher, stream))
+            .or_insert_with(|| Received {
+                first: sequence,
+                highest: sequence - 1,
+                missing: BTreeMap::new(),
+            });
+
+        if sequence > received.highest {
+            let gap = sequence - received.highest - 1;
+
+            if gap > 0 {
+                received.missing.insert(received.highest + 1, sequence - 1);
+
+                PUBSUB_RECEIVE_MISSING.add(gap as _);
+                if let Some(topic) = topic {
+                    topic.missing.add(gap as _);
+                }
+            }
+
+            received.highest = sequence;
+        } else if sequence < received.first || received.fill(sequence) {
+            if sequence >= received.first {
+                PUBSUB_RECEIVE_MISSING.sub(1);
+                if let Some(topic) = topic {
+                    topic.missing.sub(1);
+                }
+            }
+
+            PUBSUB_RECEIVE_REORDERED.increment();
+            if let Some(topic) = topic {
+                topic.reordered.increment();
+            }
+        } else {
+            PUBSUB_RECEIVE_DUPLICATE.increment();
+            if let Some(topic) = topic {
+                topic.duplicate.increment();
+            }
+        }
+    }
+}
+
+impl Drop for Subscription {
+    /// A subscription which is closed during the run only counts the messages
+    /// still missing from its gaps as lost, as it was not expected to receive
+    /// any later messages. Subscriptions dropped at shutdown are kept to be
+    /// reconciled.
+    fn drop(&mut self) {
+        let tracking = match self.tracking.get_mut() {
+            Ok(tracking) => std::mem::take(tracking),
+            Err(_) => return,
+        };
+
+        if !RUNNING.load(Ordering::Relaxed) {
+            CLOSED.lock().unwrap().push(tracking);
+            return;
+        }
+
+        for ((_, stream), received) in tracking.streams.iter() {
+            let lost = received.missing();
+
+            if let Some(topic) = topic_stats(*stream) {
+                topic.lost.add(lost as _);
+            }
+
+            RETIRED_LOST.fetch_add(lost, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Counts the messages which were lost. See `Tracking::loss` for the messages
+/// which are counted. Messages which were in flight at shutdown are counted as
+/// lost.
+pub fn reconcile() {
+    let mut published = HashMap::new();
+
+    for publisher in PUBLISHERS.lock().unwrap().iter() {
+        for (stream, sequence) in publisher.sequences.lock().unwrap().iter() {
+            published.insert((publisher.id, *stream), *sequence);
+        }
+    }
+
+    let mut total = RETIRED_LOST.load(Ordering::Relaxed);
+    let mut missing = 0;
+
+    let mut count = |tracking: &Tracking| {
+        for (stream, lost, m) in tracking.loss(&published) {
+            if let Some(topic) = topic_stats(stream) {
+                topic.lost.add(lost as _);
+            }
+
+            total += lost;
+            missing += m;
+        }
+    };
+
+    for subscription in GROUPS.lock().unwrap().values() {
+        count(&subscription.tracking.lock().unwrap());
+    }
+
+    // these are held until the closed subscriptions are counted, so that any
+    // which are dropped meanwhile are not counted twice
+    let subscriptions: Vec<_> = SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect();
+
+    for subscription in subscriptions.iter() {
+        count(&subscription.tracking.lock().unwrap());
+    }
+
+    for tracking in CLOSED.lock().unwrap().iter() {
+        count(tracking);
+    }
+
+    PUBSUB_RECEIVE_LOST.add(total);
+
+    output!("-----");
+    output!(
+        "Subscriber Sequence: Lost: {} Missing: {} Unreceived: {} Duplicate: {} Reordered: {}",
+        total,
+        missing,
+        total - missing,
+        PUBSUB_RECEIVE_DUPLICATE.value(),
+        PUBSUB_RECEIVE_REORDERED.value(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLISHER: u64 = 1;
+
+    // registers a topic with two partitions, returning the stream IDs
+    fn streams() -> (Arc<String>, u64, u64) {
+        let topic = Arc::new("sequence".to_string());
+
+        STREAMS.get_or_init(|| {
+            (0..2)
+                .map(|partition| {
+                    (
+                        stream_id(&topic, partition),
+                        Stream {
+                            topic: topic.clone(),
+                            stats: None,
+                        },
+                    )
+                })
+                .collect()
+        });
+
+        (topic, stream_id(&topic, 0), stream_id(&topic, 1))
+    }
+
+    fn missing(subscription: &Subscription, stream: u64) -> u64 {
+        subscription.tracking.lock().unwrap().streams[&(PUBLISHER, stream)].missing()
+    }
+
+    #[test]
+    fn receive_in_order() {
+        let (_, stream, _) = streams();
+        let subscription = Subscription::default();
+
+        for sequence in 1..=10 {
+            subscription.receive(PUBLISHER, stream, sequence);
+        }
+
+        let tracking = subscription.tracking.lock().unwrap();
+        let received = &tracking.streams[&(PUBLISHER, stream)];
+        assert_eq!(received.first, 1);
+        assert_eq!(received.highest, 10);
+        assert_eq!(received.missing(), 0);
+    }
+
+    #[test]
+    fn receive_gaps() {
+        let (_, stream, _) = streams();
+        let subscription = Subscription::default();
+
+        for sequence in [1, 4, 5, 9] {
+            subscription.receive(PUBLISHER, stream, sequence);
+        }
+
+        // 2..=3 and 6..=8 are missing
+        assert_eq!(missing(&subscription, stream), 5);
+
+        // filling the middle of a gap splits it
+        subscription.receive(PUBLISHER, stream, 7);
+        assert_eq!(missing(&subscription, stream), 4);
+
+        // the ends of a gap shrink it
+        subscription.receive(PUBLISHER, stream, 2);
+        subscription.receive(PUBLISHER, stream, 8);
+        assert_eq!(missing(&subscription, stream), 2);
+
+        // duplicates do not change the gaps
+        subscription.receive(PUBLISHER, stream, 7);
+        subscription.receive(PUBLISHER, stream, 9);
+        assert_eq!(missing(&subscription, stream), 2);
+
+        subscription.receive(PUBLISHER, stream, 3);
+        subscription.receive(PUBLISHER, stream, 6);
+        assert_eq!(missing(&subscription, stream), 0);
+    }
+
+    #[test]
+    fn receive_before_first() {
+        let (_, stream, _) = streams();
+        let subscription = Subscription::default();
+
+        // messages published before the subscription started are not missing
+        subscription.receive(PUBLISHER, stream, 5);
+        subscription.receive(PUBLISHER, stream, 3);
+
+        let tracking = subscription.tracking.lock().unwrap();
+        let received = &tracking.streams[&(PUBLISHER, stream)];
+        assert_eq!(received.first, 5);
+        assert_eq!(received.missing(), 0);
+    }
+
+    #[test]
+    fn receive_unknown_stream() {
+        streams();
+        let subscription = Subscription::default();
+
+        subscription.receive(PUBLISHER, stream_id("unknown", 0), 1);
+
+        assert!(subscription.tracking.lock().unwrap().streams.is_empty());
+    }
+
+    #[test]
+    fn reconcile_lost() {
+        let (topic, stream, other) = streams();
+        let subscription = Subscription::default();
+        subscription.tracking.lock().unwrap().topics.insert(topic);
+
+        for sequence in [1, 2, 4, 5] {
+            subscription.receive(PUBLISHER, stream, sequence);
+        }
+
+        let published = HashMap::from([((PUBLISHER, stream), 8), ((PUBLISHER, other), 3)]);
+
+        let mut loss = subscription.tracking.lock().unwrap().loss(&published);
+        loss.sort();
+
+        let mut expected = vec![
+            // one missing from the gap and the three after the last received
+            (stream, 4, 1),
+            // the stream was never received
+            (other, 3, 0),
+        ];
+        expected.sort();
+
+        assert_eq!(loss, expected);
+    }
+
+    #[test]
+    fn reconcile_unsubscribed() {
+        let (_, _, other) = streams();
+        let subscription = Subscription::default();
+
+        // streams for other topics are not lost
+        let published = HashMap::from([((PUBLISHER, other), 3)]);
+
+        assert!(subscription
+            .tracking
+            .lock()
+            .unwrap()
+            .loss(&published)
+            .is_empty());
+    }
+
+    #[test]
+    fn reconcile_group() {
+        let (topic, stream, other) = streams();
+        let topics = [topic];
+
+        // the members of a group share the subscription, so the messages
+        // received by each member are reconciled together
+        let a = Subscription::new(Some("reconcile_group"), &topics);
+        let b = Subscription::new(Some("reconcile_group"), &topics);
+        assert!(Arc::ptr_eq(&a, &b));
+
+        // each member receives one of the streams, and the second member
+        // takes over the first stream part way through
+        for sequence in 1..=5 {
+            a.receive(PUBLISHER, stream, sequence);
+        }
+        for sequence in 6..=10 {
+            b.receive(PUBLISHER, stream, sequence);
+        }
+        for sequence in 1..=10 {
+            b.receive(PUBLISHER, other, sequence);
+        }
+
+        let published = HashMap::from([((PUBLISHER, stream), 10), ((PUBLISHER, other), 10)]);
+
+        let loss = a.tracking.lock().unwrap().loss(&published);
+
+        assert_eq!(loss.iter().map(|(_, lost, _)| lost).sum::<u64>(), 0);
+    }
+}
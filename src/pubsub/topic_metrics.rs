@@ -0,0 +1,136 @@
+use super::*;
+use metriken::{AtomicHistogram, Counter, DynBoxedMetric, MetricBuilder};
+use std::sync::OnceLock;
+
+// the metrics for each topic which has its own metrics, by topic name and by
+// the ID of each of its streams
+static TOPICS: OnceLock<HashMap<String, Arc<TopicMetrics>>> = OnceLock::new();
+static STREAMS: OnceLock<HashMap<u64, Arc<TopicMetrics>>> = OnceLock::new();
+
+/// Publish and receive metrics for a single topic. Each metric has the name of
+/// its family and the topic in its metadata, so that it can be exported with
+/// a topic label.
+pub struct TopicMetrics {
+    publish: DynBoxedMetric<Counter>,
+    publish_latency: DynBoxedMetric<AtomicHistogram>,
+    receive: DynBoxedMetric<Counter>,
+    latency: DynBoxedMetric<AtomicHistogram>,
+}
+
+impl TopicMetrics {
+    fn new(topic: &str) -> Self {
+        let builder = |name: &str, description: &'static str| {
+            MetricBuilder::new(format!("pubsub/topic/{topic}/{name}"))
+                .description(description)
+                .metadata("metric", format!("pubsub/topic/{name}"))
+                .metadata("topic", topic)
+        };
+
+        Self {
+            publish: builder("publish", "messages published to the topic").build(Counter::new()),
+            publish_latency: builder(
+                "publish_latency",
+                "distribution of publish latency for the topic in nanoseconds.",
+            )
+            .build(AtomicHistogram::new(7, 64)),
+            receive: builder("receive", "messages received from the topic").build(Counter::new()),
+            latency: builder(
+                "latency",
+                "distribution of end-to-end latency for the topic in nanoseconds.",
+            )
+            .build(AtomicHistogram::new(7, 64)),
+        }
+    }
+
+    /// Returns the metrics for the topic, if it has its own metrics.
+    pub fn get(topic: &str) -> Option<&'static TopicMetrics> {
+        TOPICS
+            .get()
+            .and_then(|topics| topics.get(topic))
+            .map(|t| &**t)
+    }
+
+    /// Records a successful publish to the topic.
+    pub fn published(&self, latency: u64) {
+        self.publish.increment();
+        let _ = self.publish_latency.increment(latency);
+    }
+}
+
+/// Returns true if the topic is one of the first `pubsub.topic_metrics`
+/// topics in its component, which are the topics with their own metrics.
+pub fn is_tracked(config: &Config, topics: &workload::Topics, topic: &str) -> bool {
+    let limit = match config.pubsub() {
+        Some(pubsub) => pubsub.topic_metrics(),
+        None => return false,
+    };
+
+    topics
+        .topics()
+        .iter()
+        .take(limit)
+        .any(|t| t.as_str() == topic)
+}
+
+/// Registers the metrics for the first `pubsub.topic_metrics` topics of each
+/// topics component. The topics are in rank order, so with a zipf topic
+/// distribution these are the hottest topics.
+pub fn register(config: &Config, workload_components: &[Component]) {
+    let limit = match config.pubsub() {
+        Some(pubsub) => pubsub.topic_metrics(),
+        None => {
+            debug!("No pubsub configuration specified");
+            return;
+        }
+    };
+
+    let mut topics = HashMap::new();
+    let mut streams = HashMap::new();
+
+    for component in workload_components {
+        if let Component::Topics(t) = component {
+            for topic in t.topics().iter().take(limit) {
+                let metrics = Arc::new(TopicMetrics::new(topic));
+
+                for partition in 0..t.partitions() {
+                    streams.insert(sequence::stream_id(topic, partition), metrics.clone());
+                }
+
+                topics.insert(topic.to_string(), metrics);
+            }
+        }
+    }
+
+    let _ = TOPICS.set(topics);
+    let _ = STREAMS.set(streams);
+}
+
+/// Records a message received from a stream, if its topic has its own
+/// metrics.
+pub fn received(stream: u64, latency: u64) {
+    if let Some(metrics) = STREAMS.get().and_then(|streams| streams.get(&stream)) {
+        metrics.receive.increment();
+        let _ = metrics.latency.increment(latency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_without_pubsub() {
+        let config = Config::new(concat!(env!("CARGO_MANIFEST_DIR"), "/configs/redis.toml"));
+        assert!(config.pubsub().is_none());
+
+        let components: Vec<Component> = workload::generators(&config)
+            .iter()
+            .flat_map(|g| g.components().iter().cloned())
+            .collect();
+
+        // this is called for every config, so it must not require pubsub
+        register(&config, &components);
+
+        assert!(TOPICS.get().is_none());
+    }
+}
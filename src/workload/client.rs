@@ -36,6 +36,74 @@ pub struct Delete {
     pub key: Arc<[u8]>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct Increment {
+    pub key: Arc<[u8]>,
+    pub amount: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Decrement {
+    pub key: Arc<[u8]>,
+    pub amount: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Gets {
+    pub key: Arc<[u8]>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cas {
+    pub key: Arc<[u8]>,
+    pub value: Vec<u8>,
+    pub ttl: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Touch {
+    pub key: Arc<[u8]>,
+    pub ttl: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GetAndTouch {
+    pub key: Arc<[u8]>,
+    pub ttl: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Append {
+    pub key: Arc<[u8]>,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Prepend {
+    pub key: Arc<[u8]>,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Transaction {
+    pub requests: Vec<ClientRequest>,
+}
+
+/// A script which is read from a file when the workload is created.
+#[derive(Debug, PartialEq)]
+pub struct Script {
+    /// the hex-encoded SHA1 digest of the script
+    pub sha: Arc<[u8]>,
+    pub body: Arc<[u8]>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Eval {
+    pub script: Arc<Script>,
+    pub keys: Vec<Arc<[u8]>>,
+    pub args: Vec<Vec<u8>>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Replace {
     pub key: Arc<[u8]>,
@@ -207,6 +275,32 @@ pub struct SortedSetScore {
     pub members: Vec<Arc<[u8]>>,
 }
 
+// Streams
+#[derive(Debug, PartialEq)]
+pub struct StreamAdd {
+    pub key: Arc<[u8]>,
+    pub fields: Vec<(Arc<[u8]>, Vec<u8>)>,
+    pub maxlen: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StreamRead {
+    pub key: Arc<[u8]>,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StreamReadGroup {
+    pub key: Arc<[u8]>,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StreamTrim {
+    pub key: Arc<[u8]>,
+    pub maxlen: u32,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, PartialEq)]
 pub enum ClientRequest {
@@ -217,10 +311,28 @@ pub enum ClientRequest {
     Add(Add),
     Get(Get),
     Delete(Delete),
+    Increment(Increment),
+    Decrement(Decrement),
+    Gets(Gets),
+    Cas(Cas),
+    Touch(Touch),
+    GetAndTouch(GetAndTouch),
+    Append(Append),
+    Prepend(Prepend),
     MultiGet(MultiGet),
     Replace(Replace),
     Set(Set),
 
+    // Transactions and Scripting
+    Transaction(Transaction),
+    Eval(Eval),
+
+    // Streams
+    StreamAdd(StreamAdd),
+    StreamRead(StreamRead),
+    StreamReadGroup(StreamReadGroup),
+    StreamTrim(StreamTrim),
+
     // Hash Commands
     HashExists(HashExists),
     HashDelete(HashDelete),
@@ -6,9 +6,10 @@ use rand::distributions::{Alphanumeric, Uniform};
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng, RngCore, SeedableRng};
 use rand_distr::Distribution as RandomDistribution;
-use rand_distr::WeightedAliasIndex;
+use rand_distr::{LogNormal, WeightedAliasIndex};
 use rand_xoshiro::{Seed512, Xoshiro512PlusPlus};
 use ratelimit::Ratelimiter;
+use sha1::{Digest, Sha1};
 use std::collections::{HashMap, HashSet};
 use std::io::{Result, Write};
 use std::sync::atomic::AtomicU64;
@@ -19,13 +20,13 @@ use zipf::ZipfDistribution;
 pub mod client;
 mod publisher;
 
-pub use client::{ClientRequest, ClientWorkItem};
+pub use client::{ClientRequest, ClientWorkItem, Script};
 pub use publisher::PublisherWorkItem;
 
 static SEQUENCE_NUMBER: AtomicU64 = AtomicU64::new(0);
 
 pub fn launch_workload(
-    generator: Generator,
+    generators: Vec<Generator>,
     config: &Config,
     client_sender: Sender<ClientWorkItem>,
     pubsub_sender: Sender<PublisherWorkItem>,
@@ -44,24 +45,26 @@ pub fn launch_workload(
     let mut rng = Xoshiro512PlusPlus::from_seed(config.general().initial_seed());
 
     // spawn the request generators on a blocking threads
-    for _ in 0..config.workload().threads() {
-        let client_sender = client_sender.clone();
-        let pubsub_sender = pubsub_sender.clone();
-        let generator = generator.clone();
-
-        // generate the seed for this workload thread
-        let mut seed = [0; 64];
-        rng.fill_bytes(&mut seed);
-
-        workload_rt.spawn_blocking(move || {
-            // since this seed is unique, each workload thread should produce
-            // requests in a different sequence
-            let mut rng = Xoshiro512PlusPlus::from_seed(Seed512(seed));
-
-            while RUNNING.load(Ordering::Relaxed) {
-                generator.generate(&client_sender, &pubsub_sender, &mut rng);
-            }
-        });
+    for generator in generators {
+        for _ in 0..generator.threads {
+            let client_sender = client_sender.clone();
+            let pubsub_sender = pubsub_sender.clone();
+            let generator = generator.clone();
+
+            // generate the seed for this workload thread
+            let mut seed = [0; 64];
+            rng.fill_bytes(&mut seed);
+
+            workload_rt.spawn_blocking(move || {
+                // since this seed is unique, each workload thread should produce
+                // requests in a different sequence
+                let mut rng = Xoshiro512PlusPlus::from_seed(Seed512(seed));
+
+                while RUNNING.load(Ordering::Relaxed) {
+                    generator.generate(&client_sender, &pubsub_sender, &mut rng);
+                }
+            });
+        }
     }
 
     let c = config.clone();
@@ -70,19 +73,110 @@ pub fn launch_workload(
     workload_rt
 }
 
+/// Returns the generators for the workload. Each component with its own
+/// ratelimit has a generator, and the remaining components share a generator
+/// which uses the workload ratelimit.
+pub fn generators(config: &Config) -> Vec<Generator> {
+    let mut shared = Vec::new();
+    let mut generators = Vec::new();
+    let mut names = HashSet::new();
+
+    let scripts = load_scripts(config);
+
+    let mut add = |name: String,
+                   ratelimit: Option<&config::Ratelimit>,
+                   threads: usize,
+                   component: Component,
+                   weight: usize| {
+        if !names.insert(name.clone()) {
+            eprintln!("workload component name `{name}` is not unique");
+            std::process::exit(2);
+        }
+
+        match ratelimit {
+            Some(ratelimit) => generators.push(Generator::new(
+                Some(name),
+                ratelimit,
+                threads,
+                vec![(component, weight)],
+            )),
+            None => shared.push((component, weight)),
+        }
+    };
+
+    for (i, keyspace) in config.workload().keyspaces().iter().enumerate() {
+        add(
+            keyspace
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("keyspace{i}")),
+            keyspace.ratelimit(),
+            keyspace.threads(),
+            Component::Keyspace(Keyspace::new(config, keyspace, &scripts)),
+            keyspace.weight(),
+        );
+    }
+
+    for (i, topics) in config.workload().topics().iter().enumerate() {
+        add(
+            topics
+                .name()
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("topics{i}")),
+            topics.ratelimit(),
+            topics.threads(),
+            Component::Topics(Topics::new(config, topics)),
+            topics.weight(),
+        );
+    }
+
+    if shared.is_empty() && generators.is_empty() {
+        eprintln!("no workload components were specified in the config");
+        std::process::exit(1);
+    }
+
+    // the workload ratelimit and threads are only used when some component
+    // does not have its own ratelimit
+    if !shared.is_empty() {
+        generators.insert(
+            0,
+            Generator::new(
+                None,
+                config.workload().ratelimit(),
+                config.workload().threads(),
+                shared,
+            ),
+        );
+    }
+
+    generators
+}
+
 #[derive(Clone)]
 pub struct Generator {
+    // the name of the component, when this generator is for a single
+    // component with its own ratelimit
+    name: Option<String>,
+    ratelimit: config::Ratelimit,
     ratelimiter: Option<Arc<Ratelimiter>>,
+    threads: usize,
     components: Vec<Component>,
     component_dist: WeightedAliasIndex<usize>,
 }
 
 impl Generator {
-    pub fn new(config: &Config) -> Self {
-        let ratelimiter = config.workload().ratelimit().start().map(|rate| {
+    fn new(
+        name: Option<String>,
+        ratelimit: &config::Ratelimit,
+        threads: usize,
+        components: Vec<(Component, usize)>,
+    ) -> Self {
+        let ratelimiter = ratelimit.start().map(|rate| {
             let rate = rate.get();
             let amount = (rate as f64 / 1_000_000.0).ceil() as u64;
-            RATELIMIT_CURR.set(rate as i64);
+            if name.is_none() {
+                RATELIMIT_CURR.set(rate as i64);
+            }
 
             // even though we might not have nanosecond level clock resolution,
             // by using a nanosecond level duration, we achieve more accurate
@@ -99,31 +193,31 @@ impl Generator {
             )
         });
 
-        let mut components = Vec::new();
-        let mut component_weights = Vec::new();
+        let (components, component_weights): (Vec<_>, Vec<_>) = components.into_iter().unzip();
 
-        for keyspace in config.workload().keyspaces() {
-            components.push(Component::Keyspace(Keyspace::new(config, keyspace)));
-            component_weights.push(keyspace.weight());
-        }
-
-        for topics in config.workload().topics() {
-            components.push(Component::Topics(Topics::new(config, topics)));
-            component_weights.push(topics.weight());
-        }
-
-        if components.is_empty() {
-            eprintln!("no workload components were specified in the config");
-            std::process::exit(1);
-        }
+        let component_dist = WeightedAliasIndex::new(component_weights).unwrap();
 
         Self {
+            name,
+            ratelimit: ratelimit.clone(),
             ratelimiter,
+            threads,
             components,
-            component_dist: WeightedAliasIndex::new(component_weights).unwrap(),
+            component_dist,
         }
     }
 
+    /// The name of the component, or `None` for the generator which is
+    /// shared by the components without their own ratelimit.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The ratelimit config, which is used to ramp the ratelimit.
+    pub fn ratelimit(&self) -> &config::Ratelimit {
+        &self.ratelimit
+    }
+
     pub fn ratelimiter(&self) -> Option<Arc<Ratelimiter>> {
         self.ratelimiter.clone()
     }
@@ -136,7 +230,9 @@ impl Generator {
     ) {
         if let Some(ref ratelimiter) = self.ratelimiter {
             loop {
-                RATELIMIT_DROPPED.set(ratelimiter.dropped());
+                if self.name.is_none() {
+                    RATELIMIT_DROPPED.set(ratelimiter.dropped());
+                }
 
                 if ratelimiter.try_wait().is_ok() {
                     break;
@@ -170,18 +266,38 @@ impl Generator {
         let topic_index = topics.topic_dist.sample(rng);
         let topic = topics.topics[topic_index].clone();
 
-        let mut m = vec![0_u8; topics.message_len];
+        let mut m = if let Some(corpus) = &topics.corpus {
+            // the body is a sample message from the corpus
+            let body = &corpus[rng.gen_range(0..corpus.len())];
+            let mut m = vec![0_u8; MESSAGE_HEADER_LEN + body.len()];
+            m[MESSAGE_HEADER_LEN..].copy_from_slice(body);
+            m
+        } else {
+            let len = topics.message_len.sample(rng);
+            let mut m = vec![0_u8; len];
+
+            // determine the range to fill with random bytes and fill that range
+            let random_bytes = match topics.message_len {
+                MessageLength::Fixed(_) => topics.message_random_bytes,
+                _ => (len as f64 * topics.message_random_ratio) as usize,
+            };
+            let limit = std::cmp::min(random_bytes + MESSAGE_HEADER_LEN, m.len());
+            rng.fill(&mut m[MESSAGE_HEADER_LEN..limit]);
+            m
+        };
 
         // add a header
         [m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7]] =
             [0x54, 0x45, 0x53, 0x54, 0x49, 0x4E, 0x47, 0x21];
 
-        // determine the range to fill with random bytes and fill that range
-        let limit = std::cmp::min(topics.message_random_bytes + 32, m.len());
-        rng.fill(&mut m[32..limit]);
-
         // generate the key
-        if topics.key_len == 0 {
+        if let Some((keys, key_dist)) = &topics.keys {
+            PublisherWorkItem::Publish {
+                topic,
+                key: Some(keys[key_dist.sample(rng)].to_vec()),
+                message: m,
+            }
+        } else if topics.key_len == 0 {
             PublisherWorkItem::Publish {
                 topic,
                 key: None,
@@ -201,15 +317,39 @@ impl Generator {
     fn generate_request(&self, keyspace: &Keyspace, rng: &mut dyn RngCore) -> ClientWorkItem {
         let command = &keyspace.commands[keyspace.command_dist.sample(rng)];
 
-        let request = match command.verb() {
+        ClientWorkItem::Request {
+            request: self.generate_command(keyspace, command, rng),
+            sequence: SEQUENCE_NUMBER.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    fn generate_command(
+        &self,
+        keyspace: &Keyspace,
+        command: &Command,
+        rng: &mut dyn RngCore,
+    ) -> ClientRequest {
+        match command.verb() {
             Verb::Add => ClientRequest::Add(client::Add {
                 key: keyspace.sample(rng),
                 value: keyspace.gen_value(rng),
                 ttl: keyspace.ttl(),
             }),
-            Verb::Get => ClientRequest::Get(client::Get {
-                key: keyspace.sample(rng),
-            }),
+            Verb::Get => {
+                let cardinality = command.cardinality();
+                if cardinality == 1 {
+                    ClientRequest::Get(client::Get {
+                        key: keyspace.sample(rng),
+                    })
+                } else {
+                    let mut keys = Vec::with_capacity(cardinality);
+                    for _ in 0..cardinality {
+                        keys.push(keyspace.sample(rng));
+                    }
+
+                    ClientRequest::MultiGet(client::MultiGet { keys })
+                }
+            }
             Verb::Set => ClientRequest::Set(client::Set {
                 key: keyspace.sample(rng),
                 value: keyspace.gen_value(rng),
@@ -218,6 +358,38 @@ impl Generator {
             Verb::Delete => ClientRequest::Delete(client::Delete {
                 key: keyspace.sample(rng),
             }),
+            Verb::Increment => ClientRequest::Increment(client::Increment {
+                key: keyspace.sample(rng),
+                amount: 1,
+            }),
+            Verb::Decrement => ClientRequest::Decrement(client::Decrement {
+                key: keyspace.sample(rng),
+                amount: 1,
+            }),
+            Verb::Gets => ClientRequest::Gets(client::Gets {
+                key: keyspace.sample(rng),
+            }),
+            Verb::Cas => ClientRequest::Cas(client::Cas {
+                key: keyspace.sample(rng),
+                value: keyspace.gen_value(rng),
+                ttl: keyspace.ttl(),
+            }),
+            Verb::Touch => ClientRequest::Touch(client::Touch {
+                key: keyspace.sample(rng),
+                ttl: keyspace.ttl(),
+            }),
+            Verb::GetAndTouch => ClientRequest::GetAndTouch(client::GetAndTouch {
+                key: keyspace.sample(rng),
+                ttl: keyspace.ttl(),
+            }),
+            Verb::Append => ClientRequest::Append(client::Append {
+                key: keyspace.sample(rng),
+                value: keyspace.gen_value(rng),
+            }),
+            Verb::Prepend => ClientRequest::Prepend(client::Prepend {
+                key: keyspace.sample(rng),
+                value: keyspace.gen_value(rng),
+            }),
             Verb::Replace => ClientRequest::Replace(client::Replace {
                 key: keyspace.sample(rng),
                 value: keyspace.gen_value(rng),
@@ -392,19 +564,85 @@ impl Generator {
                 key: keyspace.sample(rng),
                 member: keyspace.sample_inner(rng),
             }),
-        };
+            Verb::StreamAdd => {
+                let cardinality = command.cardinality();
+                let mut fields = Vec::with_capacity(cardinality);
+                for _ in 0..cardinality {
+                    fields.push((keyspace.sample_inner(rng), keyspace.gen_value(rng)));
+                }
+                ClientRequest::StreamAdd(client::StreamAdd {
+                    key: keyspace.sample(rng),
+                    fields,
+                    maxlen: command.truncate(),
+                })
+            }
+            Verb::StreamRead => ClientRequest::StreamRead(client::StreamRead {
+                key: keyspace.sample(rng),
+                count: command.cardinality(),
+            }),
+            Verb::StreamReadGroup => ClientRequest::StreamReadGroup(client::StreamReadGroup {
+                key: keyspace.sample(rng),
+                count: command.cardinality(),
+            }),
+            Verb::StreamTrim => ClientRequest::StreamTrim(client::StreamTrim {
+                key: keyspace.sample(rng),
+                maxlen: command.truncate().unwrap(),
+            }),
+            Verb::Transaction => ClientRequest::Transaction(client::Transaction {
+                requests: command
+                    .commands()
+                    .iter()
+                    .map(|command| self.generate_command(keyspace, command, rng))
+                    .collect(),
+            }),
+            Verb::Eval => {
+                let mut keys = Vec::with_capacity(command.cardinality());
+                for _ in 0..command.cardinality() {
+                    keys.push(keyspace.sample(rng));
+                }
 
-        ClientWorkItem::Request {
-            request,
-            sequence: SEQUENCE_NUMBER.fetch_add(1, Ordering::Relaxed),
+                let mut args = Vec::with_capacity(command.values() + command.args().len());
+                for _ in 0..command.values() {
+                    args.push(keyspace.gen_value(rng));
+                }
+                args.extend(command.args().iter().map(|arg| arg.as_bytes().to_vec()));
+
+                ClientRequest::Eval(client::Eval {
+                    script: keyspace.scripts[command.script().unwrap()].clone(),
+                    keys,
+                    args,
+                })
+            }
         }
     }
 
     pub fn components(&self) -> &[Component] {
         &self.components
     }
+
+    /// Generates a request for each of the keyspace commands.
+    #[cfg(test)]
+    pub fn requests(&self) -> Vec<ClientRequest> {
+        let mut rng = Xoshiro512PlusPlus::seed_from_u64(0);
+        let mut requests = Vec::new();
+
+        for component in &self.components {
+            if let Component::Keyspace(keyspace) = component {
+                for command in &keyspace.commands {
+                    requests.push(self.generate_command(keyspace, command, &mut rng));
+                }
+            }
+        }
+
+        requests
+    }
 }
 
+/// The length of the header at the start of each pubsub message. The header
+/// holds the magic bytes, checksum, timestamp, publisher ID, sequence number,
+/// and stream ID.
+pub const MESSAGE_HEADER_LEN: usize = 48;
+
 #[derive(Clone)]
 pub enum Component {
     Keyspace(Keyspace),
@@ -418,8 +656,15 @@ pub struct Topics {
     replications: usize,
     topic_dist: Distribution,
     key_len: usize,
-    message_len: usize,
+    // the keys and their distribution, when messages use a fixed set of keys
+    keys: Option<(Vec<Arc<[u8]>>, Distribution)>,
+    message_len: MessageLength,
     message_random_bytes: usize,
+    // the fraction of each message which is random, used when the message
+    // lengths are not fixed
+    message_random_ratio: f64,
+    // sample message bodies, which are used instead of random messages
+    corpus: Option<Vec<Arc<[u8]>>>,
     subscriber_poolsize: usize,
     subscriber_concurrency: usize,
     kafka_single_subscriber_group: bool,
@@ -435,6 +680,16 @@ impl Topics {
         let replications = std::cmp::max(1, topics.replications());
         let topiclen = topics.topic_len();
         let message_len = topics.message_len();
+        if message_len < MESSAGE_HEADER_LEN {
+            eprintln!("message_len must be at least {MESSAGE_HEADER_LEN} bytes to hold the message header");
+            std::process::exit(2);
+        }
+        let message_random_ratio = message_random_bytes as f64 / message_len as f64;
+        let message_len = match topics.message_len_distribution() {
+            None => MessageLength::Fixed(message_len),
+            Some(distribution) => MessageLength::new(distribution),
+        };
+        let corpus = topics.message_corpus().map(load_corpus);
         let key_len = topics.key_len();
         let subscriber_poolsize = topics.subscriber_poolsize();
         let subscriber_concurrency = topics.subscriber_concurrency();
@@ -476,14 +731,50 @@ impl Topics {
             topic_names = topics.drain().map(|k| k.into()).collect();
         }
 
+        // generate the set of message keys, if there is one
+        let keys = match topics.nkeys() {
+            Some(nkeys) if key_len > 0 => {
+                let nkeys = std::cmp::max(1, nkeys);
+
+                // we use a predictable seed to generate the keys
+                let mut rng = Xoshiro512PlusPlus::from_seed(config.general().initial_seed());
+                // the first seed is used for the topic names, so the keys use
+                // the second
+                let mut raw_seed = [0_u8; 64];
+                rng.fill_bytes(&mut raw_seed);
+                rng.fill_bytes(&mut raw_seed);
+                let mut rng = Xoshiro512PlusPlus::from_seed(Seed512(raw_seed));
+                let mut keys = HashSet::with_capacity(nkeys);
+                while keys.len() < nkeys {
+                    let key = (&mut rng)
+                        .sample_iter(&Alphanumeric)
+                        .take(key_len)
+                        .collect::<Vec<u8>>();
+                    let _ = keys.insert(key);
+                }
+                let keys: Vec<Arc<[u8]>> = keys.drain().map(|k| k.into()).collect();
+                let key_dist = match topics.key_distribution() {
+                    config::Distribution::Uniform => Distribution::Uniform(Uniform::new(0, nkeys)),
+                    config::Distribution::Zipf => {
+                        Distribution::Zipf(ZipfDistribution::new(nkeys, 1.0).unwrap())
+                    }
+                };
+                Some((keys, key_dist))
+            }
+            _ => None,
+        };
+
         Self {
             topics: topic_names,
             partitions,
             replications,
             topic_dist,
             key_len,
+            keys,
             message_len,
             message_random_bytes,
+            message_random_ratio,
+            corpus,
             subscriber_poolsize,
             subscriber_concurrency,
             kafka_single_subscriber_group: topics.kafka_single_subscriber_group(),
@@ -527,6 +818,8 @@ pub struct Keyspace {
     vkind: ValueKind,
     value_random_bytes: usize,
     ttl: Option<Duration>,
+    // the scripts called by the commands, by path
+    scripts: HashMap<String, Arc<Script>>,
 }
 
 #[derive(Clone)]
@@ -544,8 +837,122 @@ impl Distribution {
     }
 }
 
+/// The length of each message, which is either fixed or sampled from a
+/// distribution. Sampled lengths are never shorter than the message header.
+#[derive(Clone)]
+pub enum MessageLength {
+    Fixed(usize),
+    Range(Uniform<usize>),
+    Lognormal(LogNormal<f64>, usize),
+    Buckets(Vec<usize>, WeightedAliasIndex<usize>),
+}
+
+impl MessageLength {
+    fn new(distribution: &config::MessageLength) -> Self {
+        let check = |len: usize, name: &str| {
+            if len < MESSAGE_HEADER_LEN {
+                eprintln!("message length {name} must be at least {MESSAGE_HEADER_LEN} bytes to hold the message header");
+                std::process::exit(2);
+            }
+        };
+
+        match distribution {
+            config::MessageLength::Range { min, max } => {
+                check(*min, "min");
+                if min > max {
+                    eprintln!("message length min must not be greater than max");
+                    std::process::exit(2);
+                }
+                Self::Range(Uniform::new_inclusive(*min, *max))
+            }
+            config::MessageLength::Lognormal { median, sigma, max } => {
+                check(*median, "median");
+                if median > max {
+                    eprintln!("message length median must not be greater than max");
+                    std::process::exit(2);
+                }
+                let dist = LogNormal::new((*median as f64).ln(), *sigma).unwrap_or_else(|e| {
+                    eprintln!("invalid lognormal message length: {e}");
+                    std::process::exit(2);
+                });
+                Self::Lognormal(dist, *max)
+            }
+            config::MessageLength::Buckets { buckets } => {
+                if buckets.is_empty() {
+                    eprintln!("message length buckets must not be empty");
+                    std::process::exit(2);
+                }
+                for (len, _) in buckets {
+                    check(*len, "bucket");
+                }
+                let lengths = buckets.iter().map(|(len, _)| *len).collect();
+                let weights = buckets.iter().map(|(_, weight)| *weight).collect();
+                let dist = WeightedAliasIndex::new(weights).unwrap_or_else(|e| {
+                    eprintln!("invalid message length bucket weights: {e}");
+                    std::process::exit(2);
+                });
+                Self::Buckets(lengths, dist)
+            }
+        }
+    }
+
+    pub fn sample(&self, rng: &mut dyn RngCore) -> usize {
+        match self {
+            Self::Fixed(len) => *len,
+            Self::Range(dist) => dist.sample(rng),
+            Self::Lognormal(dist, max) => {
+                (dist.sample(rng) as usize).clamp(MESSAGE_HEADER_LEN, *max)
+            }
+            Self::Buckets(lengths, dist) => lengths[dist.sample(rng)],
+        }
+    }
+}
+
+/// Loads each file in the directory as a sample message body.
+fn load_corpus(path: &str) -> Vec<Arc<[u8]>> {
+    let entries = std::fs::read_dir(path).unwrap_or_else(|e| {
+        eprintln!("failed to read message corpus directory `{path}`: {e}");
+        std::process::exit(2);
+    });
+
+    let mut corpus: Vec<Arc<[u8]>> = Vec::new();
+
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                eprintln!("failed to read message corpus directory: {e}");
+                std::process::exit(2);
+            }
+        };
+
+        if !path.is_file() {
+            continue;
+        }
+
+        match std::fs::read(&path) {
+            Ok(body) => corpus.push(body.into()),
+            Err(e) => {
+                eprintln!("failed to read message corpus file {path:?}: {e}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if corpus.is_empty() {
+        eprintln!("message corpus directory `{path}` contains no files");
+        std::process::exit(2);
+    }
+
+    corpus
+}
+
 impl Keyspace {
-    pub fn new(config: &Config, keyspace: &config::Keyspace) -> Self {
+    pub fn new(
+        config: &Config,
+        keyspace: &config::Keyspace,
+        scripts: &HashMap<String, Arc<Script>>,
+    ) -> Self {
         let value_random_bytes = estimate_random_bytes_needed(
             keyspace.vlen().unwrap_or(0),
             keyspace.compression_ratio(),
@@ -606,85 +1013,20 @@ impl Keyspace {
         let mut command_weights = Vec::new();
 
         for command in keyspace.commands() {
-            commands.push(*command);
+            commands.push(command.clone());
             command_weights.push(command.weight());
 
-            // validate that the keyspace is adaquately specified for the given
-            // verb
-
-            // commands that set generated values need a `vlen`
-            if keyspace.vlen().is_none()
-                && keyspace.vkind() == ValueKind::Bytes
-                && matches!(command.verb(), Verb::Set | Verb::HashSet)
-            {
-                eprintln!(
-                    "verb: {:?} requires that the keyspace has a `vlen` set when `vkind` is `bytes`",
-                    command.verb()
-                );
-                std::process::exit(2);
-            }
-
-            // cardinality must always be > 0
-            if command.cardinality() == 0 {
-                eprintln!("cardinality must not be zero",);
-                std::process::exit(2);
-            }
-
-            // not all commands support cardinality > 1
-            if command.cardinality() > 1 && !command.verb().supports_cardinality() {
-                eprintln!(
-                    "verb: {:?} requires that `cardinality` is set to `1`",
-                    command.verb()
-                );
-                std::process::exit(2);
-            }
-
-            if command.start().is_some() && !command.verb().supports_start() {
-                eprintln!(
-                    "verb: {:?} does not support the `start` argument",
-                    command.verb()
-                );
-            }
-
-            if command.end().is_some() && !command.verb().supports_end() {
-                eprintln!(
-                    "verb: {:?} does not support the `end` argument",
-                    command.verb()
-                );
-            }
-
-            if command.by_score() && !command.verb().supports_by_score() {
-                eprintln!(
-                    "verb: {:?} does not support the `by_score` option",
-                    command.verb()
-                );
-            }
-
-            if command.truncate().is_some() {
-                // truncate must be >= 1
-                if command.truncate().unwrap() == 0 {
-                    eprintln!("truncate must be >= 1",);
-                    std::process::exit(2);
-                }
-
-                // not all commands support truncate
-                if !command.verb().supports_truncate() {
-                    eprintln!("verb: {:?} does not support truncate", command.verb());
-                    std::process::exit(2);
-                }
-            }
-
-            if command.verb().needs_inner_key()
-                && (keyspace.inner_keys_nkeys().is_none() || keyspace.inner_keys_klen().is_none())
-            {
-                eprintln!(
-                    "verb: {:?} requires that `inner_key_klen` and `inner_key_nkeys` are set",
-                    command.verb()
-                );
-                std::process::exit(2);
-            }
+            validate_command(keyspace, command);
         }
 
+        // only the scripts which are called by this keyspace
+        let mut paths = Vec::new();
+        script_paths(keyspace.commands(), &mut paths);
+        let scripts = paths
+            .into_iter()
+            .map(|path| (path.to_string(), scripts[path].clone()))
+            .collect();
+
         let command_dist = WeightedAliasIndex::new(command_weights).unwrap();
 
         Self {
@@ -698,9 +1040,15 @@ impl Keyspace {
             vkind: keyspace.vkind(),
             value_random_bytes,
             ttl: keyspace.ttl(),
+            scripts,
         }
     }
 
+    /// The scripts which are called by the commands in this keyspace.
+    pub fn scripts(&self) -> impl Iterator<Item = &Arc<Script>> {
+        self.scripts.values()
+    }
+
     pub fn sample(&self, rng: &mut dyn RngCore) -> Arc<[u8]> {
         let index = self.key_dist.sample(rng);
         self.keys[index].clone()
@@ -727,6 +1075,186 @@ impl Keyspace {
     }
 }
 
+// Validates that the keyspace is adequately specified for the given command.
+// The commands within a transaction are validated in the same way.
+fn validate_command(keyspace: &config::Keyspace, command: &Command) {
+    // commands that set generated values need a `vlen`
+    if keyspace.vlen().is_none()
+        && keyspace.vkind() == ValueKind::Bytes
+        && matches!(command.verb(), Verb::Set | Verb::HashSet | Verb::StreamAdd)
+    {
+        eprintln!(
+            "verb: {:?} requires that the keyspace has a `vlen` set when `vkind` is `bytes`",
+            command.verb()
+        );
+        std::process::exit(2);
+    }
+
+    // cardinality must always be > 0
+    if command.cardinality() == 0 {
+        eprintln!("cardinality must not be zero",);
+        std::process::exit(2);
+    }
+
+    // not all commands support cardinality > 1
+    if command.cardinality() > 1 && !command.verb().supports_cardinality() {
+        eprintln!(
+            "verb: {:?} requires that `cardinality` is set to `1`",
+            command.verb()
+        );
+        std::process::exit(2);
+    }
+
+    if command.start().is_some() && !command.verb().supports_start() {
+        eprintln!(
+            "verb: {:?} does not support the `start` argument",
+            command.verb()
+        );
+    }
+
+    if command.end().is_some() && !command.verb().supports_end() {
+        eprintln!(
+            "verb: {:?} does not support the `end` argument",
+            command.verb()
+        );
+    }
+
+    if command.by_score() && !command.verb().supports_by_score() {
+        eprintln!(
+            "verb: {:?} does not support the `by_score` option",
+            command.verb()
+        );
+    }
+
+    if command.truncate().is_some() {
+        // truncate must be >= 1
+        if command.truncate().unwrap() == 0 {
+            eprintln!("truncate must be >= 1",);
+            std::process::exit(2);
+        }
+
+        // not all commands support truncate
+        if !command.verb().supports_truncate() {
+            eprintln!("verb: {:?} does not support truncate", command.verb());
+            std::process::exit(2);
+        }
+    }
+
+    if command.verb() == Verb::StreamTrim && command.truncate().is_none() {
+        eprintln!("verb: {:?} requires that `truncate` is set", command.verb());
+        std::process::exit(2);
+    }
+
+    if command.verb().needs_inner_key()
+        && (keyspace.inner_keys_nkeys().is_none() || keyspace.inner_keys_klen().is_none())
+    {
+        eprintln!(
+            "verb: {:?} requires that `inner_key_klen` and `inner_key_nkeys` are set",
+            command.verb()
+        );
+        std::process::exit(2);
+    }
+
+    if command.verb() == Verb::Transaction {
+        if command.commands().is_empty() {
+            eprintln!("verb: {:?} requires at least one command", command.verb());
+            std::process::exit(2);
+        }
+
+        for inner in command.commands() {
+            // transactions can not be nested, and commands which send a
+            // further request based on their reply can not be queued, as the
+            // reply is not known until the transaction is executed
+            if matches!(
+                inner.verb(),
+                Verb::Transaction | Verb::Eval | Verb::StreamReadGroup
+            ) {
+                eprintln!(
+                    "verb: {:?} can not be used within a transaction",
+                    inner.verb()
+                );
+                std::process::exit(2);
+            }
+
+            validate_command(keyspace, inner);
+        }
+    } else if !command.commands().is_empty() {
+        eprintln!(
+            "verb: {:?} does not support the `commands` option",
+            command.verb()
+        );
+        std::process::exit(2);
+    }
+
+    if command.verb() == Verb::Eval {
+        if command.script().is_none() {
+            eprintln!("verb: {:?} requires that `script` is set", command.verb());
+            std::process::exit(2);
+        }
+
+        if command.values() > 0 && keyspace.vlen().is_none() && keyspace.vkind() == ValueKind::Bytes
+        {
+            eprintln!(
+                "verb: {:?} requires that the keyspace has a `vlen` set when `vkind` is `bytes`",
+                command.verb()
+            );
+            std::process::exit(2);
+        }
+    } else if command.script().is_some() || command.values() > 0 || !command.args().is_empty() {
+        eprintln!(
+            "verb: {:?} does not support the `script`, `values`, or `args` options",
+            command.verb()
+        );
+        std::process::exit(2);
+    }
+}
+
+// Returns the paths of the scripts called by the commands, including those
+// within transactions.
+fn script_paths<'a>(commands: &'a [Command], paths: &mut Vec<&'a str>) {
+    for command in commands {
+        if let Some(path) = command.script() {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        script_paths(command.commands(), paths);
+    }
+}
+
+// Reads each script used by the workload once and calculates the digest which
+// is used to call it with `EVALSHA`.
+fn load_scripts(config: &Config) -> HashMap<String, Arc<Script>> {
+    let mut paths = Vec::new();
+
+    for keyspace in config.workload().keyspaces() {
+        script_paths(keyspace.commands(), &mut paths);
+    }
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let body = std::fs::read(path).unwrap_or_else(|e| {
+                eprintln!("failed to read script: {path}: {e}");
+                std::process::exit(2);
+            });
+
+            let sha: String = Sha1::digest(&body)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+
+            let script = Script {
+                sha: sha.into_bytes().into(),
+                body: body.into(),
+            };
+
+            (path.to_string(), Arc::new(script))
+        })
+        .collect()
+}
+
 pub async fn reconnect(work_sender: Sender<ClientWorkItem>, config: Config) -> Result<()> {
     if config.client().is_none() {
         return Ok(());
@@ -781,9 +1309,7 @@ pub struct Ratelimit {
 }
 
 impl Ratelimit {
-    pub fn new(config: &Config) -> Option<Self> {
-        let ratelimit_config = config.workload().ratelimit();
-
+    pub fn new(ratelimit_config: &config::Ratelimit) -> Option<Self> {
         if !ratelimit_config.is_dynamic() {
             return None;
         }